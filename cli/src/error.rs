@@ -14,6 +14,14 @@ pub enum SubwasmError {
 	#[error("SourceParseError {0}")]
 	SourceParseError(String),
 
+	/// Error parsing a hash
+	#[error("Invalid hash {0}, expected 32 bytes as hex")]
+	HashParseError(String),
+
+	/// Error parsing the input of a runtime API call
+	#[error("Invalid call data {0}, expected hex")]
+	CallDataParseError(String),
+
 	/// Generic error
 	#[error("Generic lib error: {0}")]
 	Generic(SubwasmLibError),
@@ -14,7 +14,7 @@ use std::{env, io::Write, str::FromStr};
 use subwasmlib::*;
 use text_style::{AnsiColor, StyledStr};
 use utils::*;
-use wasm_loader::{NodeEndpoint, Source as WasmLoaderSource};
+use wasm_loader::{CompressionOptions, OnchainBlock, RuntimeCache, Source as WasmLoaderSource};
 
 /// Main entry point of the `subwasm` cli
 fn main() -> color_eyre::Result<()> {
@@ -23,6 +23,10 @@ fn main() -> color_eyre::Result<()> {
 
 	let opts: Opts = Opts::parse();
 	// debug!("opts: {opts:#?}");
+	let cache = get_cache(opts.no_cache);
+	let retry = get_retry_policy(opts.retries, opts.backoff, opts.timeout);
+	let headers = get_headers(opts.headers, opts.config)?;
+	let tls = get_tls(opts.ca_bundles, opts.client_cert, opts.client_key, opts.insecure);
 
 	match opts.subcmd {
 		Some(SubCommand::Get(get_opts)) => {
@@ -34,25 +38,69 @@ fn main() -> color_eyre::Result<()> {
 			let download_url = select_url(gh_url, get_opts.url);
 			debug!("download_url: {download_url:?}");
 
-			// Get the RPC url if the user passed a chain name, alias, or url
-			let rpc_url = if let Some(o) = get_opts.rpc_url {
-				Some(o.as_url()?)
+			// The runtime of a parachain, as stored by its relay chain
+			if let Some((relay, para_id)) = get_opts.relay.zip(get_opts.para) {
+				let relay = OnchainBlock { headers, tls, ..relay.onchain_block(get_opts.block, retry)? };
+				let (file, code) = fetch_validation_code(&relay, para_id, get_opts.output)?;
+
+				if opts.json {
+					let s = serde_json::to_string_pretty(&json!({
+						"block_hash": code.block_hash,
+						"endpoint": code.endpoint,
+						"validation_code_hash": code.code_hash,
+						"output": file
+					}))?;
+					println!("{s}");
+				} else {
+					println!("🧱 Block hash: {}", code.block_hash);
+					println!("🌐 Endpoint: {}", code.endpoint);
+					println!("🗳️ Validation code hash: {}", code.code_hash);
+					println!("💾 Runtime saved to {}", file.display());
+				}
+				return Ok(());
+			}
+
+			// Get the node(s) if the user passed a chain name, alias, or url
+			let reference = if let Some(o) = get_opts.rpc_url {
+				Some(OnchainBlock { block_ref: get_opts.block, retry, headers, tls, ..o })
 			} else if let Some(c) = get_opts.chain {
-				Some(c.get_random_url(None)?)
+				Some(OnchainBlock { headers, tls, ..c.onchain_block(get_opts.block, retry)? })
 			} else {
 				None
 			};
-			debug!("rpc_url: {rpc_url:?}");
-
-			match (download_url, rpc_url) {
-				(None, Some(rpc_url)) => {
-					let _file =
-						download_runtime(NodeEndpoint::from_str(rpc_url.as_str())?, get_opts.block, get_opts.output)?;
+			debug!("reference: {reference:?}");
+
+			match (download_url, reference) {
+				(None, Some(reference)) => {
+					// Resolving the block first ensures we report the block the runtime comes from
+					let reference = reference.resolve()?;
+					let block_hash =
+						reference.block_ref.as_ref().and_then(|b| b.as_hash()).cloned().unwrap_or_default();
+					let (file, endpoint) = fetch_runtime_with_cache(&reference, get_opts.output, cache.as_ref())?;
+
+					if opts.json {
+						let s = serde_json::to_string_pretty(
+							&json!({ "block_hash": block_hash, "endpoint": endpoint, "output": file }),
+						)?;
+						println!("{s}");
+					} else {
+						println!("🧱 Block hash: {block_hash}");
+						println!("🌐 Endpoint: {endpoint}");
+						println!("💾 Runtime saved to {}", file.display());
+					}
 					Ok(())
 				}
 				(Some(url), _) => {
 					let target = get_output_file_local(get_opts.output);
-					let output = fetch_at_url(url, Some(target))?;
+					let download = get_download_options(get_opts.expect_blake2, get_opts.expect_sha256);
+					let output = match cache.as_ref() {
+						Some(cache) => {
+							let cached = fetch_at_url_with_cache(url, cache, &download)?;
+							std::fs::copy(cached, &target)?;
+							target
+						}
+						None => fetch_at_url_with(url, Some(target), &download)?,
+					};
 					debug!("Fetched at {output:?}");
 					if output.exists() {
 						info!("Got runtime at {output:?}");
@@ -71,34 +119,72 @@ fn main() -> color_eyre::Result<()> {
 			let gh_url =
 				if let Some(u) = info_opts.github { Some(GithubRef::from_str(u.as_str())?.as_url()) } else { None };
 			let download_url = select_url(gh_url, info_opts.url);
-			let source = get_source(info_opts.file, info_opts.chain, info_opts.block, download_url)?;
-
-			info!("⏱️  Loading WASM from {:?}", &source);
-			let subwasm = Subwasm::new(&source.try_into()?)?;
-
-			Ok(subwasm.runtime_info().print(opts.json)?)
+			let source = get_source(
+				info_opts.file,
+				info_opts.chain,
+				info_opts.relay.zip(info_opts.para),
+				info_opts.block,
+				download_url,
+				cache.as_ref(),
+				&get_download_options(info_opts.expect_blake2, info_opts.expect_sha256),
+			)?
+			.with_retry_policy(retry)?
+			.with_headers(&headers)
+			.with_tls(&tls);
+
+			info!("⏱️  Loading WASM from {source}");
+			let subwasm = Subwasm::new_with_cache(&source.try_into()?, cache.as_ref())?;
+
+			Ok(subwasm.runtime_info()?.print(opts.json)?)
 		}
 
 		Some(SubCommand::Version(info_opts)) => {
 			let gh_url =
 				if let Some(u) = info_opts.github { Some(GithubRef::from_str(u.as_str())?.as_url()) } else { None };
 			let download_url = select_url(gh_url, info_opts.url);
-			let source = get_source(info_opts.file, info_opts.chain, info_opts.block, download_url)?;
-
-			info!("⏱️  Loading WASM from {:?}", &source);
-			let subwasm: Subwasm = Subwasm::new(&source.try_into()?)?;
-
-			Ok(subwasm.runtime_info().print_version(opts.json)?)
+			let source = get_source(
+				info_opts.file,
+				info_opts.chain,
+				info_opts.relay.zip(info_opts.para),
+				info_opts.block,
+				download_url,
+				cache.as_ref(),
+				&get_download_options(info_opts.expect_blake2, info_opts.expect_sha256),
+			)?
+			.with_retry_policy(retry)?
+			.with_headers(&headers)
+			.with_tls(&tls);
+
+			info!("⏱️  Loading WASM from {source}");
+			let subwasm: Subwasm = Subwasm::new_with_cache(&source.try_into()?, cache.as_ref())?;
+
+			Ok(subwasm.runtime_info()?.print_version(opts.json)?)
 		}
 
 		Some(SubCommand::Metadata(meta_opts)) => {
 			let gh_url =
 				if let Some(u) = meta_opts.github { Some(GithubRef::from_str(u.as_str())?.as_url()) } else { None };
 			let download_url = select_url(gh_url, meta_opts.url);
-			let source = get_source(meta_opts.file, meta_opts.chain, meta_opts.block, download_url)?;
-
-			info!("⏱️  Loading WASM from {:?}", &source);
-			let subwasm: Subwasm = Subwasm::new(&source.try_into()?)?;
+			let source = get_source(
+				meta_opts.file,
+				meta_opts.chain,
+				meta_opts.relay.zip(meta_opts.para),
+				meta_opts.block,
+				download_url,
+				cache.as_ref(),
+				&DownloadOptions::default(),
+			)?
+			.with_retry_policy(retry)?
+			.with_headers(&headers)
+			.with_tls(&tls);
+
+			let subwasm: Subwasm = if meta_opts.metadata_only {
+				info!("⏱️  Loading the metadata from {source}");
+				Subwasm::new_metadata_only(&source.try_into()?)?
+			} else {
+				info!("⏱️  Loading WASM from {source}");
+				Subwasm::new_with_cache(&source.try_into()?, cache.as_ref())?
+			};
 
 			let mut fmt: OutputFormat = meta_opts.format.unwrap_or_else(|| "human".into()).into();
 			if opts.json {
@@ -134,13 +220,82 @@ fn main() -> color_eyre::Result<()> {
 			Ok(())
 		}
 
+		Some(SubCommand::Call(call_opts)) => {
+			let storage = call_opts.storage.as_deref().map(TestbedStorage::load).transpose()?;
+			let runtime = call_opts
+				.runtime
+				.with_retry_policy(retry)?
+				.with_headers(&headers)
+				.with_tls(&tls)
+				.as_file_with(cache.as_ref(), &DownloadOptions::default())?;
+
+			info!("⏱️  Loading WASM from {}", runtime.display());
+			let subwasm = Subwasm::new(&WasmLoaderSource::File(runtime))?;
+
+			Ok(subwasm.call_api(&call_opts.method, &call_opts.data.0, storage.as_ref())?.print(opts.json)?)
+		}
+
+		Some(SubCommand::Genesis(genesis_opts)) => {
+			let runtime = genesis_opts
+				.runtime
+				.with_retry_policy(retry)?
+				.with_headers(&headers)
+				.with_tls(&tls)
+				.as_file_with(cache.as_ref(), &DownloadOptions::default())?;
+
+			info!("⏱️  Loading WASM from {}", runtime.display());
+			let subwasm = Subwasm::new(&WasmLoaderSource::File(runtime))?;
+
+			if genesis_opts.list {
+				return Ok(subwasm.genesis_presets()?.print(opts.json)?);
+			}
+
+			let preset = genesis_opts.preset.as_deref();
+			let genesis =
+				if genesis_opts.chain_spec { subwasm.chain_spec(preset)? } else { subwasm.genesis_config(preset)? };
+			println!("{}", serde_json::to_string_pretty(&genesis)?);
+			Ok(())
+		}
+
+		Some(SubCommand::TryUpgrade(try_opts)) => {
+			let storage = TestbedStorage::load(&try_opts.snapshot)?;
+			let runtime = try_opts
+				.runtime
+				.with_retry_policy(retry)?
+				.with_headers(&headers)
+				.with_tls(&tls)
+				.as_file_with(cache.as_ref(), &DownloadOptions::default())?;
+
+			info!("⏱️  Loading WASM from {}", runtime.display());
+			let subwasm = Subwasm::new(&WasmLoaderSource::File(runtime))?;
+
+			let report = subwasm.try_upgrade(&storage, try_opts.checks)?;
+			report.print(opts.json)?;
+
+			if !report.succeeded() {
+				std::process::exit(1)
+			}
+			Ok(())
+		}
+
 		Some(SubCommand::Diff(diff_opts)) => {
 			// debug!("{:#?}", &diff_opts);
 
-			let runtime_1 = diff_opts.runtime_1.as_file()?;
+			let download = get_download_options(diff_opts.expect_blake2, diff_opts.expect_sha256);
+			let runtime_1 = diff_opts
+				.runtime_1
+				.with_retry_policy(retry)?
+				.with_headers(&headers)
+				.with_tls(&tls)
+				.as_file_with(cache.as_ref(), &download)?;
 			debug!("Runtime 1: {}", runtime_1.display());
 
-			let runtime_2 = diff_opts.runtime_2.as_file()?;
+			let runtime_2 = diff_opts
+				.runtime_2
+				.with_retry_policy(retry)?
+				.with_headers(&headers)
+				.with_tls(&tls)
+				.as_file_with(cache.as_ref(), &download)?;
 			debug!("Runtime 2: {}", runtime_2.display());
 
 			let src_a = WasmLoaderSource::File(runtime_1);
@@ -169,10 +324,63 @@ fn main() -> color_eyre::Result<()> {
 			}
 		}
 
-		Some(SubCommand::Compress(copts)) => Ok(compress(copts.input, copts.output)?),
+		Some(SubCommand::Compress(copts)) => {
+			let options =
+				CompressionOptions { level: copts.level, bomb_limit: copts.bomb_limit, verify: !copts.no_verify };
+			let report = compress_with(copts.input, copts.output, &options, copts.max_code_size)?;
+			Ok(report.print(opts.json)?)
+		}
 
 		Some(SubCommand::Decompress(dopts)) => Ok(decompress(dopts.input, dopts.output)?),
 
+		Some(SubCommand::UpgradeStatus(us_opts)) => {
+			let reference = match (us_opts.rpc_url, us_opts.chain) {
+				(Some(o), _) => OnchainBlock { block_ref: us_opts.block, retry, headers, tls, ..o },
+				(None, Some(c)) => OnchainBlock { headers, tls, ..c.onchain_block(us_opts.block, retry)? },
+				_ => unreachable!(),
+			};
+
+			info!("⏱️  Checking the upgrade status with {:?}", &reference);
+			let report = UpgradeStatusReport::new(&reference, us_opts.para, us_opts.file)?;
+			report.print(opts.json)?;
+
+			match report.runtime_matches() {
+				Some(false) => std::process::exit(1),
+				_ => Ok(()),
+			}
+		}
+
+		Some(SubCommand::History(history_opts)) => {
+			let reference = match (history_opts.rpc_url, history_opts.chain) {
+				(Some(o), _) => OnchainBlock { retry, headers, tls, ..o },
+				(None, Some(c)) => OnchainBlock { headers, tls, ..c.onchain_block(None, retry)? },
+				_ => unreachable!(),
+			};
+
+			info!("⏱️  Scanning the runtime upgrades with {:?}", &reference);
+			let mut report = HistoryReport::new(&reference, history_opts.from, history_opts.to)?;
+			if let Some(dir) = history_opts.download {
+				report.download(&reference, &dir, cache.as_ref())?;
+			}
+			Ok(report.print(opts.json)?)
+		}
+
+		Some(SubCommand::Cache(cache_opts)) => {
+			let cache = RuntimeCache::default();
+			match cache_opts.cmd {
+				CacheCommand::List => Ok(CacheReport::new(&cache)?.print(opts.json)?),
+				CacheCommand::Clear => {
+					let removed = cache.clear()?;
+					if opts.json {
+						println!("{}", serde_json::to_string_pretty(&json!({ "removed": removed }))?);
+					} else {
+						println!("🧹 Removed {removed} runtime(s) from {}", cache.root().display());
+					}
+					Ok(())
+				}
+			}
+		}
+
 		None => {
 			if opts.version {
 				let name = crate_name!();
@@ -205,12 +413,30 @@ fn main() -> color_eyre::Result<()> {
 			let gh_url =
 				if let Some(u) = show_opts.github { Some(GithubRef::from_str(u.as_str())?.as_url()) } else { None };
 			let download_url = select_url(gh_url, show_opts.url);
-			let source = get_source(show_opts.file, show_opts.chain, show_opts.block, download_url)?;
-
-			info!("⏱️  Loading WASM from {:?}", &source);
-			let subwasm: Subwasm = Subwasm::new(&source.try_into()?)?;
+			let source = get_source(
+				show_opts.file,
+				show_opts.chain,
+				show_opts.relay.zip(show_opts.para),
+				show_opts.block,
+				download_url,
+				cache.as_ref(),
+				&DownloadOptions::default(),
+			)?
+			.with_retry_policy(retry)?
+			.with_headers(&headers)
+			.with_tls(&tls);
+
+			let subwasm: Subwasm = if show_opts.metadata_only {
+				info!("⏱️  Loading the metadata from {source}");
+				Subwasm::new_metadata_only(&source.try_into()?)?
+			} else {
+				info!("⏱️  Loading WASM from {source}");
+				Subwasm::new_with_cache(&source.try_into()?, cache.as_ref())?
+			};
 
-			if show_opts.summary {
+			if show_opts.apis {
+				Ok(subwasm.runtime_apis()?.print(opts.json)?)
+			} else if show_opts.summary {
 				Ok(subwasm.display_reduced_summary(opts.json)?)
 			} else {
 				if let Some(pallet) = show_opts.pallet {
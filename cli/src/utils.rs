@@ -1,9 +1,9 @@
 //! Utils for the main cli
 use log::debug;
-use std::path::PathBuf;
-use subwasmlib::{fetch_at_url, source::Source, ChainInfo};
+use std::{path::PathBuf, time::Duration};
+use subwasmlib::{source::Source, ChainInfo, DownloadOptions, SubwasmLibError};
 use url::Url;
-use wasm_loader::BlockRef;
+use wasm_loader::{BlockRef, RetryPolicy, RpcHeader, RpcHeaders, RuntimeCache, TlsConfig};
 
 use crate::error;
 
@@ -16,23 +16,78 @@ pub fn select_url(gh_url: Option<Url>, dl_url: Option<Url>) -> Option<Url> {
 	}
 }
 
+/// Returns the runtime cache unless the user opted out
+pub fn get_cache(no_cache: bool) -> Option<RuntimeCache> {
+	if no_cache {
+		None
+	} else {
+		Some(RuntimeCache::default())
+	}
+}
+
+/// Build the retry policy from the user's options
+pub fn get_retry_policy(retries: u32, backoff_ms: u64, timeout_secs: u64) -> RetryPolicy {
+	RetryPolicy { retries, backoff: Duration::from_millis(backoff_ms), timeout: Duration::from_secs(timeout_secs) }
+}
+
+/// Build the TLS settings used to connect to the nodes from the user's options
+pub fn get_tls(
+	ca_bundles: Vec<PathBuf>,
+	client_cert: Option<PathBuf>,
+	client_key: Option<PathBuf>,
+	insecure: bool,
+) -> TlsConfig {
+	TlsConfig { ca_bundles, client_cert, client_key, insecure }
+}
+
+/// Build what we expect from the runtimes downloaded from a URL from the user's options
+pub fn get_download_options(blake2_256: Vec<String>, sha256: Vec<String>) -> DownloadOptions {
+	DownloadOptions { blake2_256, sha256, ..Default::default() }
+}
+
+/// Build the headers sent to the nodes: the ones of the config file, if any, then the ones passed by the user.
+/// A config file passed explicitly must exist, the default one is optional.
+pub fn get_headers(headers: Vec<RpcHeader>, config: Option<PathBuf>) -> error::Result<RpcHeaders> {
+	let mut res = match config.or_else(|| RpcHeaders::default_config_file().filter(|f| f.exists())) {
+		Some(file) => {
+			debug!("Loading headers from {}", file.display());
+			RpcHeaders::from_config_file(&file).map_err(SubwasmLibError::from)?
+		}
+		None => RpcHeaders::default(),
+	};
+	res.extend(headers);
+	Ok(res)
+}
+
 /// Retrieve one unique source from all the options the user may pass
 pub fn get_source(
 	file: Option<PathBuf>,
 	chain: Option<ChainInfo>,
+	para: Option<(ChainInfo, u32)>,
 	block: Option<BlockRef>,
 	dl_url: Option<Url>,
+	cache: Option<&RuntimeCache>,
+	download: &DownloadOptions,
 ) -> error::Result<Source> {
+	// The runtime of a parachain, read from its relay chain
+	if let Some((relay, para_id)) = para {
+		return Ok(Source::from_relay(relay, para_id, block)?);
+	}
+
 	let source: Source = Source::from_options(file, chain, block, dl_url)?;
-	// If the source is a URL, we try to fetch it first
+	// If the source is a URL, we try to fetch it first, chain specs get extracted
 
 	Ok(match source {
 		Source::URL(u) => {
 			debug!("Fetching runtime from {}", u);
-			let runtime_file = fetch_at_url(u, None)?;
+			let runtime_file = Source::URL(u).as_file_with(cache, download)?;
 			debug!("Runtime fetched at {:?}", runtime_file.display());
 			Source::File(runtime_file)
 		}
+		Source::ChainSpec(spec) => {
+			debug!("Extracting runtime from chain spec {}", spec.display());
+			Source::File(Source::ChainSpec(spec).as_file()?)
+		}
 		s => s,
 	})
 }
@@ -0,0 +1,36 @@
+mod test_utils;
+
+#[cfg(test)]
+mod cli_tests {
+	#[cfg(test)]
+	mod cache {
+		use assert_cmd::Command;
+
+		fn cmd(dir: &str) -> Command {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let root = std::env::temp_dir().join(format!("subwasm-cli-cache-{dir}-{}", std::process::id()));
+			cmd.env("SUBWASM_CACHE_DIR", root);
+			cmd
+		}
+
+		#[test]
+		fn it_lists_an_empty_cache() {
+			let assert = cmd("list").args(["cache", "list", "--json"]).assert().success().code(0);
+			let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+			assert!(stdout.contains(r#""entries": []"#));
+		}
+
+		#[test]
+		fn it_clears_the_cache() {
+			let assert = cmd("clear").args(["cache", "clear"]).assert().success().code(0);
+			let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+			assert!(stdout.contains("Removed 0 runtime(s)"));
+		}
+
+		#[test]
+		fn it_requires_a_cache_command() {
+			let assert = cmd("none").args(["cache"]).assert();
+			assert.failure().code(2);
+		}
+	}
+}
@@ -0,0 +1,30 @@
+mod test_utils;
+
+#[cfg(test)]
+mod cli_tests {
+	#[cfg(test)]
+	mod call {
+		use assert_cmd::Command;
+
+		/// Neither a runtime nor a storage, the arguments and the storage are checked first
+		const INVALID_STORAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+
+		#[test]
+		fn it_rejects_invalid_call_data() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+
+			let assert = cmd.args(["call", INVALID_STORAGE, "Core_version", "--data", "0x123"]).assert();
+			let output = assert.failure().code(2).get_output().stderr.clone();
+			assert!(String::from_utf8_lossy(&output).contains("Invalid call data"));
+		}
+
+		#[test]
+		fn it_fails_with_an_invalid_storage() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+
+			let assert = cmd.args(["call", INVALID_STORAGE, "Core_version", "--storage", INVALID_STORAGE]).assert();
+			let output = assert.failure().code(1).get_output().stderr.clone();
+			assert!(String::from_utf8_lossy(&output).contains("Invalid storage"));
+		}
+	}
+}
@@ -35,5 +35,24 @@ mod cli_tests {
 				.success()
 				.code(0);
 		}
+
+		#[test]
+		fn it_compresses_with_options() {
+			let input = temp_file();
+			let output = temp_file();
+			std::fs::write(&input, "runtime ".repeat(1024)).expect("Failed writing input");
+
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["compress", "--json", "--level", "19", &input, &output]).assert().success();
+			let report: serde_json::Value =
+				serde_json::from_slice(&assert.get_output().stdout).expect("Failed parsing report");
+			assert_eq!(report["options"]["level"], 19);
+			assert_eq!(report["options"]["verify"], true);
+			assert_eq!(report["within_limit"], true);
+			assert_eq!(report["size_uncompressed"], 8 * 1024);
+
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			cmd.args(["compress", "--bomb-limit", "1024", &input, &output]).assert().failure().code(1);
+		}
 	}
 }
@@ -14,5 +14,13 @@ mod cli_tests {
 			let assert = cmd.args(["meta", test_wasm]).assert();
 			assert.success().code(0);
 		}
+
+		#[test]
+		fn it_reads_stdin_only_once() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["diff", "-", "-"]).write_stdin("not a runtime").assert();
+			let output = assert.failure().code(2).get_output().stderr.clone();
+			assert!(String::from_utf8_lossy(&output).contains("stdin only once"));
+		}
 	}
 }
@@ -0,0 +1,18 @@
+mod test_utils;
+
+#[cfg(test)]
+mod cli_tests {
+	#[cfg(test)]
+	mod genesis {
+		use assert_cmd::Command;
+
+		#[test]
+		fn it_lists_the_presets_alone() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+
+			let assert = cmd.args(["genesis", "polkadot", "--list", "--preset", "development"]).assert();
+			let output = assert.failure().code(2).get_output().stderr.clone();
+			assert!(String::from_utf8_lossy(&output).contains("cannot be used with"));
+		}
+	}
+}
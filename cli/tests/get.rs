@@ -16,6 +16,69 @@ mod cli_tests {
 			assert!(Path::new(TARGET).exists());
 		}
 
+		#[test]
+		fn it_fails_on_a_missing_socket() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+
+			let assert = cmd.args(["get", "--retries", "0", "ipc:///nonexistent/node.sock"]).assert();
+			assert.failure().code(1);
+		}
+
+		#[test]
+		fn it_checks_the_downloaded_runtime() {
+			const WASM: &[u8] = &[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+			let target = crate::test_utils::temp_file();
+
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let url = crate::test_utils::serve_once(WASM);
+			let assert = cmd
+				.args(["get", "--no-cache", "--url", &url, "--expect-sha256", &"0".repeat(64), "--output", &target])
+				.assert();
+			assert.failure().code(1);
+			assert!(!Path::new(&target).exists());
+
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let url = crate::test_utils::serve_once(b"<html>Not found</html>");
+			let assert = cmd.args(["get", "--no-cache", "--url", &url, "--output", &target]).assert();
+			assert.failure().code(1);
+			assert!(!Path::new(&target).exists());
+
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let url = crate::test_utils::serve_once(WASM);
+			let assert = cmd.args(["get", "--no-cache", "--url", &url, "--output", &target]).assert();
+			assert.success().code(0);
+			assert_eq!(std::fs::read(&target).expect("Failed reading runtime"), WASM);
+			std::fs::remove_file(target).expect("Failed removing runtime");
+		}
+
+		#[test]
+		fn it_gets_a_runtime_from_the_cache() {
+			const WASM: &[u8] = &[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+			let cache = std::env::temp_dir().join(format!("subwasm-cli-cache-get-{}", std::process::id()));
+			let url = crate::test_utils::serve_once(WASM);
+
+			// The server is gone after the first download, the second one must come from the cache
+			for _ in 0..2 {
+				let target = crate::test_utils::temp_file();
+				let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+				let assert =
+					cmd.env("SUBWASM_CACHE_DIR", &cache).args(["get", "--url", &url, "--output", &target]).assert();
+				assert.success().code(0);
+				assert_eq!(std::fs::read(&target).expect("Failed reading runtime"), WASM);
+				std::fs::remove_file(target).expect("Failed removing runtime");
+			}
+			std::fs::remove_dir_all(cache).expect("Failed removing cache");
+		}
+
+		#[test]
+		fn it_rejects_invalid_hashes() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+
+			let assert =
+				cmd.args(["get", "--url", "https://foo.bar/runtime.wasm", "--expect-blake2", "0x1234"]).assert();
+			assert.failure().code(2);
+		}
+
 		#[test]
 		fn it_fails_on_bad_chain() {
 			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
@@ -0,0 +1,33 @@
+mod test_utils;
+
+#[cfg(test)]
+mod cli_tests {
+	#[cfg(test)]
+	mod history {
+		use assert_cmd::Command;
+
+		#[test]
+		fn it_fails_without_chain() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["history"]).assert();
+			assert.failure().code(2);
+		}
+
+		#[test]
+		fn it_fails_on_an_empty_range() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert =
+				cmd.args(["history", "--rpc-url", "ws://localhost:9944", "--from", "20", "--to", "10"]).assert();
+			assert.failure().code(1);
+		}
+
+		#[test]
+		#[ignore = "need archive node"]
+		fn it_lists_the_runtime_upgrades() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert =
+				cmd.args(["history", "--chain", "polkadot", "--from", "20000000", "--to", "20500000"]).assert();
+			assert.success().code(0);
+		}
+	}
+}
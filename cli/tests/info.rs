@@ -13,6 +13,36 @@ mod cli_tests {
 			assert.failure().code(2);
 		}
 
+		#[test]
+		fn it_fails_with_para_but_no_relay() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["info", "--para", "1000"]).assert();
+			assert.failure().code(2);
+		}
+
+		#[test]
+		#[ignore = "need network"]
+		fn it_returns_infos_of_a_parachain() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["info", "--relay", "polkadot", "--para", "1000"]).assert();
+			assert.success().code(0);
+		}
+
+		#[test]
+		fn it_fails_with_a_client_key_but_no_client_cert() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["info", "--chain", "local", "--client-key", "client.key"]).assert();
+			assert.failure().code(2);
+		}
+
+		#[test]
+		fn it_fails_on_a_missing_ca_bundle() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert =
+				cmd.args(["info", "--chain", "local", "--ca-bundle", "/tmp/subwasm/does/not/exist.pem"]).assert();
+			assert.failure().code(1);
+		}
+
 		#[test]
 		fn it_returns_infos() {
 			let test_wasm: &str = &crate::test_utils::ensure_local_wasm();
@@ -21,5 +51,43 @@ mod cli_tests {
 			let assert = cmd.args(["info", test_wasm]).assert();
 			assert.success().code(0);
 		}
+
+		#[test]
+		fn it_returns_infos_from_stdin() {
+			let test_wasm: &str = &crate::test_utils::ensure_local_wasm();
+			let wasm = std::fs::read(test_wasm).expect("Failed reading wasm");
+
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["info", "-"]).write_stdin(wasm).assert();
+			assert.success().code(0);
+		}
+
+		#[test]
+		fn it_fails_on_invalid_stdin() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["info", "-"]).write_stdin("not a runtime").assert();
+			assert.failure().code(1);
+		}
+
+		#[test]
+		fn it_fails_on_missing_file() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["info", "/tmp/subwasm/does/not/exist.wasm"]).assert();
+			assert.failure().code(1);
+		}
+
+		#[test]
+		fn it_fails_on_invalid_header() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["info", "--header", "X-Api-Key", "polkadot"]).assert();
+			assert.failure().code(2);
+		}
+
+		#[test]
+		fn it_fails_on_missing_config() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["info", "--config", "/tmp/subwasm/does/not/exist.json", "polkadot"]).assert();
+			assert.failure().code(1);
+		}
 	}
 }
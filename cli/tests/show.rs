@@ -13,5 +13,19 @@ mod cli_tests {
 			let assert = cmd.args(["show", test_wasm]).assert();
 			assert.success().code(0);
 		}
+
+		#[test]
+		fn it_needs_a_node_for_metadata_only() {
+			let mut cmd = AssertCommand::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["show", "--metadata-only", "-"]).write_stdin("not a runtime").assert();
+			assert.failure().code(1);
+		}
+
+		#[test]
+		fn it_shows_the_apis_alone() {
+			let mut cmd = AssertCommand::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["show", "--apis", "--summary", "runtime.wasm"]).assert();
+			assert.failure().code(2);
+		}
 	}
 }
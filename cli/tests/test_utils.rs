@@ -35,7 +35,27 @@ pub fn ensure_local_wasm() -> String {
 #[allow(dead_code)]
 #[cfg(test)]
 pub fn temp_file() -> String {
-	let mut res = PathBuf::from(temp_dir());
+	let mut res = temp_dir();
 	res.push(PathBuf::from(uuid::Uuid::new_v4().to_string()));
 	String::from(res.to_str().expect("Failed generating temp file path"))
 }
+
+/// Serve `body` over HTTP to a single client and return the url to get it
+#[allow(dead_code)]
+#[cfg(test)]
+pub fn serve_once(body: &'static [u8]) -> String {
+	use std::io::{Read, Write};
+
+	let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed binding");
+	let port = listener.local_addr().expect("Failed getting address").port();
+
+	std::thread::spawn(move || {
+		let (mut stream, _) = listener.accept().expect("Failed accepting connection");
+		let _ = stream.read(&mut [0; 4096]);
+		let header = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+		stream.write_all(header.as_bytes()).expect("Failed sending header");
+		stream.write_all(body).expect("Failed sending body");
+	});
+
+	format!("http://127.0.0.1:{port}/runtime.wasm")
+}
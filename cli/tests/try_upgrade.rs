@@ -0,0 +1,27 @@
+mod test_utils;
+
+#[cfg(test)]
+mod cli_tests {
+	#[cfg(test)]
+	mod try_upgrade {
+		use assert_cmd::Command;
+
+		#[test]
+		fn it_rejects_unknown_checks() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+
+			let assert = cmd.args(["try-upgrade", "polkadot", "--snapshot", "state.snap", "--checks", "some"]).assert();
+			let output = assert.failure().code(2).get_output().stderr.clone();
+			assert!(String::from_utf8_lossy(&output).contains("known checks"));
+		}
+
+		#[test]
+		fn it_fails_without_a_snapshot() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+
+			let assert = cmd.args(["try-upgrade", "polkadot", "--snapshot", "missing.snap"]).assert();
+			let output = assert.failure().code(1).get_output().stderr.clone();
+			assert!(String::from_utf8_lossy(&output).contains("Invalid storage"));
+		}
+	}
+}
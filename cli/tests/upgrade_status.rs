@@ -0,0 +1,24 @@
+mod test_utils;
+
+#[cfg(test)]
+mod cli_tests {
+	#[cfg(test)]
+	mod upgrade_status {
+		use assert_cmd::Command;
+
+		#[test]
+		fn it_fails_without_chain() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["upgrade-status"]).assert();
+			assert.failure().code(2);
+		}
+
+		#[test]
+		#[ignore = "need network"]
+		fn it_shows_the_upgrade_status() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).expect("Failed getting test bin");
+			let assert = cmd.args(["upgrade-status", "--chain", "polkadot", "--para", "1000"]).assert();
+			assert.success().code(0);
+		}
+	}
+}
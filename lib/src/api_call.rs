@@ -0,0 +1,64 @@
+use crate::error::*;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// The outcome of calling a runtime API
+#[derive(Debug, Serialize)]
+pub struct ApiCallResult {
+	/// The runtime API method that was called, such as `Core_version`
+	pub method: String,
+
+	/// The SCALE encoded input, as hex
+	pub data: String,
+
+	/// The SCALE encoded output, as hex
+	pub output: String,
+
+	/// The output decoded using the V15 metadata, if the runtime describes this API
+	pub decoded: Option<serde_json::Value>,
+}
+
+impl ApiCallResult {
+	/// Print the result either using the Display impl
+	/// or serde as json.
+	pub fn print(&self, json: bool) -> Result<()> {
+		if json {
+			let serialized = serde_json::to_string_pretty(self)?;
+			println!("{serialized}");
+		} else {
+			println!("{self}");
+		}
+		Ok(())
+	}
+}
+
+impl Display for ApiCallResult {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.decoded {
+			Some(decoded) => {
+				let decoded = serde_json::to_string_pretty(decoded).map_err(|_| std::fmt::Error)?;
+				write!(fmt, "{decoded}")
+			}
+			None => write!(fmt, "{}", self.output),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_api_call {
+	use super::*;
+
+	#[test]
+	fn it_shows_the_decoded_output_when_available() {
+		let mut result = ApiCallResult {
+			method: "Core_version".to_string(),
+			data: "0x".to_string(),
+			output: "0x2a000000".to_string(),
+			decoded: None,
+		};
+		assert_eq!(result.to_string(), "0x2a000000");
+
+		result.decoded = Some(serde_json::json!({ "spec_version": 42 }));
+		assert_eq!(result.to_string(), "{\n  \"spec_version\": 42\n}");
+	}
+}
@@ -0,0 +1,55 @@
+use crate::error::*;
+use num_format::{Locale, ToFormattedString};
+use serde::Serialize;
+use std::{fmt::Display, path::PathBuf};
+use wasm_loader::{CacheEntry, RuntimeCache};
+
+/// What the runtime cache currently contains
+#[derive(Debug, Serialize)]
+pub struct CacheReport {
+	/// Location of the cache
+	pub root: PathBuf,
+
+	/// The cached runtimes
+	pub entries: Vec<CacheEntry>,
+}
+
+impl CacheReport {
+	pub fn new(cache: &RuntimeCache) -> Result<Self> {
+		Ok(Self { root: cache.root().to_path_buf(), entries: cache.list()? })
+	}
+
+	/// Print the report either using the Display impl
+	/// or serde as json.
+	pub fn print(&self, json: bool) -> Result<()> {
+		if json {
+			let serialized = serde_json::to_string_pretty(self)?;
+			println!("{serialized}");
+		} else {
+			print!("{self}");
+		}
+		Ok(())
+	}
+}
+
+impl Display for CacheReport {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let width_emoji = 1;
+		let width_title = 16;
+		let size: usize = self.entries.iter().map(|e| e.size).sum();
+
+		writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "📦", "Cache:", self.root.display())?;
+		writeln!(
+			fmt,
+			"{:<width_emoji$} {:<width_title$} {} ({} bytes)",
+			"🗂️ ",
+			"Entries:",
+			self.entries.len(),
+			size.to_formatted_string(&Locale::en)
+		)?;
+		for entry in &self.entries {
+			writeln!(fmt, "  {} {:>12} {}", entry.blake2_256, entry.size.to_formatted_string(&Locale::en), entry.key)?;
+		}
+		Ok(())
+	}
+}
@@ -4,7 +4,7 @@ use rand::seq::SliceRandom;
 use std::str::FromStr;
 use thiserror::Error;
 use url::Url;
-use wasm_loader::NodeEndpoint;
+use wasm_loader::{BlockRef, NodeEndpoint, OnchainBlock, RetryPolicy};
 
 /// An enumeration of the type of endpoints
 #[derive(Debug, PartialEq)]
@@ -14,6 +14,9 @@ pub enum EndpointType {
 
 	/// WebSocket endpoint, with or without SSL
 	WebSocket,
+
+	/// Unix socket of a local node
+	Ipc,
 }
 
 impl PartialEq<NodeEndpoint> for EndpointType {
@@ -21,6 +24,7 @@ impl PartialEq<NodeEndpoint> for EndpointType {
 		match other {
 			NodeEndpoint::Http(_) => self == &EndpointType::Http,
 			NodeEndpoint::WebSocket(_) => self == &EndpointType::WebSocket,
+			NodeEndpoint::Ipc(_) => self == &EndpointType::Ipc,
 		}
 	}
 }
@@ -60,6 +64,21 @@ impl ChainInfo {
 	}
 }
 
+impl ChainInfo {
+	/// Returns an [OnchainBlock] using all the known nodes of the chain. They are shuffled so
+	/// the load is spread amongst them: the first one is used and the others are kept as fallbacks.
+	pub fn onchain_block(&self, block_ref: Option<BlockRef>, retry: RetryPolicy) -> Result<OnchainBlock> {
+		let mut endpoints = self.endpoints.clone();
+		endpoints.shuffle(&mut rand::thread_rng());
+
+		let mut endpoints = endpoints.into_iter();
+		let endpoint =
+			endpoints.next().ok_or_else(|| SubwasmLibError::NotFound(format!("No node found for {}", self.name)))?;
+
+		Ok(OnchainBlock { fallbacks: endpoints.collect(), retry, ..OnchainBlock::from_endpoint(endpoint, block_ref) })
+	}
+}
+
 /// Custom error
 #[derive(Debug, Error)]
 pub enum ChainInfoError {
@@ -118,6 +137,14 @@ mod tests {
 		let _endpoint = info.get_random_url(None).expect("Failed getting url");
 	}
 
+	#[test]
+	fn it_uses_all_endpoints() {
+		let info = ChainInfo::from_str("polkadot").expect("Failed getting ChainInfo from string");
+		let block = info.onchain_block(None, RetryPolicy::default()).expect("Failed getting OnchainBlock");
+		assert_eq!(block.endpoints().count(), info.endpoints.len());
+		assert!(block.endpoints().all(|e| info.endpoints.contains(e)));
+	}
+
 	#[test]
 	fn it_returns_a_http_url() {
 		let info = ChainInfo::from_str("local").expect("Failed getting ChainInfo from string");
@@ -137,4 +164,30 @@ mod tests {
 		let ci = ChainInfo::from_str("polkadot").expect("Failed getting ChainInfo from string");
 		assert!(!ci.endpoints.is_empty());
 	}
+
+	#[test]
+	fn it_reports_the_fallback_endpoint() {
+		let node = wasm_loader::rpc::mock::mock_node(vec![r#"{"jsonrpc":"2.0","id":{id},"result":"0x0061736d01"}"#]);
+		// An endpoint nobody listens on
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed binding");
+		let dead = NodeEndpoint::WebSocket(format!("ws://{}", listener.local_addr().expect("Failed getting address")));
+		drop(listener);
+
+		let info = ChainInfo { name: "test".into(), endpoints: vec![dead.clone(), node.clone()] };
+		let block_ref = BlockRef::Hash("0x4d6a0bca208b85d41833a7f35cf73d1ae6974f4bad8ab576e2c3f751d691fe6c".into());
+		let retry = RetryPolicy { retries: 1, backoff: std::time::Duration::from_millis(1), ..Default::default() };
+		let block = info.onchain_block(Some(block_ref), retry).expect("Failed getting OnchainBlock");
+		// The endpoints are shuffled, make sure the dead one is tried first
+		let reference = OnchainBlock { endpoint: dead, fallbacks: vec![node.clone()], ..block };
+
+		let target = std::env::temp_dir().join(format!("subwasm-fallback-{}.wasm", std::process::id()));
+		let reference = reference.resolve().expect("Failed resolving the block");
+		let (file, endpoint) = crate::fetch_runtime(&reference, Some(target)).expect("Failed fetching runtime");
+		assert_eq!(std::fs::read(&file).expect("Failed reading runtime"), vec![0x00, 0x61, 0x73, 0x6d, 0x01]);
+		std::fs::remove_file(file).expect("Failed removing runtime");
+
+		assert_eq!(endpoint, node);
+		// As reported by `get --json`
+		assert_eq!(serde_json::json!({ "endpoint": endpoint })["endpoint"], node.to_string());
+	}
 }
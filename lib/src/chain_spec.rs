@@ -0,0 +1,129 @@
+use crate::error::*;
+use serde_json::{json, Value};
+use std::{
+	fs::File,
+	io::{Read, Write},
+	path::{Path, PathBuf},
+};
+use wasm_loader::{WasmBytes, CODE};
+
+/// Helpers to extract the runtime embedded in a chain spec.
+///
+/// Both layouts are supported:
+/// - raw chain specs store the code under `genesis.raw.top[":code"]`
+/// - plain chain specs store it under `genesis.runtimeGenesis.code` or `genesis.runtime.code`
+pub struct ChainSpec;
+
+impl ChainSpec {
+	/// Check whether a file looks like a (json) chain spec rather than a wasm blob.
+	pub fn is_chain_spec(path: &Path) -> bool {
+		let mut buffer = [0u8; 64];
+		let read = File::open(path).and_then(|mut f| f.read(&mut buffer)).unwrap_or(0);
+		buffer[..read].iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{')
+	}
+
+	/// Extract the runtime from the chain spec at `path`
+	pub fn extract_code(path: &Path) -> Result<WasmBytes> {
+		let file = File::open(path)?;
+		let spec: Value = serde_json::from_reader(std::io::BufReader::new(file))
+			.map_err(|e| SubwasmLibError::ChainSpec(format!("{} is not valid json: {e}", path.display())))?;
+		let genesis = &spec["genesis"];
+		let code_key = format!("0x{}", hex::encode(CODE));
+
+		let code = [
+			&genesis["raw"]["top"][&code_key],
+			&genesis["runtimeGenesis"]["code"],
+			&genesis["runtime"]["code"],
+			&genesis["runtime"]["system"]["code"],
+		]
+		.into_iter()
+		.find_map(|v| v.as_str())
+		.ok_or_else(|| SubwasmLibError::ChainSpec(format!("No runtime found in {}", path.display())))?;
+
+		hex::decode(code.trim_start_matches("0x"))
+			.map_err(|_e| SubwasmLibError::ChainSpec(format!("The runtime in {} is not valid hex", path.display())))
+	}
+
+	/// Build the skeleton of a plain chain spec for the runtime `code`, as a node would. A named preset
+	/// is a patch applied on top of the default genesis config, the default one is the whole config.
+	pub fn skeleton(code: &[u8], preset: Option<&str>, genesis: Value) -> Value {
+		let mut runtime_genesis = json!({ "code": format!("0x{}", hex::encode(code)) });
+		runtime_genesis[if preset.is_some() { "patch" } else { "config" }] = genesis;
+
+		json!({
+			"name": "Custom",
+			"id": "custom",
+			"chainType": "Live",
+			"bootNodes": [],
+			"telemetryEndpoints": null,
+			"protocolId": null,
+			"properties": null,
+			"codeSubstitutes": {},
+			"genesis": { "runtimeGenesis": runtime_genesis },
+		})
+	}
+
+	/// Extract the runtime from the chain spec at `path` and store it into `target`
+	pub fn extract_code_to(path: &Path, target: PathBuf) -> Result<PathBuf> {
+		let code = Self::extract_code(path)?;
+		log::info!("Extracted a runtime of {} bytes from {}", code.len(), path.display());
+		let mut buffer = File::create(&target)?;
+		buffer.write_all(&code)?;
+		Ok(target)
+	}
+}
+
+#[cfg(test)]
+mod tests_chain_spec {
+	use super::*;
+	use crate::get_output_file_tmp;
+
+	fn write_spec(spec: Value) -> PathBuf {
+		let path = get_output_file_tmp().expect("Failed getting a tmp file").with_extension("json");
+		std::fs::write(&path, serde_json::to_string_pretty(&spec).expect("Failed serializing spec"))
+			.expect("Failed writing spec");
+		path
+	}
+
+	#[test]
+	fn it_extracts_code_from_raw_specs() {
+		let path =
+			write_spec(json!({ "name": "Local", "genesis": { "raw": { "top": { "0x3a636f6465": "0x0061736d" }}}}));
+		assert!(ChainSpec::is_chain_spec(&path));
+		assert_eq!(ChainSpec::extract_code(&path).expect("Failed extracting code"), vec![0x00, 0x61, 0x73, 0x6d]);
+	}
+
+	#[test]
+	fn it_extracts_code_from_plain_specs() {
+		let path = write_spec(json!({ "genesis": { "runtimeGenesis": { "code": "0x0061736d", "patch": {} }}}));
+		assert_eq!(ChainSpec::extract_code(&path).expect("Failed extracting code"), vec![0x00, 0x61, 0x73, 0x6d]);
+
+		let path = write_spec(json!({ "genesis": { "runtime": { "code": "0x0061736d" }}}));
+		assert_eq!(ChainSpec::extract_code(&path).expect("Failed extracting code"), vec![0x00, 0x61, 0x73, 0x6d]);
+	}
+
+	#[test]
+	fn it_builds_skeletons() {
+		let spec = ChainSpec::skeleton(&[0x00, 0x61, 0x73, 0x6d], Some("development"), json!({ "balances": {} }));
+		assert_eq!(spec["genesis"]["runtimeGenesis"]["patch"], json!({ "balances": {} }));
+
+		let path = write_spec(spec);
+		assert_eq!(ChainSpec::extract_code(&path).expect("Failed extracting code"), vec![0x00, 0x61, 0x73, 0x6d]);
+
+		let spec = ChainSpec::skeleton(&[], None, json!({}));
+		assert!(spec["genesis"]["runtimeGenesis"]["config"].is_object());
+	}
+
+	#[test]
+	fn it_fails_without_code() {
+		let path = write_spec(json!({ "genesis": { "raw": { "top": {} }}}));
+		assert!(ChainSpec::extract_code(&path).is_err());
+	}
+
+	#[test]
+	fn it_does_not_confuse_wasm_and_specs() {
+		let path = get_output_file_tmp().expect("Failed getting a tmp file");
+		std::fs::write(&path, [0x00, 0x61, 0x73, 0x6d]).expect("Failed writing wasm");
+		assert!(!ChainSpec::is_chain_spec(&path));
+	}
+}
@@ -0,0 +1,81 @@
+use crate::error::*;
+use num_format::{Locale, ToFormattedString};
+use serde::Serialize;
+use std::{fmt::Display, path::PathBuf};
+use wasm_loader::CompressionOptions;
+
+/// The outcome of compressing a runtime
+#[derive(Debug, Serialize)]
+pub struct CompressionReport {
+	/// Where the compressed runtime was saved
+	pub output: PathBuf,
+
+	/// The options the runtime was compressed with
+	pub options: CompressionOptions,
+
+	/// Size of the runtime before compression, in bytes
+	pub size_uncompressed: usize,
+
+	/// Size of the compressed runtime, in bytes
+	pub size_compressed: usize,
+
+	/// The maximum code size the compressed runtime is checked against
+	pub max_code_size: usize,
+}
+
+impl CompressionReport {
+	/// Whether the compressed runtime fits within [CompressionReport::max_code_size]
+	pub fn within_limit(&self) -> bool {
+		self.size_compressed <= self.max_code_size
+	}
+
+	/// Print the report either using the Display impl
+	/// or serde as json.
+	pub fn print(&self, json: bool) -> Result<()> {
+		if json {
+			let mut value = serde_json::to_value(self)?;
+			value["within_limit"] = self.within_limit().into();
+			let serialized = serde_json::to_string_pretty(&value)?;
+			println!("{serialized}");
+		} else {
+			print!("{self}");
+		}
+		Ok(())
+	}
+}
+
+impl Display for CompressionReport {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let width_emoji = 1;
+		let width_title = 16;
+		let size = |s: usize| s.to_formatted_string(&Locale::en);
+
+		writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "💾", "Saved to:", self.output.display())?;
+		writeln!(
+			fmt,
+			"{:<width_emoji$} {:<width_title$} level {}{}",
+			"🗜 ",
+			"Compression:",
+			self.options.level,
+			if self.options.verify { ", verified" } else { "" }
+		)?;
+		writeln!(
+			fmt,
+			"{:<width_emoji$} {:<width_title$} {} -> {} bytes ({:.2}%)",
+			"🏋️ ",
+			"Size:",
+			size(self.size_uncompressed),
+			size(self.size_compressed),
+			self.size_compressed as f32 / self.size_uncompressed as f32 * 100.0
+		)?;
+		writeln!(
+			fmt,
+			"{:<width_emoji$} {:<width_title$} {:.2}% of {} bytes{}",
+			if self.within_limit() { "✅" } else { "⚠️ " },
+			"Code size limit:",
+			self.size_compressed as f32 / self.max_code_size as f32 * 100.0,
+			size(self.max_code_size),
+			if self.within_limit() { "" } else { ", the runtime is too large" }
+		)
+	}
+}
@@ -0,0 +1,112 @@
+use crate::error::*;
+use sp_crypto_hashing::{blake2_256, sha2_256};
+use url::Url;
+use wasm_loader::{Compression, CODE_BLOB_BOMB_LIMIT};
+
+/// Downloads larger than this are aborted, no runtime can be larger once decompressed anyway
+pub const DEFAULT_MAX_DOWNLOAD_SIZE: u64 = CODE_BLOB_BOMB_LIMIT as u64;
+
+/// What we expect from a runtime downloaded from a URL.
+///
+/// Whatever the options, what we download must look like a runtime, compressed or not.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadOptions {
+	/// Downloads larger than this, in bytes, are aborted
+	pub max_size: u64,
+
+	/// If not empty, the blake2-256 hash of the runtime must be one of those
+	pub blake2_256: Vec<String>,
+
+	/// If not empty, the sha256 hash of the runtime must be one of those
+	pub sha256: Vec<String>,
+}
+
+impl Default for DownloadOptions {
+	fn default() -> Self {
+		Self { max_size: DEFAULT_MAX_DOWNLOAD_SIZE, blake2_256: Vec::new(), sha256: Vec::new() }
+	}
+}
+
+impl DownloadOptions {
+	/// Check the bytes we got from `url`: their size, that they look like a runtime and their hashes
+	pub fn check(&self, url: &Url, bytes: &[u8]) -> Result<()> {
+		if bytes.len() as u64 > self.max_size {
+			return Err(SubwasmLibError::DownloadTooLarge(url.to_owned(), self.max_size));
+		}
+
+		if !Compression::is_runtime(bytes) {
+			return Err(SubwasmLibError::NoRuntimeAtUrl(url.to_owned()));
+		}
+
+		Self::check_hash(url, "blake2-256", &self.blake2_256, blake2_256(bytes))?;
+		Self::check_hash(url, "sha256", &self.sha256, sha2_256(bytes))
+	}
+
+	fn check_hash(url: &Url, algorithm: &str, expected: &[String], hash: [u8; 32]) -> Result<()> {
+		let actual = format!("0x{}", hex::encode(hash));
+		if expected.is_empty() || expected.iter().any(|e| Self::normalize(e) == actual) {
+			return Ok(());
+		}
+
+		let expected = expected.iter().map(|e| Self::normalize(e)).collect::<Vec<_>>().join(" or ");
+		Err(SubwasmLibError::ChecksumMismatch(url.to_string(), algorithm.to_string(), actual, expected))
+	}
+
+	/// Hashes are compared as lowercase hex, prefixed with `0x` as we show them
+	fn normalize(hash: &str) -> String {
+		format!("0x{}", hash.trim_start_matches("0x").to_lowercase())
+	}
+}
+
+#[cfg(test)]
+mod tests_download {
+	use super::*;
+
+	const WASM: &[u8] = &[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+	const OTHER_HASH: &str = "0x1ae8e1a7b98b5e3ee89b8d8b5e9ff9e9ba9c5ddc56bf7bce3ea1b1a0a7e5dcb0";
+
+	fn url() -> Url {
+		Url::parse("https://foo.bar/runtime.wasm").expect("Failed parsing url")
+	}
+
+	#[test]
+	fn it_accepts_runtimes() {
+		assert!(DownloadOptions::default().check(&url(), WASM).is_ok());
+
+		let compressed = Compression::compress(WASM).expect("Failed compressing");
+		assert!(DownloadOptions::default().check(&url(), &compressed).is_ok());
+	}
+
+	#[test]
+	fn it_rejects_what_is_not_a_runtime() {
+		let res = DownloadOptions::default().check(&url(), b"<html>Not found</html>");
+		assert!(matches!(res, Err(SubwasmLibError::NoRuntimeAtUrl(_))));
+	}
+
+	#[test]
+	fn it_rejects_large_downloads() {
+		let options = DownloadOptions { max_size: 4, ..Default::default() };
+		assert!(matches!(options.check(&url(), WASM), Err(SubwasmLibError::DownloadTooLarge(_, 4))));
+	}
+
+	#[test]
+	fn it_checks_the_hashes() {
+		let blake2_256 = format!("0x{}", hex::encode(blake2_256(WASM)));
+		let sha256 = hex::encode(sha2_256(WASM)).to_uppercase();
+
+		let options = DownloadOptions { blake2_256: vec![OTHER_HASH.into(), blake2_256], ..Default::default() };
+		assert!(options.check(&url(), WASM).is_ok());
+
+		let options = DownloadOptions { sha256: vec![sha256], ..options };
+		assert!(options.check(&url(), WASM).is_ok());
+
+		let options = DownloadOptions { blake2_256: vec![OTHER_HASH.into()], ..Default::default() };
+		match options.check(&url(), WASM) {
+			Err(SubwasmLibError::ChecksumMismatch(_, algorithm, _, expected)) => {
+				assert_eq!(algorithm, "blake2-256");
+				assert_eq!(expected, OTHER_HASH);
+			}
+			res => panic!("Unexpected result: {res:?}"),
+		}
+	}
+}
@@ -62,18 +62,58 @@ pub enum SubwasmLibError {
 	#[error("Generic error: {0}")]
 	Generic(String),
 
+	/// An error occurred while loading a runtime
+	#[error(transparent)]
+	WasmLoader(WasmLoaderError),
+
+	/// An error occurred in the testbed
+	#[error(transparent)]
+	WasmTestbed(WasmTestbedError),
+
 	/// Filtering is not available for all formats
 	#[error("Cannot filter with this format")]
 	UnsupportedFilter(),
 
+	/// The download is larger than the limit
+	#[error("The download from {0} exceeds the limit of {1} bytes")]
+	DownloadTooLarge(Url, u64),
+
+	/// The runtime we downloaded does not have the hash we expected: (url, algorithm, actual, expected)
+	#[error("The {1} hash of the runtime at {0} is {2}, expected {3}")]
+	ChecksumMismatch(String, String, String, String),
+
 	/// No runtime could be found at the provided URL
 	#[error("Could not find a valid runtime at {0}")]
 	NoRuntimeAtUrl(Url),
 
+	/// The chain spec is invalid or does not contain a runtime
+	#[error("Chain spec error: {0}")]
+	ChainSpec(String),
+
 	/// The source cannot be resolved
 	#[error("Cannot resolve `{0}` to a known Source")]
 	UnknownSource(String),
 
+	/// Only the metadata of the runtime was fetched
+	#[error("Only the metadata of the runtime is available")]
+	MetadataOnly(),
+
+	/// The metadata alone can only be fetched from a node
+	#[error("Fetching only the metadata requires a node, got {0}")]
+	MetadataOnlyUnsupported(String),
+
+	/// The runtime APIs are only described by the V15 metadata and later
+	#[error("This runtime does not describe its APIs, V15 metadata or later is required")]
+	NoRuntimeApis(),
+
+	/// The runtime does not offer the requested genesis preset
+	#[error("The runtime has no genesis preset `{0}`")]
+	GenesisPresetNotFound(String),
+
+	/// Stdin was requested for more than one runtime
+	#[error("The runtime can be read from stdin only once, `-` was passed more than once")]
+	StdinAlreadyRead(),
+
 	/// Unknown error
 	#[error("Unknown error")]
 	Unknown(),
@@ -86,14 +126,14 @@ impl From<std::io::Error> for SubwasmLibError {
 }
 
 impl From<WasmTestbedError> for SubwasmLibError {
-	fn from(_e: WasmTestbedError) -> Self {
-		SubwasmLibError::Generic("WasmTestbedError".to_string())
+	fn from(e: WasmTestbedError) -> Self {
+		SubwasmLibError::WasmTestbed(e)
 	}
 }
 
 impl From<WasmLoaderError> for SubwasmLibError {
-	fn from(_e: WasmLoaderError) -> Self {
-		SubwasmLibError::Generic("WasmLoaderError".to_string())
+	fn from(e: WasmLoaderError) -> Self {
+		SubwasmLibError::WasmLoader(e)
 	}
 }
 
@@ -0,0 +1,227 @@
+//! Async versions of the functions fetching runtimes, available with the `async` feature.
+
+use crate::{
+	error::*, get_output_file_local, get_output_file_tmp, source::Source, ChainInfo, ChainSpec, DownloadOptions,
+};
+use log::debug;
+use std::{path::PathBuf, str::FromStr};
+use url::Url;
+use wasm_loader::{
+	BlockRef, NodeEndpoint, OnchainBlock, RetryPolicy, RuntimeCache, Snapshot, Source as WasmLoaderSource,
+	ValidationCode, WasmLoader,
+};
+
+/// See [fetch_at_url](crate::fetch_at_url)
+pub async fn fetch_at_url_async(url: Url, target: Option<PathBuf>) -> Result<PathBuf> {
+	fetch_at_url_with_async(url, target, &DownloadOptions::default()).await
+}
+
+/// See [fetch_at_url_with](crate::fetch_at_url_with)
+pub async fn fetch_at_url_with_async(url: Url, target: Option<PathBuf>, options: &DownloadOptions) -> Result<PathBuf> {
+	debug!("Fetching from {url}");
+	let target = if let Some(target) = target { target } else { get_output_file_tmp()? };
+
+	let mut resp =
+		reqwest::get(url.to_owned()).await.map_err(|_e| SubwasmLibError::Generic("Request error".to_string()))?;
+	if !resp.status().is_success() {
+		return Err(SubwasmLibError::Generic(format!("Failed fetching url at {url}")));
+	}
+
+	// No need to download what we would reject anyway
+	if resp.content_length().is_some_and(|length| length > options.max_size) {
+		return Err(SubwasmLibError::DownloadTooLarge(url, options.max_size));
+	}
+
+	let mut bytes = Vec::new();
+	while let Some(chunk) = resp.chunk().await.map_err(|_e| SubwasmLibError::Io)? {
+		bytes.extend_from_slice(&chunk);
+		if bytes.len() as u64 > options.max_size {
+			return Err(SubwasmLibError::DownloadTooLarge(url, options.max_size));
+		}
+	}
+	options.check(&url, &bytes)?;
+
+	tokio::fs::write(&target, bytes).await.map_err(|_e| SubwasmLibError::Io)?;
+	Ok(target)
+}
+
+/// See [fetch_at_url_with_cache](crate::fetch_at_url_with_cache)
+pub async fn fetch_at_url_with_cache_async(
+	url: Url,
+	cache: &RuntimeCache,
+	options: &DownloadOptions,
+) -> Result<PathBuf> {
+	let key = RuntimeCache::url_key(url.as_str());
+	if let Some(path) = cache.get_path(&key) {
+		// What was published at the url may have changed since we cached it
+		match options.check(&url, &tokio::fs::read(&path).await?) {
+			Ok(()) => return Ok(path),
+			Err(e) => log::warn!("Ignoring the cached runtime of {url}: {e}"),
+		}
+	}
+
+	let tmp = fetch_at_url_with_async(url, None, options).await?;
+	let path = cache.insert(&key, &tokio::fs::read(&tmp).await?)?;
+	let _ = tokio::fs::remove_file(tmp).await;
+	Ok(path)
+}
+
+/// See [download_runtime](crate::download_runtime)
+pub async fn download_runtime_async(
+	endpoint: NodeEndpoint,
+	block_ref: Option<BlockRef>,
+	target: Option<PathBuf>,
+) -> Result<PathBuf> {
+	fetch_runtime_async(&OnchainBlock::from_endpoint(endpoint, block_ref), target)
+		.await
+		.map(|(outfile, _endpoint)| outfile)
+}
+
+/// See [fetch_runtime](crate::fetch_runtime)
+pub async fn fetch_runtime_async(reference: &OnchainBlock, target: Option<PathBuf>) -> Result<(PathBuf, NodeEndpoint)> {
+	log::info!("Downloading runtime from  {:?}", reference);
+
+	let loader = WasmLoader::load_from_source_async(&WasmLoaderSource::Chain(reference.to_owned())).await?;
+	let wasm = loader.original_bytes();
+
+	log::info!("Got the runtime, its size is {:?}", wasm.len());
+
+	let outfile = get_output_file_local(target);
+
+	log::info!("Saving runtime to {outfile:?}");
+	tokio::fs::write(&outfile, wasm).await?;
+	let endpoint = loader.endpoint().cloned().unwrap_or_else(|| reference.endpoint.clone());
+	Ok((outfile, endpoint))
+}
+
+impl Source {
+	/// See [Source::as_file]
+	pub async fn as_file_async(&self) -> Result<PathBuf> {
+		self.as_file_with_cache_async(None).await
+	}
+
+	/// See [Source::as_file_with_cache]
+	pub async fn as_file_with_cache_async(&self, cache: Option<&RuntimeCache>) -> Result<PathBuf> {
+		self.as_file_with_async(cache, &DownloadOptions::default()).await
+	}
+
+	/// See [Source::as_file_with]
+	pub async fn as_file_with_async(&self, cache: Option<&RuntimeCache>, options: &DownloadOptions) -> Result<PathBuf> {
+		match (self, cache) {
+			(Source::URL(u), Some(cache)) => fetch_at_url_with_cache_async(u.to_owned(), cache, options).await,
+			(Source::Github(gh), Some(cache)) => fetch_at_url_with_cache_async(gh.as_url(), cache, options).await,
+			(Source::Chain(ocb), Some(cache)) => {
+				let source = WasmLoaderSource::Chain(ocb.to_owned());
+				let loader = WasmLoader::load_from_source_with_cache_async(&source, Some(cache)).await?;
+				let cached = loader
+					.block_hash()
+					.zip(loader.endpoint())
+					.and_then(|(h, endpoint)| cache.get_path(&RuntimeCache::chain_key(endpoint, h)));
+				match cached {
+					Some(path) => Ok(path),
+					// We could not cache it, let's not fail for that
+					None => {
+						let target = get_output_file_tmp()?;
+						tokio::fs::write(&target, loader.original_bytes()).await?;
+						Ok(target)
+					}
+				}
+			}
+
+			(Source::Parachain(relay, para_id), cache) => {
+				let code = ValidationCode::fetch_async(relay, *para_id, cache).await?;
+				match cache.and_then(|c| c.get_blob_path(&code.code_hash)) {
+					Some(path) => Ok(path),
+					None => {
+						let target = get_output_file_tmp()?;
+						tokio::fs::write(&target, &code.code).await?;
+						Ok(target)
+					}
+				}
+			}
+
+			(Source::File(i), _) => Ok(i.to_owned()),
+			(Source::ChainSpec(spec), _) => ChainSpec::extract_code_to(spec, get_output_file_tmp()?),
+			(Source::Snapshot(snapshot), _) => {
+				let target = get_output_file_tmp()?;
+				tokio::fs::write(&target, Snapshot::load(snapshot)?.code()?).await?;
+				Ok(target)
+			}
+			(Source::Bytes(bytes), _) => {
+				let target = get_output_file_tmp()?;
+				tokio::fs::write(&target, bytes).await?;
+				Ok(target)
+			}
+			(Source::URL(u), None) => fetch_at_url_with_async(u.to_owned(), None, options).await,
+			(Source::Github(gh), None) => fetch_at_url_with_async(gh.as_url(), None, options).await,
+			(Source::Chain(ocb), None) => fetch_runtime_async(ocb, None).await.map(|(file, _endpoint)| file),
+			(Source::Alias(name), _) => {
+				let reference = ChainInfo::from_str(name)?.onchain_block(None, RetryPolicy::default())?;
+				Box::pin(Source::Chain(reference).as_file_with_async(cache, options)).await
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_fetch_async {
+	use super::*;
+	use std::io::{Read, Write};
+
+	const WASM: &[u8] = &[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+
+	/// Serve `body` once over HTTP
+	fn serve(body: &'static [u8]) -> Url {
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed binding");
+		let port = listener.local_addr().expect("Failed getting address").port();
+
+		std::thread::spawn(move || {
+			let (mut stream, _) = listener.accept().expect("Failed accepting connection");
+			let _ = stream.read(&mut [0; 4096]);
+			let header = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+			stream.write_all(header.as_bytes()).expect("Failed sending header");
+			stream.write_all(body).expect("Failed sending body");
+		});
+
+		Url::parse(&format!("http://127.0.0.1:{port}/runtime.wasm")).expect("Failed parsing url")
+	}
+
+	#[tokio::test]
+	async fn it_fetches_at_url() {
+		let target = get_output_file_tmp().expect("Failed getting a tmp file");
+		let path = fetch_at_url_async(serve(WASM), Some(target.clone())).await.expect("Failed fetching");
+		assert_eq!(path, target);
+		assert_eq!(std::fs::read(&path).expect("Failed reading file"), WASM);
+		std::fs::remove_file(path).expect("Failed removing file");
+	}
+
+	#[tokio::test]
+	async fn it_checks_what_it_fetches() {
+		let res = fetch_at_url_async(serve(b"<html>Not found</html>"), None).await;
+		assert!(matches!(res, Err(SubwasmLibError::NoRuntimeAtUrl(_))));
+
+		let options = DownloadOptions { max_size: 4, ..Default::default() };
+		let res = fetch_at_url_with_async(serve(WASM), None, &options).await;
+		assert!(matches!(res, Err(SubwasmLibError::DownloadTooLarge(_, 4))));
+
+		let options = DownloadOptions { sha256: vec!["0x00".into()], ..Default::default() };
+		let res = fetch_at_url_with_async(serve(WASM), None, &options).await;
+		assert!(matches!(res, Err(SubwasmLibError::ChecksumMismatch(..))));
+	}
+
+	#[tokio::test]
+	async fn it_fetches_at_url_with_cache() {
+		let root = std::env::temp_dir().join(format!("subwasm-fetch-async-test-{}", std::process::id()));
+		let cache = RuntimeCache::new(root);
+		let url = serve(WASM);
+		let options = DownloadOptions::default();
+
+		let path = fetch_at_url_with_cache_async(url.clone(), &cache, &options).await.expect("Failed fetching");
+		// The server is gone, we must get it from the cache
+		let again = fetch_at_url_with_cache_async(url, &cache, &options).await.expect("Failed fetching");
+		assert_eq!(path, again);
+		assert_eq!(std::fs::read(&path).expect("Failed reading file"), WASM);
+
+		cache.clear().expect("Failed clearing cache");
+	}
+}
@@ -0,0 +1,32 @@
+use crate::error::*;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// The genesis presets offered by a runtime through its `GenesisBuilder` API
+#[derive(Debug, Serialize)]
+pub struct GenesisPresets {
+	pub presets: Vec<String>,
+}
+
+impl GenesisPresets {
+	/// Print the presets either using the Display impl
+	/// or serde as json.
+	pub fn print(&self, json: bool) -> Result<()> {
+		if json {
+			let serialized = serde_json::to_string_pretty(self)?;
+			println!("{serialized}");
+		} else {
+			print!("{self}");
+		}
+		Ok(())
+	}
+}
+
+impl Display for GenesisPresets {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for preset in &self.presets {
+			writeln!(fmt, "{preset}")?;
+		}
+		Ok(())
+	}
+}
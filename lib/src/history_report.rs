@@ -0,0 +1,123 @@
+use crate::error::*;
+use num_format::{Locale, ToFormattedString};
+use serde::Serialize;
+use std::{
+	fmt::Display,
+	fs,
+	path::{Path, PathBuf},
+};
+use wasm_loader::{BlockRef, OnchainBlock, RuntimeCache, RuntimeHistory, RuntimeUpgrade, Source};
+use wasm_testbed::WasmTestBed;
+
+/// A runtime of the history we saved locally
+#[derive(Debug, Serialize)]
+pub struct DownloadedRuntime {
+	pub spec_version: u32,
+	pub metadata_version: u8,
+	pub file: PathBuf,
+}
+
+/// The runtime upgrades of a chain and, optionally, where we saved the runtimes
+#[derive(Debug, Serialize)]
+pub struct HistoryReport {
+	#[serde(flatten)]
+	history: RuntimeHistory,
+
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	downloads: Vec<DownloadedRuntime>,
+}
+
+impl HistoryReport {
+	/// Scan the blocks from `from` to `to` for runtime upgrades, the node must be an archive node
+	pub fn new(reference: &OnchainBlock, from: u64, to: Option<u64>) -> Result<Self> {
+		Ok(Self { history: RuntimeHistory::fetch(reference, from, to)?, downloads: Vec::new() })
+	}
+
+	/// The runtime in place at the start of the range followed by the upgrades
+	pub fn runtimes(&self) -> impl Iterator<Item = &RuntimeUpgrade> {
+		std::iter::once(&self.history.initial).chain(self.history.upgrades.iter())
+	}
+
+	/// Save each runtime as `<dir>/<spec_name>/V<metadata_version>/<spec_version>.wasm`,
+	/// the layout used for the runtimes of the tests.
+	pub fn download(&mut self, reference: &OnchainBlock, dir: &Path, cache: Option<&RuntimeCache>) -> Result<()> {
+		let mut downloads = Vec::new();
+
+		for runtime in self.runtimes() {
+			log::info!(
+				"Downloading {} v{} from block #{}",
+				runtime.spec_name,
+				runtime.spec_version,
+				runtime.block_number
+			);
+			let block = OnchainBlock {
+				endpoint: self.history.endpoint.clone(),
+				block_ref: Some(BlockRef::Hash(runtime.block_hash.clone())),
+				fallbacks: reference.endpoints().filter(|e| **e != self.history.endpoint).cloned().collect(),
+				..reference.clone()
+			};
+			let testbed = WasmTestBed::new_with_cache(&Source::Chain(block), cache)?;
+			let metadata_version = *testbed.metadata_version();
+
+			let folder = dir.join(&runtime.spec_name).join(format!("V{metadata_version}"));
+			fs::create_dir_all(&folder)?;
+			let file = folder.join(format!("{}.wasm", runtime.spec_version));
+			fs::write(&file, testbed.raw_bytes())?;
+
+			downloads.push(DownloadedRuntime { spec_version: runtime.spec_version, metadata_version, file });
+		}
+
+		self.downloads = downloads;
+		Ok(())
+	}
+
+	/// Print the report either using the Display impl
+	/// or serde as json.
+	pub fn print(&self, json: bool) -> Result<()> {
+		if json {
+			let serialized = serde_json::to_string_pretty(self)?;
+			println!("{serialized}");
+		} else {
+			print!("{self}");
+		}
+		Ok(())
+	}
+}
+
+impl Display for HistoryReport {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let width_emoji = 1;
+		let width_title = 16;
+		let history = &self.history;
+
+		writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "🌐", "Endpoint:", history.endpoint)?;
+		writeln!(
+			fmt,
+			"{:<width_emoji$} {:<width_title$} #{} to #{}",
+			"🧱",
+			"Blocks:",
+			history.from.to_formatted_string(&Locale::en),
+			history.to.to_formatted_string(&Locale::en)
+		)?;
+		writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "🆙", "Upgrades:", history.upgrades.len())?;
+
+		for runtime in self.runtimes() {
+			let file = self
+				.downloads
+				.iter()
+				.find(|d| d.spec_version == runtime.spec_version)
+				.map(|d| format!(" 💾 {}", d.file.display()))
+				.unwrap_or_default();
+			writeln!(
+				fmt,
+				"  #{:<14} {} v{:<8} {} {}{file}",
+				runtime.block_number.to_formatted_string(&Locale::en),
+				runtime.spec_name,
+				runtime.spec_version,
+				runtime.block_hash,
+				runtime.code_hash,
+			)?;
+		}
+		Ok(())
+	}
+}
@@ -3,32 +3,60 @@
 pub mod error;
 pub mod source;
 
+mod api_call;
+mod cache_report;
 mod chain_info;
+mod chain_spec;
 mod chain_urls;
+mod compression_report;
 mod convert;
+mod download;
+#[cfg(feature = "async")]
+mod fetch_async;
+mod genesis;
 mod github_ref;
+mod history_report;
 mod macros;
 mod metadata_wrapper;
+mod runtime_apis;
 mod runtime_info;
 mod subwasm;
+mod try_upgrade;
 mod types;
+mod upgrade_status;
 mod utils;
 
 use std::{fs::File, io::prelude::*, path::PathBuf, str::FromStr};
 use substrate_differ::differs::reduced::{reduced_diff_result::ReducedDiffResult, reduced_runtime::ReducedRuntime};
 use url::Url;
-use wasm_loader::{BlockRef, Compression, NodeEndpoint, OnchainBlock, Source, WasmLoader};
+use wasm_loader::{
+	BlockRef, Compression, CompressionOptions, NodeEndpoint, OnchainBlock, RuntimeCache, Source, ValidationCode,
+	WasmLoader, RELAY_MAX_CODE_SIZE,
+};
 use wasm_testbed::WasmTestBed;
 
+pub use api_call::*;
+pub use cache_report::*;
 pub use chain_info::*;
+pub use chain_spec::*;
+pub use compression_report::*;
+pub use download::*;
 pub use error::*;
+#[cfg(feature = "async")]
+pub use fetch_async::*;
+pub use genesis::*;
 pub use github_ref::*;
+pub use history_report::*;
 pub use metadata_wrapper::OutputFormat;
+pub use runtime_apis::*;
 pub use runtime_info::*;
 pub use substrate_differ::differs::diff_method::DiffMethod;
 pub use subwasm::*;
+pub use try_upgrade::*;
 pub use types::*;
+pub use upgrade_status::*;
 pub use utils::*;
+pub use wasm_testbed::{TestbedStorage, UpgradeChecks};
 
 /// Returns Some node url if possible, None otherwise.
 fn get_node_url(chain: &str) -> Result<Url> {
@@ -65,11 +93,24 @@ pub fn download_runtime(
 	block_ref: Option<BlockRef>,
 	target: Option<PathBuf>,
 ) -> Result<PathBuf> {
-	let reference = OnchainBlock { endpoint, block_ref };
+	fetch_runtime(&OnchainBlock::from_endpoint(endpoint, block_ref), target).map(|(outfile, _endpoint)| outfile)
+}
+
+/// Same as [download_runtime] but the other endpoints of the reference, if any, are tried
+/// in turn if the main one fails. The endpoint that served the runtime is returned along with the file.
+pub fn fetch_runtime(reference: &OnchainBlock, target: Option<PathBuf>) -> Result<(PathBuf, NodeEndpoint)> {
+	fetch_runtime_with_cache(reference, target, None)
+}
+
+/// Same as [fetch_runtime] but the runtime is looked up in and stored into the given cache
+pub fn fetch_runtime_with_cache(
+	reference: &OnchainBlock,
+	target: Option<PathBuf>,
+	cache: Option<&RuntimeCache>,
+) -> Result<(PathBuf, NodeEndpoint)> {
 	log::info!("Downloading runtime from  {:?}", reference);
 
-	let loader =
-		wasm_loader::WasmLoader::load_from_source(&Source::Chain(reference)).expect("Getting wasm from the node");
+	let loader = wasm_loader::WasmLoader::load_from_source_with_cache(&Source::Chain(reference.to_owned()), cache)?;
 	let wasm = loader.original_bytes();
 
 	log::info!("Got the runtime, its size is {:?}", wasm.len());
@@ -79,7 +120,25 @@ pub fn download_runtime(
 	log::info!("Saving runtime to {outfile:?}");
 	let mut buffer = File::create(&outfile)?;
 	buffer.write_all(wasm)?;
-	Ok(outfile)
+	let endpoint = loader.endpoint().cloned().unwrap_or_else(|| reference.endpoint.clone());
+	Ok((outfile, endpoint))
+}
+
+/// Fetch the validation code of the parachain `para_id` from its relay chain and store the wasm locally.
+/// The wasm is stored at the provided target or into a file name that is generated.
+pub fn fetch_validation_code(
+	relay: &OnchainBlock,
+	para_id: u32,
+	target: Option<PathBuf>,
+) -> Result<(PathBuf, ValidationCode)> {
+	log::info!("Downloading the validation code of para {para_id} from {:?}", relay);
+	let code = ValidationCode::fetch(relay, para_id, None)?;
+
+	let outfile = get_output_file_local(target);
+	log::info!("Saving runtime to {outfile:?}");
+	let mut buffer = File::create(&outfile)?;
+	buffer.write_all(&code.code)?;
+	Ok((outfile, code))
 }
 
 /// Compute the diff of 2 runtimes
@@ -99,23 +158,39 @@ pub fn reduced_diff(src_a: Source, src_b: Source) -> Result<ReducedDiffResult> {
 /// Compress a given runtime into a new file. You cannot compress
 /// a runtime that is already compressed.
 pub fn compress(input: PathBuf, output: PathBuf) -> Result<()> {
+	compress_with(input, output, &CompressionOptions::default(), RELAY_MAX_CODE_SIZE).map(|_report| ())
+}
+
+/// Same as [compress] but using the given [CompressionOptions]. The size of the compressed
+/// runtime is reported against `max_code_size`, usually the code size limit of the relay chain.
+pub fn compress_with(
+	input: PathBuf,
+	output: PathBuf,
+	options: &CompressionOptions,
+	max_code_size: usize,
+) -> Result<CompressionReport> {
 	let wasm = WasmLoader::load_from_source(&Source::File(input))?;
 
 	if wasm.compression().compressed() {
 		return Err(error::SubwasmLibError::AlreadyCompressed());
 	}
 
-	let bytes_compressed =
-		Compression::compress(wasm.original_bytes()).map_err(|_e| error::SubwasmLibError::CompressionFailed())?;
+	let bytes_compressed = Compression::compress_with(wasm.original_bytes(), options)?;
 
 	log::debug!("original   = {:?}", wasm.original_bytes().len());
 	log::debug!("compressed = {:?}", bytes_compressed.len());
 	log::info!("Saving compressed runtime to {:?}", output);
 
-	let mut buffer = File::create(output)?;
+	let mut buffer = File::create(&output)?;
 	buffer.write_all(&bytes_compressed.to_vec())?;
 
-	Ok(())
+	Ok(CompressionReport {
+		output,
+		options: *options,
+		size_uncompressed: wasm.original_bytes().len(),
+		size_compressed: bytes_compressed.len(),
+		max_code_size,
+	})
 }
 
 /// Decompress a given runtime file. It is fine decompressing an already
@@ -59,6 +59,36 @@ macro_rules! write_v14_meta {
 	};
 }
 
+/// Write the calls, events, errors, storage and constants of a V14 pallet, or a later version sharing those items
+#[macro_export]
+macro_rules! write_v14_pallet {
+	($v14: expr, $meta: expr, $out: ident) => {
+		|| -> error::Result<()> {
+			writeln!($out, "🤙 Calls:")?;
+			$crate::write_v14_meta!($v14, $meta, calls, $out);
+
+			writeln!($out, "📢 Events:")?;
+			$crate::write_v14_meta!($v14, $meta, event, $out);
+
+			writeln!($out, "⛔️ Errors:")?;
+			$crate::write_v14_meta!($v14, $meta, error, $out);
+
+			writeln!($out, "📦 Storage:")?;
+			if let Some(meta) = &$meta.storage {
+				for entry in &meta.entries {
+					writeln!($out, "- {}", entry.name)?;
+				}
+			}
+
+			writeln!($out, "💎 Constants:")?;
+			for item in &$meta.constants {
+				writeln!($out, "- {}", item.name)?;
+			}
+			Ok(())
+		}()?
+	};
+}
+
 #[macro_export]
 macro_rules! display_module {
 	($modules: expr, $filter: ident) => {
@@ -1,4 +1,4 @@
-use crate::{convert::convert, error, utils::print_big_output_safe, write_module, write_v14_meta};
+use crate::{convert::convert, error, utils::print_big_output_safe, write_module, write_v14_pallet};
 use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
 use log::debug;
 use scale_info::scale::Encode;
@@ -63,10 +63,8 @@ impl<'a> MetadataWrapper<'a> {
 			}
 
 			OutputFormat::Scale if filter.is_none() => {
-				match out.write_all(&self.runtime_metadata_prefixed().encode()) {
-					Ok(_) => {}
-					Err(_) => {} // Silence broken pipe errors
-				};
+				// Silence broken pipe errors
+				let _ = out.write_all(&self.runtime_metadata_prefixed().encode());
 			}
 
 			OutputFormat::HexScale if filter.is_none() => {
@@ -99,14 +97,14 @@ impl<'a> MetadataWrapper<'a> {
 		match self.runtime_metadata() {
 			RuntimeMetadata::V12(v12) => {
 				let mut modules = convert(&v12.modules).clone();
-				modules.sort_by(|a, b| a.index.cmp(&b.index));
+				modules.sort_by_key(|a| a.index);
 				modules.iter().try_for_each(|module| -> std::io::Result<()> {
 					writeln!(out, " - {:02}: {}", module.index, convert(&module.name))
 				})?;
 			}
 			RuntimeMetadata::V13(v13) => {
 				let mut modules = convert(&v13.modules).clone();
-				modules.sort_by(|a, b| a.index.cmp(&b.index));
+				modules.sort_by_key(|a| a.index);
 				modules.iter().try_for_each(|module| -> std::io::Result<()> {
 					writeln!(out, " - {:02}: {}", module.index, convert(&module.name))
 				})?;
@@ -119,6 +117,20 @@ impl<'a> MetadataWrapper<'a> {
 					writeln!(out, " - {:02}: {}", pallet.index, pallet.name)
 				})?;
 			}
+			RuntimeMetadata::V15(v15) => {
+				let mut pallets = v15.pallets.clone();
+				pallets.sort_by_key(|p| p.index);
+				pallets.iter().try_for_each(|pallet| -> std::io::Result<()> {
+					writeln!(out, " - {:02}: {}", pallet.index, pallet.name)
+				})?;
+			}
+			RuntimeMetadata::V16(v16) => {
+				let mut pallets = v16.pallets.clone();
+				pallets.sort_by_key(|p| p.index);
+				pallets.iter().try_for_each(|pallet| -> std::io::Result<()> {
+					writeln!(out, " - {:02}: {}", pallet.index, pallet.name)
+				})?;
+			}
 			_ => return Err(error::SubwasmLibError::UnsupportedRuntimeVersion()),
 		};
 		Ok(())
@@ -146,25 +158,41 @@ impl<'a> MetadataWrapper<'a> {
 					.ok_or_else(|| error::SubwasmLibError::PalletNotFound(filter.to_string()))?;
 
 				writeln!(out, "Module {:02}: {}", meta.index, &meta.name)?;
+				write_v14_pallet!(v14, meta, out);
+			}
+			RuntimeMetadata::V15(v15) => {
+				let meta = v15
+					.pallets
+					.iter()
+					.find(|pallet| pallet.name.to_lowercase() == filter.to_lowercase())
+					.ok_or_else(|| error::SubwasmLibError::PalletNotFound(filter.to_string()))?;
 
-				writeln!(out, "🤙 Calls:")?;
-				write_v14_meta!(v14, meta, calls, out);
-
-				writeln!(out, "📢 Events:")?;
-				write_v14_meta!(v14, meta, event, out);
+				writeln!(out, "Module {:02}: {}", meta.index, &meta.name)?;
+				for line in &meta.docs {
+					writeln!(out, "  {line}")?;
+				}
+				write_v14_pallet!(v15, meta, out);
+			}
+			RuntimeMetadata::V16(v16) => {
+				let meta = v16
+					.pallets
+					.iter()
+					.find(|pallet| pallet.name.to_lowercase() == filter.to_lowercase())
+					.ok_or_else(|| error::SubwasmLibError::PalletNotFound(filter.to_string()))?;
 
-				writeln!(out, "⛔️ Errors:")?;
-				write_v14_meta!(v14, meta, error, out);
+				writeln!(out, "Module {:02}: {}", meta.index, &meta.name)?;
+				for line in &meta.docs {
+					writeln!(out, "  {line}")?;
+				}
+				write_v14_pallet!(v16, meta, out);
 
-				writeln!(out, "📦 Storage:")?;
-				if let Some(meta) = &meta.storage {
-					for entry in &meta.entries {
-						writeln!(out, "- {}", entry.name)?;
-					}
+				writeln!(out, "👀 View functions:")?;
+				for item in &meta.view_functions {
+					writeln!(out, "- {}", item.name)?;
 				}
 
-				writeln!(out, "💎 Constants:")?;
-				for item in &meta.constants {
+				writeln!(out, "🧩 Associated types:")?;
+				for item in &meta.associated_types {
 					writeln!(out, "- {}", item.name)?;
 				}
 			}
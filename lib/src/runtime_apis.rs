@@ -0,0 +1,239 @@
+use crate::error::*;
+use frame_metadata::{v15::RuntimeMetadataV15, v16::RuntimeMetadataV16};
+use scale_info::PortableRegistry;
+use serde::Serialize;
+use sp_crypto_hashing::blake2_64;
+use sp_version::RuntimeVersion;
+use std::fmt::Display;
+use substrate_differ::differs::reduced::type_name::type_name;
+
+/// A parameter of a runtime API method
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RuntimeApiParam {
+	pub name: String,
+
+	/// The type of the parameter, as named in the runtime
+	pub ty: String,
+}
+
+/// A method of a runtime API, called as `<api>_<method>`
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RuntimeApiMethod {
+	pub name: String,
+	pub inputs: Vec<RuntimeApiParam>,
+
+	/// The type of the output, as named in the runtime
+	pub output: String,
+	pub docs: Vec<String>,
+}
+
+/// A runtime API, such as `Core`, as described by the V15 or V16 metadata
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RuntimeApi {
+	pub name: String,
+
+	/// The version of the API, as reported by `Core_version` or, failing that, by the V16 metadata
+	pub version: Option<u32>,
+	pub methods: Vec<RuntimeApiMethod>,
+	pub docs: Vec<String>,
+}
+
+/// The runtime APIs exposed by a runtime
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RuntimeApis {
+	pub apis: Vec<RuntimeApi>,
+}
+
+impl RuntimeApis {
+	/// Resolve the APIs described by the `metadata`, their versions are taken from the runtime `version`
+	pub fn new(metadata: &RuntimeMetadataV15, version: &RuntimeVersion) -> Self {
+		let apis = metadata
+			.apis
+			.iter()
+			.map(|api| RuntimeApi {
+				name: api.name.clone(),
+				version: RuntimeApi::reported_version(&api.name, version),
+				methods: api
+					.methods
+					.iter()
+					.map(|m| {
+						let inputs = m.inputs.iter().map(|i| (&i.name, i.ty.id));
+						RuntimeApiMethod::new(&m.name, inputs, m.output.id, &m.docs, &metadata.types)
+					})
+					.collect(),
+				docs: api.docs.clone(),
+			})
+			.collect();
+		Self { apis }
+	}
+
+	/// Same as [RuntimeApis::new] for the V16 metadata, which also describes the versions of the APIs
+	pub fn from_v16(metadata: &RuntimeMetadataV16, version: &RuntimeVersion) -> Self {
+		let apis = metadata
+			.apis
+			.iter()
+			.map(|api| RuntimeApi {
+				name: api.name.clone(),
+				version: RuntimeApi::reported_version(&api.name, version).or(Some(api.version)),
+				methods: api
+					.methods
+					.iter()
+					.map(|m| {
+						let inputs = m.inputs.iter().map(|i| (&i.name, i.ty.id));
+						RuntimeApiMethod::new(&m.name, inputs, m.output.id, &m.docs, &metadata.types)
+					})
+					.collect(),
+				docs: api.docs.clone(),
+			})
+			.collect();
+		Self { apis }
+	}
+
+	/// Print the APIs either using the Display impl
+	/// or serde as json.
+	pub fn print(&self, json: bool) -> Result<()> {
+		if json {
+			let serialized = serde_json::to_string_pretty(self)?;
+			println!("{serialized}");
+		} else {
+			print!("{self}");
+		}
+		Ok(())
+	}
+}
+
+impl RuntimeApi {
+	/// The runtime reports the versions of its APIs by the blake2-64 hash of their names
+	fn reported_version(name: &str, version: &RuntimeVersion) -> Option<u32> {
+		let id = blake2_64(name.as_bytes());
+		version.apis.iter().find(|(api_id, _)| *api_id == id).map(|(_, version)| *version)
+	}
+}
+
+impl RuntimeApiMethod {
+	fn new<'a>(
+		name: &str,
+		inputs: impl Iterator<Item = (&'a String, u32)>,
+		output: u32,
+		docs: &[String],
+		registry: &PortableRegistry,
+	) -> Self {
+		Self {
+			name: name.to_string(),
+			inputs: inputs
+				.map(|(name, ty)| RuntimeApiParam { name: name.clone(), ty: type_name(registry, ty) })
+				.collect(),
+			output: type_name(registry, output),
+			docs: docs.to_vec(),
+		}
+	}
+}
+
+impl Display for RuntimeApis {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for api in &self.apis {
+			writeln!(fmt, "{api}")?;
+		}
+		Ok(())
+	}
+}
+
+impl Display for RuntimeApi {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.version {
+			Some(version) => writeln!(fmt, "{} v{version}", self.name)?,
+			None => writeln!(fmt, "{}", self.name)?,
+		}
+		for method in &self.methods {
+			writeln!(fmt, "  {method}")?;
+		}
+		Ok(())
+	}
+}
+
+impl Display for RuntimeApiMethod {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let inputs = self.inputs.iter().map(|i| format!("{}: {}", i.name, i.ty)).collect::<Vec<_>>();
+		write!(fmt, "{}({}) -> {}", self.name, inputs.join(", "), self.output)
+	}
+}
+
+#[cfg(test)]
+mod tests_runtime_apis {
+	use super::*;
+	use frame_metadata::v15::*;
+	use scale_info::{meta_type, TypeInfo};
+	use std::borrow::Cow;
+	use substrate_differ::differs::test_runtimes::{metadata_v15, metadata_v16};
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	struct Header {
+		number: u32,
+	}
+
+	fn metadata() -> RuntimeMetadataV15 {
+		let method = |name, inputs, output| RuntimeApiMethodMetadata { name, inputs, output, docs: vec![] };
+		let param = |name, ty| RuntimeApiMethodParamMetadata { name, ty };
+		let core = RuntimeApiMetadata {
+			name: "Core",
+			methods: vec![
+				method("version", vec![], meta_type::<u32>()),
+				method("initialize_block", vec![param("header", meta_type::<Header>())], meta_type::<()>()),
+			],
+			docs: vec!["The `Core` runtime api that every Substrate runtime needs to implement."],
+		};
+		let metadata = RuntimeApiMetadata {
+			name: "Metadata",
+			methods: vec![method(
+				"metadata_at_version",
+				vec![param("version", meta_type::<u32>())],
+				meta_type::<Option<Vec<u8>>>(),
+			)],
+			docs: vec![],
+		};
+		metadata_v15(vec![core, metadata])
+	}
+
+	#[test]
+	fn it_resolves_the_signatures() {
+		let version = RuntimeVersion { apis: Cow::Owned(vec![(blake2_64(b"Core"), 5)]), ..Default::default() };
+		let apis = RuntimeApis::new(&metadata(), &version);
+
+		assert_eq!(apis.apis.len(), 2);
+		assert_eq!(apis.apis[0].version, Some(5));
+		assert_eq!(apis.apis[1].version, None);
+		assert_eq!(
+			apis.to_string(),
+			"Core v5\n  version() -> u32\n  initialize_block(header: Header) -> ()\n\n\
+			 Metadata\n  metadata_at_version(version: u32) -> Option<Vec<u8>>\n\n"
+		);
+	}
+
+	#[test]
+	fn it_resolves_the_v16_signatures() {
+		use frame_metadata::v16;
+
+		let not_deprecated = || v16::DeprecationStatus::NotDeprecated;
+		let version = v16::RuntimeApiMethodMetadata {
+			name: "version",
+			inputs: vec![],
+			output: meta_type::<u32>(),
+			docs: vec![],
+			deprecation_info: not_deprecated(),
+		};
+		let api = |name, version| v16::RuntimeApiMetadata {
+			name,
+			methods: vec![],
+			docs: vec![],
+			deprecation_info: not_deprecated(),
+			version,
+		};
+		let core = v16::RuntimeApiMetadata { methods: vec![version], ..api("Core", 4) };
+		let metadata = metadata_v16(&[], false, vec![core, api("Metadata", 2)]);
+
+		let version = RuntimeVersion { apis: Cow::Owned(vec![(blake2_64(b"Core"), 5)]), ..Default::default() };
+		let apis = RuntimeApis::from_v16(&metadata, &version);
+		assert_eq!(apis.to_string(), "Core v5\n  version() -> u32\n\nMetadata v2\n\n");
+	}
+}
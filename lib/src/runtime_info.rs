@@ -4,7 +4,7 @@ use num_format::{Locale, ToFormattedString};
 use serde::Serialize;
 use sp_version::RuntimeVersion as SubstrateRuntimeVersion;
 use std::fmt::Display;
-use wasm_loader::Compression;
+use wasm_loader::{BlockHash, Compression, NodeEndpoint};
 use wasm_testbed::{ReservedMeta, WasmTestBed};
 
 /// Describe the summary information of a runtime.
@@ -28,6 +28,9 @@ pub struct RuntimeInfo {
 	/// Knowing the metadata version is required to properly decode the metadata
 	metadata_version: u8,
 
+	/// All the metadata versions the runtime offers, [metadata_version] being the latest we support
+	metadata_versions: Vec<u32>,
+
 	/// This is the core version of the runtime as reported by the runtimes
 	core_version: SubstrateRuntimeVersion,
 
@@ -44,6 +47,19 @@ pub struct RuntimeInfo {
 
 	/// The blake2_256 hash of the runtime
 	blake2_256: String,
+
+	/// The validation code hash as seen by the relay chain, when the runtime comes from there.
+	/// It should match the blake2_256 hash.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	validation_code_hash: Option<String>,
+
+	/// The hash of the block the runtime was fetched at, when it comes from a node
+	#[serde(skip_serializing_if = "Option::is_none")]
+	block_hash: Option<BlockHash>,
+
+	/// The endpoint that served the runtime, when it comes from a node
+	#[serde(skip_serializing_if = "Option::is_none")]
+	endpoint: Option<NodeEndpoint>,
 }
 
 impl RuntimeInfo {
@@ -61,11 +77,15 @@ impl RuntimeInfo {
 			reserved_meta: testbed.reserved_meta(),
 			reserved_meta_valid: testbed.reserved_meta_valid(),
 			metadata_version: *testbed.metadata_version(),
+			metadata_versions: testbed.metadata_versions().to_vec(),
 			core_version,
 			proposal_hash,
 			parachain_authorize_upgrade_hash,
 			ipfs_hash,
 			blake2_256,
+			validation_code_hash: testbed.validation_code_hash().cloned(),
+			block_hash: testbed.block_hash().cloned(),
+			endpoint: testbed.endpoint().cloned(),
 		})
 	}
 
@@ -131,7 +151,12 @@ impl Display for RuntimeInfo {
 			if self.reserved_meta_valid { "OK" } else { "Unknown!" },
 			self.reserved_meta,
 		)?;
-		writeln!(fmt, "{:<width_emoji$} {:<width_title$} V{:?}", "🎁", "Metadata version:", self.metadata_version)?;
+		let versions = self.metadata_versions.iter().map(|v| format!("V{v}")).collect::<Vec<_>>().join(", ");
+		writeln!(
+			fmt,
+			"{:<width_emoji$} {:<width_title$} V{:?} (offers {versions})",
+			"🎁", "Metadata version:", self.metadata_version
+		)?;
 		writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "🔥", "Core version:", self.core_version)?;
 		writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "🗳️ ", "system.setCode hash:", self.proposal_hash)?;
 		writeln!(
@@ -140,8 +165,17 @@ impl Display for RuntimeInfo {
 			"🗳️ ", "authorizeUpgrade hash:", self.parachain_authorize_upgrade_hash
 		)?;
 		writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "🗳️ ", "Blake2-256 hash:", self.blake2_256)?;
+		if let Some(hash) = &self.validation_code_hash {
+			writeln!(fmt, "{:<width_emoji$} {:<width_title$} {hash}", "🗳️ ", "Validation code hash:")?;
+		}
 		let ipfs_url = format!("https://cf-ipfs.com/ipfs/{cid}", cid = self.ipfs_hash);
 		writeln!(fmt, "{:<width_emoji$} {:<width_title$} {ipfs_url}", "📦", "IPFS:")?;
+		if let Some(block_hash) = &self.block_hash {
+			writeln!(fmt, "{:<width_emoji$} {:<width_title$} {block_hash}", "🧱", "Block hash:")?;
+		}
+		if let Some(endpoint) = &self.endpoint {
+			writeln!(fmt, "{:<width_emoji$} {:<width_title$} {endpoint}", "🌐", "Endpoint:")?;
+		}
 		Ok(())
 	}
 }
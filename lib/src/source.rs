@@ -1,27 +1,59 @@
 use error::*;
-use std::{fmt::Display, path::PathBuf, str::FromStr};
+use std::{
+	fmt::Display,
+	io::Read,
+	path::PathBuf,
+	str::FromStr,
+	sync::atomic::{AtomicBool, Ordering},
+};
 use url::Url;
-use wasm_loader::{BlockRef, OnchainBlock, Source as WasmLoaderSource};
+use wasm_loader::{
+	BlockRef, OnchainBlock, RetryPolicy, RpcHeaders, RuntimeCache, Snapshot, Source as WasmLoaderSource, TlsConfig,
+	ValidationCode, WasmLoader,
+};
 
 use crate::chain_urls::get_chain_urls;
-use crate::download_runtime;
 use crate::error;
-use crate::fetch_at_url;
+use crate::fetch_at_url_with;
+use crate::fetch_at_url_with_cache;
+use crate::fetch_runtime;
+use crate::fetch_validation_code;
+use crate::get_output_file_tmp;
 use crate::github_ref::GithubRef;
 use crate::is_wasm_from_url;
 use crate::ChainInfo;
+use crate::ChainSpec;
+use crate::DownloadOptions;
+
+/// Passing this instead of a file name reads the runtime from stdin
+pub const STDIN: &str = "-";
+
+/// Stdin can only be read once, a second `-` would silently get an empty runtime
+static STDIN_READ: AtomicBool = AtomicBool::new(false);
 
 /// The [wasm_loader::WasmLoader] provides a basic Source struct that
 /// can handle only a file or RPC endpoint.
 /// This Enum here is fancier and will allow more sources.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Clone, PartialEq)]
 pub enum Source {
 	/// A file on your local filesystem
 	File(PathBuf),
 
+	/// A chain spec on your local filesystem, raw or plain, containing the runtime
+	ChainSpec(PathBuf),
+
+	/// A try-runtime state snapshot on your local filesystem
+	Snapshot(PathBuf),
+
+	/// Some bytes, usually read from stdin
+	Bytes(Vec<u8>),
+
 	/// A remote endpoint we can connect to
 	Chain(OnchainBlock),
 
+	/// The validation code of a parachain, given its id, read from its relay chain
+	Parachain(OnchainBlock, u32),
+
 	/// A chain alias such as "westend" or "wnd"
 	Alias(String),
 
@@ -39,6 +71,10 @@ impl TryFrom<&str> for Source {
 	/// If you run into issues, first use the get command and then pass the path
 	/// to handle your runtime as a file.
 	fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+		if s == STDIN {
+			return Self::from_stdin();
+		}
+
 		// GithubRef can be parsed
 		if let Ok(gh_ref) = GithubRef::from_str(s) {
 			return Ok(Source::Github(gh_ref));
@@ -53,11 +89,14 @@ impl TryFrom<&str> for Source {
 
 		// First we deal with the easy case of files
 		let src = WasmLoaderSource::from_str(s);
-		if let Ok(s) = &src {
-			match s {
-				WasmLoaderSource::File(f) => return Ok(Source::File(f.to_owned())),
-				_ => {}
+		if let Ok(WasmLoaderSource::File(f)) = &src {
+			if ChainSpec::is_chain_spec(f) {
+				return Ok(Source::ChainSpec(f.to_owned()));
 			}
+			return Ok(Source::File(f.to_owned()));
+		}
+		if let Ok(WasmLoaderSource::Snapshot(f)) = &src {
+			return Ok(Source::Snapshot(f.to_owned()));
 		}
 
 		// This is where we try to be smart about URLs...
@@ -79,11 +118,8 @@ impl TryFrom<&str> for Source {
 			// if src.is_ok_and(|s| matches!(s, WasmLoaderSource::Chain(c))) {
 			// 	return Ok(Source::Chain(c));
 			// }
-			if let Ok(s) = &src {
-				match s {
-					WasmLoaderSource::Chain(c) => return Ok(Source::Chain(c.to_owned())),
-					_ => {}
-				}
+			if let Ok(WasmLoaderSource::Chain(c)) = &src {
+				return Ok(Source::Chain(c.to_owned()));
 			}
 		}
 
@@ -95,6 +131,15 @@ impl TryFrom<PathBuf> for Source {
 	type Error = SubwasmLibError;
 
 	fn try_from(s: PathBuf) -> std::result::Result<Self, Self::Error> {
+		if s.as_os_str() == STDIN {
+			return Self::from_stdin();
+		}
+		if ChainSpec::is_chain_spec(&s) {
+			return Ok(Source::ChainSpec(s));
+		}
+		if Snapshot::is_snapshot(&s) {
+			return Ok(Source::Snapshot(s));
+		}
 		Ok(WasmLoaderSource::File(s).into())
 	}
 }
@@ -103,7 +148,10 @@ impl From<WasmLoaderSource> for Source {
 	fn from(s: WasmLoaderSource) -> Self {
 		match s {
 			WasmLoaderSource::File(f) => Self::File(f),
+			WasmLoaderSource::Snapshot(f) => Self::Snapshot(f),
+			WasmLoaderSource::Bytes(b) => Self::Bytes(b),
 			WasmLoaderSource::Chain(c) => Self::Chain(c),
+			WasmLoaderSource::Parachain(relay, para_id) => Self::Parachain(relay, para_id),
 		}
 	}
 }
@@ -114,8 +162,11 @@ impl TryFrom<Source> for WasmLoaderSource {
 	fn try_from(val: Source) -> std::result::Result<Self, Self::Error> {
 		match val {
 			Source::File(f) => Ok(Self::File(f)),
+			Source::Snapshot(f) => Ok(Self::Snapshot(f)),
+			Source::Bytes(b) => Ok(Self::Bytes(b)),
 			Source::Chain(c) => Ok(Self::Chain(c)),
-			_ => Err(SubwasmLibError::Generic("Cannot convert Source::Alias to WasmLoaderSource".to_string())),
+			Source::Parachain(relay, para_id) => Ok(Self::Parachain(relay, para_id)),
+			s => Err(SubwasmLibError::Generic(format!("Cannot convert {s} to WasmLoaderSource"))),
 		}
 	}
 }
@@ -125,27 +176,148 @@ impl Source {
 	/// it offers convenient mechanisms to fetch the runtime.
 	/// This function takes care of the work required to get the file.
 	pub fn as_file(&self) -> Result<PathBuf> {
+		self.as_file_with_cache(None)
+	}
+
+	/// Same as [Source::as_file] but runtimes fetched from a URL or a node
+	/// are looked up in and stored into the given cache.
+	pub fn as_file_with_cache(&self, cache: Option<&RuntimeCache>) -> Result<PathBuf> {
+		self.as_file_with(cache, &DownloadOptions::default())
+	}
+
+	/// Same as [Source::as_file_with_cache] but runtimes downloaded from a URL
+	/// are checked against the given [DownloadOptions].
+	pub fn as_file_with(&self, cache: Option<&RuntimeCache>, options: &DownloadOptions) -> Result<PathBuf> {
+		match (self, cache) {
+			// Fetch or reuse the runtime, the file we get is in the cache
+			(Source::URL(u), Some(cache)) => fetch_at_url_with_cache(u.to_owned(), cache, options),
+			(Source::Github(gh), Some(cache)) => fetch_at_url_with_cache(gh.as_url(), cache, options),
+			(Source::Chain(ocb), Some(cache)) => {
+				let loader =
+					WasmLoader::load_from_source_with_cache(&WasmLoaderSource::Chain(ocb.to_owned()), Some(cache))?;
+				let cached = loader
+					.block_hash()
+					.zip(loader.endpoint())
+					.and_then(|(h, endpoint)| cache.get_path(&RuntimeCache::chain_key(endpoint, h)));
+				match cached {
+					Some(path) => Ok(path),
+					// We could not cache it, let's not fail for that
+					None => {
+						let target = get_output_file_tmp()?;
+						std::fs::write(&target, loader.original_bytes())?;
+						Ok(target)
+					}
+				}
+			}
+
+			// The validation code is cached under its hash
+			(Source::Parachain(relay, para_id), Some(cache)) => {
+				let code = ValidationCode::fetch(relay, *para_id, Some(cache))?;
+				match cache.get_blob_path(&code.code_hash) {
+					Some(path) => Ok(path),
+					None => {
+						let target = get_output_file_tmp()?;
+						std::fs::write(&target, &code.code)?;
+						Ok(target)
+					}
+				}
+			}
+
+			// Resolve the alias to a node first
+			(Source::Alias(_), Some(_)) => {
+				self.to_owned().with_retry_policy(RetryPolicy::default())?.as_file_with(cache, options)
+			}
+
+			(source, _) => source.as_file_uncached(options),
+		}
+	}
+
+	fn as_file_uncached(&self, options: &DownloadOptions) -> Result<PathBuf> {
 		match self {
 			// That one is easy :)
 			Source::File(i) => Ok(i.to_owned()),
 
+			// Extract the runtime from the chain spec into a tmp file
+			Source::ChainSpec(spec) => ChainSpec::extract_code_to(spec, get_output_file_tmp()?),
+
+			// Extract the runtime from the snapshot into a tmp file
+			Source::Snapshot(snapshot) => {
+				let target = get_output_file_tmp()?;
+				std::fs::write(&target, Snapshot::load(snapshot)?.code()?)?;
+				Ok(target)
+			}
+
+			// Store the bytes in a tmp file
+			Source::Bytes(bytes) => {
+				let target = get_output_file_tmp()?;
+				std::fs::write(&target, bytes)?;
+				Ok(target)
+			}
+
 			// Fetch from a URL and store the file in a tmp dir
-			Source::URL(u) => fetch_at_url(u.to_owned(), None),
+			Source::URL(u) => fetch_at_url_with(u.to_owned(), None, options),
 
 			// Generate the URL and fetch the file to a tmp dir
-			Source::Github(gh) => fetch_at_url(gh.as_url(), None),
+			Source::Github(gh) => fetch_at_url_with(gh.as_url(), None, options),
 
 			// Use the wasm_loader to download the runtime from a node
-			Source::Chain(ocb) => download_runtime(ocb.endpoint.to_owned(), ocb.block_ref.to_owned(), None),
+			Source::Chain(ocb) => fetch_runtime(ocb, None).map(|(file, _endpoint)| file),
+
+			// Read the validation code from the relay chain into a tmp file
+			Source::Parachain(relay, para_id) => {
+				fetch_validation_code(relay, *para_id, Some(get_output_file_tmp()?)).map(|(file, _code)| file)
+			}
 
-			// Get a URL then try fetching the runtime from a RPC node
+			// Get the nodes of the chain then try fetching the runtime from them
 			Source::Alias(name) => {
-				let url = ChainInfo::from_str(name)?.get_random_url(None)?;
-				download_runtime(url.try_into()?, None, None)
+				let reference = ChainInfo::from_str(name)?.onchain_block(None, RetryPolicy::default())?;
+				fetch_runtime(&reference, None).map(|(file, _endpoint)| file)
 			}
 		}
 	}
 
+	/// Set how hard we try the nodes of a chain. An alias is resolved to
+	/// the nodes of the chain so they can be tried in turn.
+	pub fn with_retry_policy(self, retry: RetryPolicy) -> Result<Self> {
+		Ok(match self {
+			Source::Chain(ocb) => Source::Chain(OnchainBlock { retry, ..ocb }),
+			Source::Parachain(relay, para_id) => Source::Parachain(OnchainBlock { retry, ..relay }, para_id),
+			Source::Alias(name) => Source::Chain(ChainInfo::from_str(&name)?.onchain_block(None, retry)?),
+			source => source,
+		})
+	}
+
+	/// Set the headers sent to the nodes of a chain, see [RpcHeaders]
+	pub fn with_headers(self, headers: &RpcHeaders) -> Self {
+		match self {
+			Source::Chain(ocb) => Source::Chain(OnchainBlock { headers: headers.clone(), ..ocb }),
+			Source::Parachain(relay, para_id) => {
+				Source::Parachain(OnchainBlock { headers: headers.clone(), ..relay }, para_id)
+			}
+			source => source,
+		}
+	}
+
+	/// Set the TLS settings used to connect to the nodes of a chain, see [TlsConfig]
+	pub fn with_tls(self, tls: &TlsConfig) -> Self {
+		match self {
+			Source::Chain(ocb) => Source::Chain(OnchainBlock { tls: tls.clone(), ..ocb }),
+			Source::Parachain(relay, para_id) => Source::Parachain(OnchainBlock { tls: tls.clone(), ..relay }, para_id),
+			source => source,
+		}
+	}
+
+	/// Read the runtime from stdin, this is what we get when the user passes `-`
+	pub fn from_stdin() -> Result<Self> {
+		if STDIN_READ.swap(true, Ordering::SeqCst) {
+			return Err(SubwasmLibError::StdinAlreadyRead());
+		}
+		let mut bytes = Vec::new();
+		std::io::stdin().read_to_end(&mut bytes)?;
+		log::debug!("Read {} bytes from stdin", bytes.len());
+		Ok(Self::Bytes(bytes))
+	}
+
 	pub fn from_options(
 		file: Option<PathBuf>,
 		chain: Option<ChainInfo>,
@@ -159,13 +331,11 @@ impl Source {
 		log::trace!(" - url  : {url:?}");
 
 		if let Some(f) = file {
-			return Ok(Self::File(f));
+			return Self::try_from(f);
 		}
 
 		if let Some(c) = chain {
-			let url = c.get_random_url(None)?;
-			let onchain_block = OnchainBlock::new(url.as_str(), block)?;
-			return Ok(Self::Chain(onchain_block));
+			return Ok(Self::Chain(c.onchain_block(block, RetryPolicy::default())?));
 		}
 
 		if let Some(u) = url {
@@ -175,10 +345,22 @@ impl Source {
 		Err(error::SubwasmLibError::UnknownSource(String::from("No file or chain or url provided!")))
 	}
 
+	/// The validation code of the parachain `para_id`, read from the relay chain `relay`
+	pub fn from_relay(relay: ChainInfo, para_id: u32, block: Option<BlockRef>) -> Result<Self> {
+		Ok(Self::Parachain(relay.onchain_block(block, RetryPolicy::default())?, para_id))
+	}
+
 	pub fn get_source_type(s: &str) -> Result<Source> {
+		if s == STDIN {
+			return Self::from_stdin();
+		}
+
 		// This covers WasmLoaderSource::File and WasmLoaderSource::Chain
 		if let Ok(source) = WasmLoaderSource::from_str(s) {
-			return Ok(source.into());
+			return match source {
+				WasmLoaderSource::File(f) => Self::try_from(f),
+				source => Ok(source.into()),
+			};
 		}
 
 		let hit_maybe = get_chain_urls(s);
@@ -191,11 +373,32 @@ impl Source {
 	}
 }
 
+/// Same as the derived impl but showing the length of [Source::Bytes] instead of dumping the whole runtime
+impl std::fmt::Debug for Source {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Source::File(file) => fmt.debug_tuple("File").field(file).finish(),
+			Source::ChainSpec(spec) => fmt.debug_tuple("ChainSpec").field(spec).finish(),
+			Source::Snapshot(snapshot) => fmt.debug_tuple("Snapshot").field(snapshot).finish(),
+			Source::Bytes(bytes) => write!(fmt, "Bytes({} bytes)", bytes.len()),
+			Source::Chain(chain) => fmt.debug_tuple("Chain").field(chain).finish(),
+			Source::Parachain(relay, para_id) => fmt.debug_tuple("Parachain").field(relay).field(para_id).finish(),
+			Source::Alias(alias) => fmt.debug_tuple("Alias").field(alias).finish(),
+			Source::URL(url) => fmt.debug_tuple("URL").field(url).finish(),
+			Source::Github(gh) => fmt.debug_tuple("Github").field(gh).finish(),
+		}
+	}
+}
+
 impl Display for Source {
 	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Source::File(file) => write!(fmt, "{file:?}"),
+			Source::ChainSpec(spec) => write!(fmt, "chain spec: {spec:?}"),
+			Source::Snapshot(snapshot) => write!(fmt, "snapshot: {snapshot:?}"),
+			Source::Bytes(bytes) => write!(fmt, "{} bytes", bytes.len()),
 			Source::Chain(chain) => write!(fmt, "chain: {chain:?}"),
+			Source::Parachain(relay, para_id) => write!(fmt, "para {para_id} on relay: {relay:?}"),
 			Source::Alias(alias) => write!(fmt, "alias: {alias:?}"),
 			Source::URL(url) => write!(fmt, "url: {url:?}"),
 			Source::Github(gh) => write!(fmt, "github: {gh}"),
@@ -209,6 +412,13 @@ mod tests_source {
 	use std::{env::temp_dir, fs::File};
 	use wasm_loader::NodeEndpoint;
 
+	#[test]
+	fn it_does_not_dump_the_bytes() {
+		let source = Source::Bytes(vec![0; 1024]);
+		assert_eq!(format!("{source:?}"), "Bytes(1024 bytes)");
+		assert_eq!(source.to_string(), "1024 bytes");
+	}
+
 	#[test]
 	fn it_converts_from_chain_ws() {
 		let urls = vec!["ws://localhost:9933", "wss://localhost:9933"];
@@ -259,6 +469,15 @@ mod tests_source {
 		}
 	}
 
+	#[test]
+	fn it_converts_from_chain_ipc() {
+		let src = Source::try_from("ipc:///var/run/node.sock").expect("Failing parsing source");
+		match src {
+			Source::Chain(r) => assert_eq!(r.endpoint.to_string(), "ipc:///var/run/node.sock"),
+			_ => unreachable!(),
+		}
+	}
+
 	#[test]
 	fn it_converts_from_alias() {
 		let names = vec!["polkadot", "dot"];
@@ -295,6 +514,19 @@ mod tests_source {
 		}
 	}
 
+	#[test]
+	fn it_converts_from_chain_spec() {
+		let mut path = temp_dir();
+		path.push("subwasm_fake_chain_spec.json");
+		std::fs::write(&path, r#"{ "genesis": { "raw": { "top": { "0x3a636f6465": "0x0061736d" } } } }"#)
+			.expect("We should be able to write a chain spec");
+
+		let src = Source::try_from(path.display().to_string().as_str()).expect("Failing parsing source");
+		assert_eq!(src, Source::ChainSpec(path.clone()));
+		let wasm = src.as_file().expect("Failed extracting the runtime");
+		assert_eq!(std::fs::read(wasm).expect("Failed reading the runtime"), vec![0x00, 0x61, 0x73, 0x6d]);
+	}
+
 	#[test]
 	fn it_catches_unknown() {
 		let v = vec!["foo", "bar"];
@@ -1,25 +1,46 @@
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use scale_info::scale::Decode;
+use sp_version::RuntimeVersion;
 use std::io::Write;
 use substrate_differ::differs::reduced::{reduced_runtime::ReducedRuntime, reduced_runtime_summary::*};
-use wasm_loader::Source;
-use wasm_testbed::{WasmTestBed, WasmTestbedError};
+use wasm_loader::{OnchainMetadata, RuntimeCache, Source};
+use wasm_testbed::{TestbedStorage, UpgradeChecks, WasmTestBed, WasmTestbedError};
 
 use crate::{
 	error,
 	error::*,
 	metadata_wrapper::{self, MetadataWrapper},
 	utils::print_big_output_safe,
-	RuntimeInfo,
+	ApiCallResult, ChainSpec, GenesisPresets, RuntimeApis, RuntimeInfo, TryUpgradeReport,
 };
 
 /// The main `subwasm` object
 pub struct Subwasm {
-	testbed: WasmTestBed,
-	runtime_info: RuntimeInfo,
+	runtime: Runtime,
+}
+
+/// What we know about the runtime
+enum Runtime {
+	/// The runtime itself, loaded in a testbed
+	Wasm { testbed: Box<WasmTestBed>, runtime_info: RuntimeInfo },
+
+	/// Only the metadata and version, as reported by a node
+	Metadata { metadata: RuntimeMetadataPrefixed, version: RuntimeVersion },
 }
 
 impl Subwasm {
 	pub fn new(source: &Source) -> Result<Self> {
-		let testbed = WasmTestBed::new(source).map_err(|e| {
+		Self::new_with_cache(source, None)
+	}
+
+	/// Same as [Subwasm::new] but using the given cache for runtimes fetched from a node
+	pub fn new_with_cache(source: &Source, cache: Option<&RuntimeCache>) -> Result<Self> {
+		let testbed = WasmTestBed::new_with_cache(source, cache).map_err(|e| {
+			// We could not even get the runtime, there is nothing worth reporting
+			if let WasmTestbedError::Loading(_, _) = e {
+				return SubwasmLibError::WasmTestbed(e);
+			}
+
 			eprintln!("{e}");
 			if let WasmTestbedError::Decoding(data) = e {
 				WasmTestBed::print_magic_and_version(&data);
@@ -34,11 +55,133 @@ impl Subwasm {
 			SubwasmLibError::Generic("Failed loading runtime".to_string())
 		})?;
 		let runtime_info = RuntimeInfo::new(&testbed)?;
-		Ok(Self { testbed, runtime_info })
+		Ok(Self { runtime: Runtime::Wasm { testbed: Box::new(testbed), runtime_info } })
+	}
+
+	/// Only fetch the metadata and version of the runtime from a node, without the runtime itself.
+	/// This is much faster but only the metadata related features are then available.
+	pub fn new_metadata_only(source: &Source) -> Result<Self> {
+		let Source::Chain(reference) = source else {
+			return Err(SubwasmLibError::MetadataOnlyUnsupported(source.to_string()));
+		};
+
+		let onchain = OnchainMetadata::fetch(reference)?;
+		log::debug!("Fetched the metadata at {} from {}", onchain.block_hash, onchain.endpoint);
+		Self::from_metadata(&onchain.metadata, onchain.runtime_version)
+	}
+
+	/// Build a metadata only [Subwasm] from the SCALE encoded `RuntimeMetadataPrefixed`
+	pub fn from_metadata(metadata: &[u8], version: RuntimeVersion) -> Result<Self> {
+		if metadata.len() < 5 || !WasmTestBed::is_substrate_wasm(&metadata.to_vec()) {
+			return Err(SubwasmLibError::NoMetadataFound());
+		}
+		if WasmTestBed::get_metadata_version(metadata) < 12 {
+			return Err(SubwasmLibError::UnsupportedRuntimeVersion());
+		}
+
+		let metadata = RuntimeMetadataPrefixed::decode(&mut &metadata[..])
+			.map_err(|e| SubwasmLibError::Parsing("metadata".to_string(), format!(" {e}")))?;
+		Ok(Self { runtime: Runtime::Metadata { metadata, version } })
+	}
+
+	/// Returns the [RuntimeInfo], which is not available when only the metadata was fetched
+	pub fn runtime_info(&self) -> Result<&RuntimeInfo> {
+		match &self.runtime {
+			Runtime::Wasm { runtime_info, .. } => Ok(runtime_info),
+			Runtime::Metadata { .. } => Err(SubwasmLibError::MetadataOnly()),
+		}
 	}
 
-	pub fn runtime_info(&self) -> &RuntimeInfo {
-		&self.runtime_info
+	/// Whether only the metadata of the runtime is available
+	pub fn is_metadata_only(&self) -> bool {
+		matches!(self.runtime, Runtime::Metadata { .. })
+	}
+
+	/// The version of the runtime
+	pub fn runtime_version(&self) -> RuntimeVersion {
+		match &self.runtime {
+			Runtime::Wasm { testbed, .. } => testbed.core_version(),
+			Runtime::Metadata { version, .. } => version.clone(),
+		}
+	}
+
+	fn runtime_metadata_prefixed(&self) -> &RuntimeMetadataPrefixed {
+		match &self.runtime {
+			Runtime::Wasm { testbed, .. } => testbed.runtime_metadata_prefixed(),
+			Runtime::Metadata { metadata, .. } => metadata,
+		}
+	}
+
+	fn metadata(&self) -> &RuntimeMetadata {
+		&self.runtime_metadata_prefixed().1
+	}
+
+	/// Call the runtime API `method`, such as `Core_version`, with the SCALE encoded `data`.
+	/// This requires the runtime itself. Without `storage`, the state is empty and only the APIs
+	/// that do not read it can succeed.
+	pub fn call_api(&self, method: &str, data: &[u8], storage: Option<&TestbedStorage>) -> Result<ApiCallResult> {
+		let testbed = self.testbed()?;
+		let output = match storage {
+			Some(storage) => testbed.call_api_with_storage(method, data, storage)?,
+			None => testbed.call_api(method, data)?,
+		};
+		let decoded = testbed.decode_api_output(method, &output)?;
+		Ok(ApiCallResult {
+			method: method.to_string(),
+			data: format!("0x{}", hex::encode(data)),
+			output: format!("0x{}", hex::encode(&output)),
+			decoded,
+		})
+	}
+
+	/// Run the upgrade to this runtime against `storage`, usually a state snapshot, as `try-runtime` does.
+	/// This requires a runtime built with the `try-runtime` feature.
+	pub fn try_upgrade(&self, storage: &TestbedStorage, checks: UpgradeChecks) -> Result<TryUpgradeReport> {
+		Ok(self.testbed()?.try_upgrade(storage, checks)?.into())
+	}
+
+	/// The genesis presets of the runtime, using its `GenesisBuilder` API
+	pub fn genesis_presets(&self) -> Result<GenesisPresets> {
+		Ok(GenesisPresets { presets: self.testbed()?.genesis_preset_names()? })
+	}
+
+	/// The genesis config of the runtime as JSON: the given `preset` or the default config
+	pub fn genesis_config(&self, preset: Option<&str>) -> Result<serde_json::Value> {
+		self.testbed()?
+			.genesis_preset(preset)?
+			.ok_or_else(|| SubwasmLibError::GenesisPresetNotFound(preset.unwrap_or_default().to_string()))
+	}
+
+	/// The skeleton of a plain chain spec embedding this runtime and its genesis config, see [Self::genesis_config]
+	pub fn chain_spec(&self, preset: Option<&str>) -> Result<serde_json::Value> {
+		let genesis = self.genesis_config(preset)?;
+		Ok(ChainSpec::skeleton(self.testbed()?.raw_bytes(), preset, genesis))
+	}
+
+	fn testbed(&self) -> Result<&WasmTestBed> {
+		match &self.runtime {
+			Runtime::Wasm { testbed, .. } => Ok(testbed),
+			Runtime::Metadata { .. } => Err(SubwasmLibError::MetadataOnly()),
+		}
+	}
+
+	/// The runtime APIs, as described by the V15 or V16 metadata of the runtime
+	pub fn runtime_apis(&self) -> Result<RuntimeApis> {
+		let fetched;
+		let metadata = match (self.metadata(), &self.runtime) {
+			(RuntimeMetadata::V16(metadata), _) => return Ok(RuntimeApis::from_v16(metadata, &self.runtime_version())),
+			(RuntimeMetadata::V15(metadata), _) => metadata,
+			(_, Runtime::Wasm { testbed, .. }) => {
+				fetched = testbed.metadata_at_version(15)?;
+				match &fetched {
+					Some(RuntimeMetadataPrefixed(_, RuntimeMetadata::V15(metadata))) => metadata,
+					_ => return Err(SubwasmLibError::NoRuntimeApis()),
+				}
+			}
+			_ => return Err(SubwasmLibError::NoRuntimeApis()),
+		};
+
+		Ok(RuntimeApis::new(metadata, &self.runtime_version()))
 	}
 
 	pub fn write_metadata<O: Write>(
@@ -47,13 +190,13 @@ impl Subwasm {
 		filter: Option<String>,
 		out: &mut O,
 	) -> error::Result<()> {
-		let metadata = self.testbed.runtime_metadata_prefixed();
+		let metadata = self.runtime_metadata_prefixed();
 		let wrapper = MetadataWrapper(metadata);
 		wrapper.write(fmt, filter, out)
 	}
 
 	pub fn display_reduced_runtime(&self, json: bool) -> error::Result<()> {
-		let reduced_runtime: ReducedRuntime = self.testbed.metadata().into();
+		let reduced_runtime: ReducedRuntime = self.metadata().into();
 
 		if json {
 			let serialized = serde_json::to_string_pretty(&reduced_runtime)?;
@@ -64,7 +207,7 @@ impl Subwasm {
 	}
 
 	pub fn display_reduced_pallet(&self, pallet: &str, json: bool) -> error::Result<()> {
-		let reduced_runtime: ReducedRuntime = self.testbed.metadata().into();
+		let reduced_runtime: ReducedRuntime = self.metadata().into();
 		let pallet_maybe = reduced_runtime.get_pallet_by_name(pallet);
 
 		if let Some(reduced_pallet) = pallet_maybe {
@@ -82,7 +225,7 @@ impl Subwasm {
 	}
 
 	pub fn display_reduced_summary(&self, json: bool) -> Result<()> {
-		let reduced_runtime: ReducedRuntime = self.testbed.metadata().into();
+		let reduced_runtime: ReducedRuntime = self.metadata().into();
 		let reduced_runtime_summary: ReducedRuntimeSummary = ReducedRuntimeSummary::from(&reduced_runtime);
 		if json {
 			let serialized = serde_json::to_string_pretty(&reduced_runtime_summary)?;
@@ -92,3 +235,109 @@ impl Subwasm {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests_subwasm {
+	use super::*;
+	use frame_metadata::v14::{ExtrinsicMetadata, PalletMetadata, PalletStorageMetadata, RuntimeMetadataV14};
+	use scale_info::{meta_type, scale::Encode};
+	use substrate_differ::differs::test_runtimes::{metadata_v15, metadata_v16};
+
+	fn metadata() -> Vec<u8> {
+		let pallet = PalletMetadata {
+			name: "System",
+			storage: Some(PalletStorageMetadata { prefix: "System", entries: vec![] }),
+			calls: None,
+			event: None,
+			constants: vec![],
+			error: None,
+			index: 0,
+		};
+		let extrinsic = ExtrinsicMetadata { ty: meta_type::<()>(), version: 4, signed_extensions: vec![] };
+		let metadata: RuntimeMetadataPrefixed =
+			RuntimeMetadataV14::new(vec![pallet], extrinsic, meta_type::<()>()).into();
+		metadata.encode()
+	}
+
+	#[test]
+	fn it_builds_from_metadata_only() {
+		let version = RuntimeVersion { spec_version: 42, ..Default::default() };
+		let subwasm = Subwasm::from_metadata(&metadata(), version).expect("Failed decoding metadata");
+
+		assert!(subwasm.is_metadata_only());
+		assert!(subwasm.runtime_info().is_err());
+		assert_eq!(subwasm.runtime_version().spec_version, 42);
+
+		let reduced: ReducedRuntime = subwasm.metadata().into();
+		assert!(reduced.get_pallet_by_name("System").is_some());
+	}
+
+	#[test]
+	fn it_supports_v15() {
+		let metadata: RuntimeMetadataPrefixed = metadata_v15(vec![]).into();
+		let subwasm =
+			Subwasm::from_metadata(&metadata.encode(), RuntimeVersion::default()).expect("Failed decoding metadata");
+
+		let mut out = Vec::new();
+		subwasm.write_metadata(metadata_wrapper::OutputFormat::Human, None, &mut out).expect("Failed writing metadata");
+		assert_eq!(String::from_utf8_lossy(&out), " - 00: System\n");
+
+		let mut out = Vec::new();
+		subwasm
+			.write_metadata(metadata_wrapper::OutputFormat::Human, Some("system".into()), &mut out)
+			.expect("Failed writing pallet");
+		assert!(String::from_utf8_lossy(&out).contains("Low-level operations of the runtime"));
+
+		let reduced: ReducedRuntime = subwasm.metadata().into();
+		assert!(reduced.outer_enums.is_some());
+		assert!(subwasm.runtime_apis().expect("Failed getting the runtime APIs").apis.is_empty());
+	}
+
+	#[test]
+	fn it_supports_v16() {
+		let metadata: RuntimeMetadataPrefixed = metadata_v16(&["free_balance"], true, vec![]).into();
+		let subwasm =
+			Subwasm::from_metadata(&metadata.encode(), RuntimeVersion::default()).expect("Failed decoding metadata");
+
+		let mut out = Vec::new();
+		subwasm.write_metadata(metadata_wrapper::OutputFormat::Human, None, &mut out).expect("Failed writing metadata");
+		assert_eq!(String::from_utf8_lossy(&out), " - 10: Balances\n");
+
+		let mut out = Vec::new();
+		subwasm
+			.write_metadata(metadata_wrapper::OutputFormat::Human, Some("balances".into()), &mut out)
+			.expect("Failed writing pallet");
+		assert!(String::from_utf8_lossy(&out).contains("👀 View functions:\n- free_balance\n"));
+
+		let reduced: ReducedRuntime = subwasm.metadata().into();
+		let balances = reduced.get_pallet_by_name("Balances").expect("Failed getting pallet by name");
+		assert!(balances.to_string().contains("Calls::Transfer is deprecated"));
+		assert!(subwasm.runtime_apis().expect("Failed getting the runtime APIs").apis.is_empty());
+	}
+
+	#[test]
+	fn it_requires_v15_for_the_runtime_apis() {
+		let subwasm = Subwasm::from_metadata(&metadata(), RuntimeVersion::default()).expect("Failed decoding metadata");
+		assert!(matches!(subwasm.runtime_apis(), Err(SubwasmLibError::NoRuntimeApis())));
+	}
+
+	#[test]
+	fn it_requires_the_runtime_for_the_genesis() {
+		let subwasm = Subwasm::from_metadata(&metadata(), RuntimeVersion::default()).expect("Failed decoding metadata");
+		assert!(matches!(subwasm.genesis_presets(), Err(SubwasmLibError::MetadataOnly())));
+		assert!(matches!(subwasm.chain_spec(None), Err(SubwasmLibError::MetadataOnly())));
+	}
+
+	#[test]
+	fn it_requires_the_runtime_to_try_the_upgrade() {
+		let subwasm = Subwasm::from_metadata(&metadata(), RuntimeVersion::default()).expect("Failed decoding metadata");
+		let storage = TestbedStorage::KeyValues(Default::default());
+		assert!(matches!(subwasm.try_upgrade(&storage, UpgradeChecks::All), Err(SubwasmLibError::MetadataOnly())));
+	}
+
+	#[test]
+	fn it_rejects_invalid_metadata() {
+		assert!(Subwasm::from_metadata(b"nope", RuntimeVersion::default()).is_err());
+		assert!(Subwasm::from_metadata(&metadata()[..10], RuntimeVersion::default()).is_err());
+	}
+}
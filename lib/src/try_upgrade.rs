@@ -0,0 +1,141 @@
+use crate::error::*;
+use serde::Serialize;
+use std::fmt::Display;
+use wasm_testbed::{TryUpgradeOutcome, Weight};
+
+/// A weight with both of its dimensions
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct UpgradeWeight {
+	pub ref_time: u64,
+	pub proof_size: u64,
+}
+
+impl From<Weight> for UpgradeWeight {
+	fn from(weight: Weight) -> Self {
+		Self { ref_time: weight.ref_time, proof_size: weight.proof_size }
+	}
+}
+
+/// A log line emitted by the runtime during the upgrade
+#[derive(Debug, Serialize)]
+pub struct UpgradeLog {
+	pub level: String,
+	pub target: String,
+	pub message: String,
+}
+
+/// The outcome of running the runtime upgrade against a state snapshot
+#[derive(Debug, Serialize)]
+pub struct TryUpgradeReport {
+	/// The weight used by the upgrade, if it succeeded
+	pub weight: Option<UpgradeWeight>,
+
+	/// The maximum weight of a block, if the upgrade succeeded
+	pub max_weight: Option<UpgradeWeight>,
+
+	/// Why the upgrade or its checks failed
+	pub error: Option<String>,
+
+	/// The logs of the runtime, up to the `info` level
+	pub logs: Vec<UpgradeLog>,
+}
+
+impl TryUpgradeReport {
+	/// Whether the upgrade and its checks succeeded
+	pub fn succeeded(&self) -> bool {
+		self.error.is_none()
+	}
+
+	/// Print the report either using the Display impl
+	/// or serde as json.
+	pub fn print(&self, json: bool) -> Result<()> {
+		if json {
+			let serialized = serde_json::to_string_pretty(self)?;
+			println!("{serialized}");
+		} else {
+			print!("{self}");
+		}
+		Ok(())
+	}
+}
+
+impl From<TryUpgradeOutcome> for TryUpgradeReport {
+	fn from(outcome: TryUpgradeOutcome) -> Self {
+		let logs = outcome
+			.logs
+			.into_iter()
+			.map(|log| UpgradeLog { level: log.level.to_string(), target: log.target, message: log.message })
+			.collect();
+
+		match outcome.result {
+			Ok((weight, max_weight)) => {
+				Self { weight: Some(weight.into()), max_weight: Some(max_weight.into()), error: None, logs }
+			}
+			Err(error) => Self { weight: None, max_weight: None, error: Some(error), logs },
+		}
+	}
+}
+
+impl Display for TryUpgradeReport {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for log in &self.logs {
+			writeln!(fmt, "{:<5} {}: {}", log.level, log.target, log.message)?;
+		}
+
+		match (&self.weight, &self.max_weight, &self.error) {
+			(_, _, Some(error)) => writeln!(fmt, "❌ The upgrade failed: {error}"),
+			(Some(weight), Some(max), None) => {
+				let percent = |used: u64, max: u64| if max == 0 { 0.0 } else { used as f64 * 100.0 / max as f64 };
+				writeln!(fmt, "✅ The upgrade succeeded")?;
+				writeln!(
+					fmt,
+					"ref_time:   {} / {} ({:.2}% of a block)",
+					weight.ref_time,
+					max.ref_time,
+					percent(weight.ref_time, max.ref_time)
+				)?;
+				writeln!(
+					fmt,
+					"proof_size: {} / {} ({:.2}% of a block)",
+					weight.proof_size,
+					max.proof_size,
+					percent(weight.proof_size, max.proof_size)
+				)
+			}
+			_ => writeln!(fmt, "✅ The upgrade succeeded"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_try_upgrade {
+	use super::*;
+	use wasm_testbed::RuntimeLog;
+
+	fn logs() -> Vec<RuntimeLog> {
+		vec![RuntimeLog { level: log::Level::Info, target: "runtime::balances".into(), message: "Migrated".into() }]
+	}
+
+	#[test]
+	fn it_reports_the_weight() {
+		let weight = Weight { ref_time: 250, proof_size: 10 };
+		let max = Weight { ref_time: 1_000, proof_size: 100 };
+		let report = TryUpgradeReport::from(TryUpgradeOutcome { result: Ok((weight, max)), logs: logs() });
+
+		assert!(report.succeeded());
+		assert_eq!(
+			report.to_string(),
+			"INFO  runtime::balances: Migrated\n✅ The upgrade succeeded\nref_time:   250 / 1000 (25.00% of a block)\nproof_size: 10 / 100 (10.00% of a block)\n"
+		);
+	}
+
+	#[test]
+	fn it_reports_failures() {
+		let report =
+			TryUpgradeReport::from(TryUpgradeOutcome { result: Err("post_upgrade failed".into()), logs: logs() });
+
+		assert!(!report.succeeded());
+		assert_eq!(report.weight, None);
+		assert!(report.to_string().ends_with("❌ The upgrade failed: post_upgrade failed\n"));
+	}
+}
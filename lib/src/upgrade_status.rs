@@ -0,0 +1,128 @@
+use crate::error::*;
+use serde::Serialize;
+use std::{fmt::Display, path::PathBuf};
+use wasm_loader::{OnchainBlock, Source, UpgradeAuthorization, UpgradeStatus};
+use wasm_testbed::WasmTestBed;
+
+/// How a local runtime relates to the upgrades found on chain
+#[derive(Debug, Serialize)]
+pub struct RuntimeMatch {
+	/// The runtime file we compared
+	file: PathBuf,
+
+	/// The blake2_256 hash of the runtime
+	blake2_256: String,
+
+	/// The storage items referring to this runtime
+	matches: Vec<&'static str>,
+}
+
+/// The pending and authorized upgrades of a chain and, optionally,
+/// whether a local runtime is the one that is authorized.
+#[derive(Debug, Serialize)]
+pub struct UpgradeStatusReport {
+	#[serde(flatten)]
+	status: UpgradeStatus,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
+	runtime: Option<RuntimeMatch>,
+}
+
+impl UpgradeStatusReport {
+	pub fn new(reference: &OnchainBlock, para_id: Option<u32>, file: Option<PathBuf>) -> Result<Self> {
+		let status = UpgradeStatus::fetch(reference, para_id)?;
+
+		let runtime = match file {
+			Some(file) => {
+				let testbed = WasmTestBed::new(&Source::File(file.clone()))?;
+				let blake2_256 = testbed.blake2_256_hash()?;
+				let matches = status.matches(&blake2_256);
+				Some(RuntimeMatch { file, blake2_256, matches })
+			}
+			None => None,
+		};
+
+		Ok(Self { status, runtime })
+	}
+
+	/// Returns true if the local runtime, if any, matches one of the pending or authorized upgrades
+	pub fn runtime_matches(&self) -> Option<bool> {
+		self.runtime.as_ref().map(|r| !r.matches.is_empty())
+	}
+
+	/// Print the report either using the Display impl
+	/// or serde as json.
+	pub fn print(&self, json: bool) -> Result<()> {
+		if json {
+			let serialized = serde_json::to_string_pretty(self)?;
+			println!("{serialized}");
+		} else {
+			println!("{self}");
+		}
+		Ok(())
+	}
+}
+
+impl Display for UpgradeStatusReport {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let width_emoji = 1;
+		let width_title = 36;
+		let none = String::from("-");
+		let authorization = |auth: &Option<UpgradeAuthorization>| match auth {
+			Some(UpgradeAuthorization { code_hash, check_version: Some(check) }) => {
+				format!("{code_hash} (check_version: {check})")
+			}
+			Some(UpgradeAuthorization { code_hash, check_version: None }) => code_hash.to_string(),
+			None => none.clone(),
+		};
+		let status = &self.status;
+
+		writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "🧱", "Block hash:", status.block_hash)?;
+		writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "🌐", "Endpoint:", status.endpoint)?;
+		writeln!(
+			fmt,
+			"{:<width_emoji$} {:<width_title$} {}",
+			"⏳",
+			":pending_code:",
+			status.pending_code_hash.as_ref().unwrap_or(&none)
+		)?;
+		writeln!(
+			fmt,
+			"{:<width_emoji$} {:<width_title$} {}",
+			"🗳️ ",
+			"System::AuthorizedUpgrade:",
+			authorization(&status.system_authorized_upgrade)
+		)?;
+		writeln!(
+			fmt,
+			"{:<width_emoji$} {:<width_title$} {}",
+			"🗳️ ",
+			"ParachainSystem::AuthorizedUpgrade:",
+			authorization(&status.parachain_system_authorized_upgrade)
+		)?;
+		if let Some(para_id) = status.para_id {
+			writeln!(
+				fmt,
+				"{:<width_emoji$} {:<width_title$} {}",
+				"🔗",
+				format!("Paras::FutureCodeHash({para_id}):"),
+				status.paras_future_code_hash.as_ref().unwrap_or(&none)
+			)?;
+		}
+
+		if let Some(runtime) = &self.runtime {
+			writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "📄", "Runtime:", runtime.file.display())?;
+			writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "#️⃣", "Blake2-256 hash:", runtime.blake2_256)?;
+			if runtime.matches.is_empty() {
+				writeln!(
+					fmt,
+					"{:<width_emoji$} {:<width_title$} None of the pending or authorized upgrades",
+					"❌", "Matches:"
+				)?;
+			} else {
+				writeln!(fmt, "{:<width_emoji$} {:<width_title$} {}", "✅", "Matches:", runtime.matches.join(", "))?;
+			}
+		}
+		Ok(())
+	}
+}
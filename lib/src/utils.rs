@@ -1,12 +1,17 @@
 use log::debug;
 use std::{
 	fs,
+	io::Read,
 	path::{Path, PathBuf},
 };
 use url::Url;
 use uuid::Uuid;
+use wasm_loader::{Compression, RuntimeCache};
 
-use crate::error::{self, *};
+use crate::{
+	error::{self, *},
+	DownloadOptions,
+};
 
 // TODO: Once the lined issue is fixed, we can remove the dependency on calm_io
 /// There is a bug caused by printing big output to stdout.
@@ -69,25 +74,57 @@ pub fn get_output_file_local(wish: Option<PathBuf>) -> PathBuf {
 /// into a file and provide the path back.
 /// If you provide None as `target`, a tmp file will be generated
 /// If you want to get a runtime from a RPC node, use `download_runtime`.
+/// The download is checked against the default [DownloadOptions].
 pub fn fetch_at_url(url: Url, target: Option<PathBuf>) -> Result<PathBuf> {
+	fetch_at_url_with(url, target, &DownloadOptions::default())
+}
+
+/// Same as [fetch_at_url] but the download is checked against the given [DownloadOptions].
+/// Nothing is written to `target` if the checks fail.
+pub fn fetch_at_url_with(url: Url, target: Option<PathBuf>, options: &DownloadOptions) -> Result<PathBuf> {
 	debug!("Fetching from {url}");
 	let target = if let Some(target) = target { target } else { get_output_file_tmp()? };
 
-	let mut resp = reqwest::blocking::get(url.to_owned())
+	let resp = reqwest::blocking::get(url.to_owned())
 		.map_err(|_e| error::SubwasmLibError::Generic("Request error".to_string()))?;
-	if resp.status().is_success() {
-		let mut out = std::fs::File::create(target.clone()).map_err(|_e| error::SubwasmLibError::Io)?;
-		resp.copy_to(&mut out).map_err(|_e| error::SubwasmLibError::Io)?;
-		Ok(target)
-	} else {
-		Err(SubwasmLibError::Generic(format!("Failed fetching url at {url}")))
+	if !resp.status().is_success() {
+		return Err(SubwasmLibError::Generic(format!("Failed fetching url at {url}")));
+	}
+
+	// No need to download what we would reject anyway
+	if resp.content_length().is_some_and(|length| length > options.max_size) {
+		return Err(SubwasmLibError::DownloadTooLarge(url, options.max_size));
 	}
+
+	// We read one more byte than allowed to tell whether the download is too large
+	let mut bytes = Vec::new();
+	resp.take(options.max_size.saturating_add(1)).read_to_end(&mut bytes).map_err(|_e| SubwasmLibError::Io)?;
+	options.check(&url, &bytes)?;
+
+	fs::write(&target, bytes).map_err(|_e| error::SubwasmLibError::Io)?;
+	Ok(target)
 }
 
-/// This helper is rather lose... If it is wrong, parsing the wasm
-/// will fail later.
-/// It tries fetching the content from a URL then does some guesswork to
-/// determine if what we got could be a wasm file.
+/// Same as [fetch_at_url_with] but the runtime is only downloaded if it is not in the cache yet.
+/// The path returned points into the cache, there is no need to clean it up.
+pub fn fetch_at_url_with_cache(url: Url, cache: &RuntimeCache, options: &DownloadOptions) -> Result<PathBuf> {
+	let key = RuntimeCache::url_key(url.as_str());
+	if let Some(path) = cache.get_path(&key) {
+		// What was published at the url may have changed since we cached it
+		match options.check(&url, &fs::read(&path)?) {
+			Ok(()) => return Ok(path),
+			Err(e) => log::warn!("Ignoring the cached runtime of {url}: {e}"),
+		}
+	}
+
+	let tmp = fetch_at_url_with(url, None, options)?;
+	let path = cache.insert(&key, &fs::read(&tmp)?)?;
+	let _ = fs::remove_file(tmp);
+	Ok(path)
+}
+
+/// Tells whether there seems to be a runtime at `url`, without downloading it all.
+/// Only the first bytes are read, to check that they look like a runtime.
 pub fn is_wasm_from_url(url: &Url) -> Result<bool> {
 	let resp = reqwest::blocking::get(url.to_owned()).map_err(|_e| error::SubwasmLibError::Io)?;
 
@@ -96,26 +133,9 @@ pub fn is_wasm_from_url(url: &Url) -> Result<bool> {
 		return Ok(false);
 	}
 
-	// We may not always get the length, but if we do, it can save us from
-	// having to fetch the bytes
-	if let Some(length) = resp.content_length() {
-		// We consider anything less than 500kb unlikely to be a valid runtime
-		debug!("The data we got from {url} is {length} bytes long");
-		return Ok(length >= 500_000);
-	}
-
-	let bytes = resp.bytes();
-	if bytes.is_err() {
-		return Ok(false);
-	}
-
-	if let Ok(data) = bytes {
-		if data.len() < 500_000 {
-			return Ok(false);
-		}
-
-		return Ok(true);
-	}
-
-	Err(SubwasmLibError::NoRuntimeAtUrl(url.to_owned()))
+	// The longest magic we look for is the prefix of compressed runtimes
+	let mut head = Vec::new();
+	resp.take(8).read_to_end(&mut head).map_err(|_e| SubwasmLibError::Io)?;
+	debug!("The data we got from {url} starts with {head:?}");
+	Ok(Compression::is_runtime(&head))
 }
@@ -42,7 +42,7 @@ impl IpfsHasher {
 			written += pushed;
 		}
 
-		let (cid, _data) = adder.finish().last().ok_or_else(|| IpfsHasherError::HashError())?;
+		let (cid, _data) = adder.finish().last().ok_or(IpfsHasherError::HashError())?;
 
 		Ok(cid.to_string())
 	}
@@ -51,7 +51,7 @@ impl IpfsHasher {
 #[cfg(test)]
 mod tests {
 	use super::IpfsHasher;
-	use wasm_loader::{OnchainBlock, Source, WasmLoader};
+	use wasm_loader::{BlockRef, OnchainBlock, Source, WasmLoader};
 
 	#[test]
 	fn it_works_with_single_block() {
@@ -72,7 +72,7 @@ mod tests {
 	fn it_computes_a_runtime_ipfs_hash() {
 		const POLKADOT_BLOCK20: &str = "0x4d6a0bca208b85d41833a7f35cf73d1ae6974f4bad8ab576e2c3f751d691fe6c"; // Polkadot Block #20
 
-		let ocb = OnchainBlock::new("wss://rpc.polkadot.io:443", Some(POLKADOT_BLOCK20.to_string()))
+		let ocb = OnchainBlock::new("wss://rpc.polkadot.io:443", Some(BlockRef::Hash(POLKADOT_BLOCK20.to_string())))
 			.expect("Failed getting onchain block");
 		let loader = WasmLoader::load_from_source(&Source::Chain(ocb)).expect("Failed loading wasm");
 		let hasher = IpfsHasher::default();
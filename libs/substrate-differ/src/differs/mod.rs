@@ -6,5 +6,5 @@ pub mod summary;
 
 pub mod utils;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "test-utils"))]
 pub mod test_runtimes;
@@ -0,0 +1,35 @@
+use super::prelude::*;
+use crate::differs::reduced::type_name::type_name;
+use comparable::Comparable;
+use frame_metadata::v16::PalletAssociatedTypeMetadata;
+use scale_info::PortableRegistry;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// Reduced associated type of the config of a pallet, as described starting with V16
+#[derive(Debug, Serialize, Hash, Comparable, PartialOrd, Ord, PartialEq, Eq, Clone)]
+pub struct AssociatedType {
+	pub name: String,
+
+	/// The type, as named in the runtime
+	pub ty: String,
+
+	#[comparable_ignore]
+	pub docs: Documentation,
+}
+
+impl AssociatedType {
+	pub fn from_v16(associated_type: &PalletAssociatedTypeMetadata<PortableForm>, registry: &PortableRegistry) -> Self {
+		Self {
+			name: associated_type.name.clone(),
+			ty: type_name(registry, associated_type.ty.id),
+			docs: associated_type.docs.clone(),
+		}
+	}
+}
+
+impl Display for AssociatedType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: {}", self.name, self.ty)
+	}
+}
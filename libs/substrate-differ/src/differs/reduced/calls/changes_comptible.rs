@@ -9,19 +9,21 @@ impl Compatible for ReducedPalletChange {
 			ReducedPalletChange::Index(_) => false,
 			ReducedPalletChange::Name(_) => false,
 
-			ReducedPalletChange::Calls(x) => x
-				.iter()
-				.map(|i| match i {
-					MapChange::Added(_k, _d) => true,
-					MapChange::Removed(_k) => false,
-					MapChange::Changed(_k, c) => c.iter().map(|cc| cc.compatible()).all(|x| x),
-				})
-				.all(|x| x),
+			ReducedPalletChange::Calls(x) => x.iter().all(|i| match i {
+				MapChange::Added(_k, _d) => true,
+				MapChange::Removed(_k) => false,
+				MapChange::Changed(_k, c) => c.iter().all(|cc| cc.compatible()),
+			}),
 			ReducedPalletChange::Events(_x) => true,
 			ReducedPalletChange::Errors(_x) => true,
 
 			ReducedPalletChange::Constants(_x) => true,
 			ReducedPalletChange::Storages(_x) => true,
+
+			// Clients break when a view function they query changes or goes away
+			ReducedPalletChange::ViewFunctions(x) => x.iter().all(|i| matches!(i, MapChange::Added(_k, _d))),
+			ReducedPalletChange::AssociatedTypes(_x) => true,
+			ReducedPalletChange::Deprecations(_x) => true,
 		};
 
 		trace!("Compat. | Pallet: {res}");
@@ -90,7 +92,7 @@ impl Compatible for StorageChange {
 
 impl Compatible for SignatureChange {
 	fn compatible(&self) -> bool {
-		let res = self.args.iter().map(|arg_changes| arg_changes.compatible()).all(|x| x);
+		let res = self.args.iter().all(|arg_changes| arg_changes.compatible());
 		trace!("Compat. | Signature: {res}");
 		res
 	}
@@ -110,7 +112,7 @@ impl Compatible for VecChange<ArgDesc, Vec<ArgChange>> {
 
 impl Compatible for Vec<ArgChange> {
 	fn compatible(&self) -> bool {
-		let res = self.iter().map(|c| c.compatible()).all(|x| x);
+		let res = self.iter().all(|c| c.compatible());
 		trace!("Compat. | Vec<ArgChange>: {res}");
 		res
 	}
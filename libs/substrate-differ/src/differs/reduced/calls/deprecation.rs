@@ -0,0 +1,92 @@
+use super::prelude::*;
+use comparable::Comparable;
+use frame_metadata::v16::{DeprecationInfo, DeprecationStatus};
+use serde::Serialize;
+use std::fmt::Display;
+
+/// Reduced deprecation of a pallet or one of its items, as described starting with V16
+#[derive(Debug, Serialize, Hash, Comparable, PartialOrd, Ord, PartialEq, Eq, Clone)]
+pub struct Deprecation {
+	/// What is deprecated, such as `Pallet`, `Calls` or `Storage::Account`
+	pub name: String,
+
+	/// Note explaining the deprecation
+	pub note: Option<String>,
+
+	/// Version since when the item is deprecated
+	pub since: Option<String>,
+}
+
+impl Deprecation {
+	/// Returns `None` if the item is not deprecated
+	pub fn from_v16(name: &str, status: &DeprecationStatus<PortableForm>) -> Option<Self> {
+		let name = name.to_string();
+		match status {
+			DeprecationStatus::NotDeprecated => None,
+			DeprecationStatus::DeprecatedWithoutNote => Some(Self { name, note: None, since: None }),
+			DeprecationStatus::Deprecated { note, since } => {
+				Some(Self { name, note: Some(note.clone()), since: since.clone() })
+			}
+		}
+	}
+
+	/// Deprecations of an enum such as the calls of a pallet. Either the whole enum is deprecated
+	/// or some of its variants, whose names are given by `variant_name`.
+	pub fn from_v16_info(
+		name: &str,
+		info: &DeprecationInfo<PortableForm>,
+		variant_name: impl Fn(u8) -> Option<String>,
+	) -> Vec<Self> {
+		match info {
+			DeprecationInfo::NotDeprecated => Vec::new(),
+			DeprecationInfo::ItemDeprecated(status) => Self::from_v16(name, status).into_iter().collect(),
+			DeprecationInfo::VariantsDeprecated(variants) => variants
+				.iter()
+				.filter_map(|(index, status)| {
+					let variant = variant_name(*index).unwrap_or_else(|| index.to_string());
+					Self::from_v16(&format!("{name}::{variant}"), status)
+				})
+				.collect(),
+		}
+	}
+}
+
+impl Display for Deprecation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} is deprecated", self.name)?;
+		if let Some(since) = &self.since {
+			write!(f, " since {since}")?;
+		}
+		if let Some(note) = &self.note {
+			write!(f, ": {note}")?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test_reduced_deprecation {
+	use super::*;
+	use std::collections::BTreeMap;
+
+	#[test]
+	fn test_deprecation() {
+		let status =
+			DeprecationStatus::Deprecated { note: "Use `transfer_allow_death`".into(), since: Some("1.2".into()) };
+		let deprecation = Deprecation::from_v16("Calls::transfer", &status).expect("Should be deprecated");
+		assert_eq!(deprecation.to_string(), "Calls::transfer is deprecated since 1.2: Use `transfer_allow_death`");
+
+		assert_eq!(Deprecation::from_v16("Pallet", &DeprecationStatus::NotDeprecated), None);
+	}
+
+	#[test]
+	fn test_deprecated_variants() {
+		let info = DeprecationInfo::VariantsDeprecated(BTreeMap::from([
+			(0, DeprecationStatus::DeprecatedWithoutNote),
+			(7, DeprecationStatus::DeprecatedWithoutNote),
+		]));
+		let deprecations = Deprecation::from_v16_info("Calls", &info, |index| (index == 0).then(|| "remark".into()));
+		let names: Vec<_> = deprecations.iter().map(|d| d.name.as_str()).collect();
+		assert_eq!(names, ["Calls::remark", "Calls::7"]);
+	}
+}
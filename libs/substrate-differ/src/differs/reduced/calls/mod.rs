@@ -1,19 +1,25 @@
+pub mod associated_type;
 pub mod call;
 pub mod changes_comptible;
 pub mod changes_require_txver_bump;
 pub mod constant;
+pub mod deprecation;
 pub mod error;
 pub mod event;
 pub mod prelude;
 pub mod signature;
 pub mod storage;
+pub mod view_function;
 
 mod displayable_vec;
 
+pub use associated_type::*;
 pub use call::*;
 pub use constant::*;
+pub use deprecation::*;
 pub use error::*;
 pub use event::*;
 pub use prelude::*;
 pub use signature::*;
 pub use storage::*;
+pub use view_function::*;
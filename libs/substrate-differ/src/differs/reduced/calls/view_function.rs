@@ -0,0 +1,48 @@
+use super::{prelude::*, signature::*};
+use crate::differs::reduced::type_name::type_name;
+use comparable::Comparable;
+use frame_metadata::v16::PalletViewFunctionMetadata;
+use scale_info::PortableRegistry;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// Reduced view function of a pallet, as described starting with V16
+#[derive(Debug, Serialize, Hash, Comparable, PartialOrd, Ord, PartialEq, Eq, Clone)]
+pub struct ViewFunction {
+	pub name: String,
+
+	/// The id used to query the view function
+	pub id: Value,
+	pub signature: Signature,
+
+	/// The type of the output, as named in the runtime
+	pub output: String,
+
+	#[comparable_ignore]
+	pub docs: Documentation,
+}
+
+impl ViewFunction {
+	pub fn from_v16(function: &PalletViewFunctionMetadata<PortableForm>, registry: &PortableRegistry) -> Self {
+		let args = function
+			.inputs
+			.iter()
+			.map(|input| Arg { name: input.name.clone(), ty: type_name(registry, input.ty.id) })
+			.collect();
+
+		Self {
+			name: function.name.clone(),
+			id: function.id.to_vec(),
+			signature: Signature { args },
+			output: type_name(registry, function.output.id),
+			docs: function.docs.clone(),
+		}
+	}
+}
+
+impl Display for ViewFunction {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let args = self.signature.args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+		write!(f, "{}({}) -> {}", self.name, args.join(", "), self.output)
+	}
+}
@@ -17,8 +17,11 @@ impl ChangedWrapper {
 			.changes
 			.iter()
 			.filter_map(|c| match c {
-				ReducedRuntimeChange::Extrinsic(_) => None,
 				ReducedRuntimeChange::Pallets(p) => Some(p),
+				ReducedRuntimeChange::Extrinsic(_)
+				| ReducedRuntimeChange::Apis(_)
+				| ReducedRuntimeChange::OuterEnums(_)
+				| ReducedRuntimeChange::Custom(_) => None,
 			})
 			.flatten()
 			.collect()
@@ -38,7 +41,10 @@ impl ChangedWrapper {
 			.changes
 			.iter()
 			.flat_map(|change| match change {
-				ReducedRuntimeChange::Extrinsic(_ex) => None,
+				ReducedRuntimeChange::Extrinsic(_)
+				| ReducedRuntimeChange::Apis(_)
+				| ReducedRuntimeChange::OuterEnums(_)
+				| ReducedRuntimeChange::Custom(_) => None,
 				ReducedRuntimeChange::Pallets(pallets) => pallets.iter().find(|&map_change| {
 					matches!(map_change,
 							MapChange::Added(id, _) |
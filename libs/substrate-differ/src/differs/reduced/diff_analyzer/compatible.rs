@@ -1,5 +1,6 @@
 use super::{traits::Compatible, DiffAnalyzer};
-use crate::differs::reduced::reduced_runtime::ReducedRuntimeChange;
+use crate::differs::reduced::{reduced_runtime::ReducedRuntimeChange, reduced_runtime_api::ReducedRuntimeApiChange};
+use comparable::MapChange;
 
 impl Compatible for DiffAnalyzer {
 	fn compatible(&self) -> bool {
@@ -7,28 +8,30 @@ impl Compatible for DiffAnalyzer {
 			return true;
 		}
 
-		self.changes
-			.0
-			.changes
-			.iter()
-			.map(|change| match change {
-				ReducedRuntimeChange::Pallets(pallets) => pallets
-					.iter()
-					.map(|p| match p {
-						comparable::MapChange::Added(_key, _desc) => true,
-						comparable::MapChange::Removed(_key) => false,
-						comparable::MapChange::Changed(_key, change) => {
-							change.iter().map(|x| x.compatible()).all(|x| x)
-						}
-					})
-					.all(|x| x),
-				ReducedRuntimeChange::Extrinsic(_extrinsic) => {
-					// TODO  todo!("Extrinsic diff not implemented yet and usually does not change")
+		self.changes.0.changes.iter().all(|change| match change {
+			ReducedRuntimeChange::Pallets(pallets) => pallets.iter().all(|p| match p {
+				comparable::MapChange::Added(_key, _desc) => true,
+				comparable::MapChange::Removed(_key) => false,
+				comparable::MapChange::Changed(_key, change) => change.iter().all(|x| x.compatible()),
+			}),
+			ReducedRuntimeChange::Extrinsic(_extrinsic) => {
+				// TODO  todo!("Extrinsic diff not implemented yet and usually does not change")
 
-					// Until implemented, we want this path to be transparent
-					true
-				}
-			})
-			.all(|x| x)
+				// Until implemented, we want this path to be transparent
+				true
+			}
+			// Clients break when a runtime API or a method they use changes or goes away
+			ReducedRuntimeChange::Apis(apis) => apis.iter().all(|api| match api {
+				MapChange::Added(_key, _desc) => true,
+				MapChange::Removed(_key) => false,
+				MapChange::Changed(_key, changes) => changes.iter().all(|change| match change {
+					ReducedRuntimeApiChange::Methods(methods) => {
+						methods.iter().all(|method| matches!(method, MapChange::Added(_, _)))
+					}
+					_ => false,
+				}),
+			}),
+			ReducedRuntimeChange::OuterEnums(_) | ReducedRuntimeChange::Custom(_) => true,
+		})
 	}
 }
@@ -21,6 +21,11 @@ pub mod reduced_runtime;
 pub mod reduced_runtime_change_wrapper;
 pub mod reduced_runtime_summary;
 
+pub mod reduced_custom;
+pub mod reduced_outer_enums;
+pub mod reduced_runtime_api;
+pub mod type_name;
+
 mod prelude;
 
 #[cfg(feature = "v13")]
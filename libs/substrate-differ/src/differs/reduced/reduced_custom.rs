@@ -0,0 +1,31 @@
+use super::{calls::prelude::*, type_name::type_name};
+use comparable::Comparable;
+use frame_metadata::{v15::CustomValueMetadata, v16};
+use scale_info::PortableRegistry;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// A value of the `custom` map of the metadata, as described starting with V15
+#[derive(Debug, PartialEq, Hash, Comparable, Serialize, Clone)]
+pub struct ReducedCustomValue {
+	pub name: String,
+	pub ty: String,
+	pub value: Value,
+}
+
+impl ReducedCustomValue {
+	pub fn from_v15(name: &str, custom: &CustomValueMetadata<PortableForm>, registry: &PortableRegistry) -> Self {
+		Self { name: name.to_string(), ty: type_name(registry, custom.ty.id), value: custom.value.clone() }
+	}
+
+	pub fn from_v16(name: &str, custom: &v16::CustomValueMetadata<PortableForm>, registry: &PortableRegistry) -> Self {
+		Self { name: name.to_string(), ty: type_name(registry, custom.ty.id), value: custom.value.clone() }
+	}
+}
+
+impl Display for ReducedCustomValue {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let displayable_value = DisplayableVec::new(&self.value, None).init().to_short_string();
+		write!(f, "{}: {} = {}", self.name, self.ty, displayable_value)
+	}
+}
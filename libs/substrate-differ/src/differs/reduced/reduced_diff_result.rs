@@ -102,3 +102,94 @@ impl Display for ReducedDiffResult {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+#[cfg(feature = "v15")]
+mod test_reduced_diff_result {
+	use super::*;
+	use crate::differs::test_runtimes::{core_api, metadata_v15};
+
+	fn diff(methods_a: &[&'static str], methods_b: &[&'static str]) -> ReducedDiffResult {
+		let ra = ReducedRuntime::from_v15(&metadata_v15(vec![core_api(methods_a)])).expect("Failed reducing runtime");
+		let rb = ReducedRuntime::from_v15(&metadata_v15(vec![core_api(methods_b)])).expect("Failed reducing runtime");
+		ReducedDiffResult::new(ra, rb)
+	}
+
+	#[test]
+	fn test_diff_runtime_apis() {
+		let unchanged = diff(&["version"], &["version"]);
+		assert!(unchanged.changes.is_none());
+
+		let added = diff(&["version"], &["version", "execute_block"]);
+		assert!(added.compatible());
+		assert!(!added.require_transaction_version_bump());
+		assert!(added.to_string().contains("[+] execute_block"));
+
+		let removed = diff(&["version", "execute_block"], &["version"]);
+		assert!(!removed.compatible());
+		assert!(removed.to_string().contains("[-] execute_block"));
+	}
+
+	#[test]
+	fn test_display_changed_items() {
+		let ra =
+			ReducedRuntime::from_v15(&metadata_v15(vec![core_api(&["version"])])).expect("Failed reducing runtime");
+		let mut rb =
+			ReducedRuntime::from_v15(&metadata_v15(vec![core_api(&["version"])])).expect("Failed reducing runtime");
+		rb.apis.get_mut("Core").unwrap().methods.get_mut("version").unwrap().output = String::from("u64");
+		rb.custom.get_mut("fee_asset").unwrap().value = vec![43, 0, 0, 0];
+		rb.outer_enums.as_mut().unwrap().event = String::from("RuntimeEvent");
+
+		let rendered = ReducedDiffResult::new(ra, rb).to_string();
+		assert!(rendered.contains("    [≠] version\n"));
+		assert!(rendered.contains("after : version(header: u32) -> u64"));
+		assert!(rendered.contains("[≠] custom value: fee_asset\n"));
+		assert!(rendered.contains("[≠] outer enums\n"));
+		assert!(rendered.contains("event: RuntimeEvent"));
+		assert!(!rendered.contains("Change("));
+	}
+
+	#[test]
+	fn test_display_extrinsic_changes() {
+		use crate::differs::reduced::reduced_extrinsic::ReducedSignedExtension;
+
+		let ra = ReducedRuntime::from_v15(&metadata_v15(vec![])).expect("Failed reducing runtime");
+		let mut rb = ReducedRuntime::from_v15(&metadata_v15(vec![])).expect("Failed reducing runtime");
+		rb.extrinsic.version = 5;
+		rb.extrinsic.signed_extensions.push(ReducedSignedExtension { identifier: String::from("CheckNonce") });
+		rb.extrinsic.extensions_by_version.insert(0, vec![String::from("CheckNonce")]);
+
+		let rendered = ReducedDiffResult::new(ra, rb).to_string();
+		assert!(rendered.contains("[≠] extrinsic version: 4 -> 5"));
+		assert!(rendered.contains("[≠] extrinsic signed extensions\n    [+] CheckNonce\n"));
+		assert!(rendered.contains("[+] extensions of extrinsic v0: CheckNonce"));
+		assert!(!rendered.contains("Change("));
+	}
+
+	#[test]
+	#[cfg(feature = "v16")]
+	fn test_diff_v16_pallets() {
+		use crate::differs::test_runtimes::metadata_v16;
+
+		let diff = |functions_a, deprecated_a, functions_b, deprecated_b| {
+			let ra = ReducedRuntime::from_v16(&metadata_v16(functions_a, deprecated_a, vec![]))
+				.expect("Failed reducing runtime");
+			let rb = ReducedRuntime::from_v16(&metadata_v16(functions_b, deprecated_b, vec![]))
+				.expect("Failed reducing runtime");
+			ReducedDiffResult::new(ra, rb)
+		};
+
+		let deprecated = diff(&["free_balance"], false, &["free_balance"], true);
+		assert!(deprecated.compatible());
+		assert!(deprecated.to_string().contains("deprecations changes"));
+
+		let added = diff(&["free_balance"], false, &["free_balance", "reserved_balance"], false);
+		assert!(added.compatible());
+		assert!(!added.require_transaction_version_bump());
+		assert!(added.to_string().contains("view_functions changes"));
+
+		let removed = diff(&["free_balance", "reserved_balance"], false, &["free_balance"], false);
+		assert!(!removed.compatible());
+		assert!(removed.to_string().contains("[-] \"reserved_balance\""));
+	}
+}
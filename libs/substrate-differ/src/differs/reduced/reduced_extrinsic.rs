@@ -1,12 +1,19 @@
 use comparable::Comparable;
-use frame_metadata::v14::{ExtrinsicMetadata, SignedExtensionMetadata};
+use frame_metadata::{
+	v14::{ExtrinsicMetadata, SignedExtensionMetadata},
+	v15, v16,
+};
 use scale_info::form::PortableForm;
 use serde::Serialize;
+use std::collections::BTreeMap;
 
 #[derive(Debug, PartialEq, Hash, Comparable, Serialize, Clone)]
 pub struct ReducedExtrinsic {
-	version: u8,
-	signed_extensions: Vec<ReducedSignedExtension>,
+	pub(crate) version: u8,
+	pub(crate) signed_extensions: Vec<ReducedSignedExtension>,
+
+	/// The identifiers of the transaction extensions used by each extrinsic version, only described starting with V16
+	pub(crate) extensions_by_version: BTreeMap<u8, Vec<String>>,
 }
 
 impl ReducedExtrinsic {
@@ -14,14 +21,51 @@ impl ReducedExtrinsic {
 		let version = extrinsic.version;
 		let signed_extensions = extrinsic.signed_extensions.iter().map(ReducedSignedExtension::from).collect();
 
-		Self { version, signed_extensions }
+		Self { version, signed_extensions, extensions_by_version: BTreeMap::new() }
+	}
+
+	pub fn from_v15(extrinsic: &v15::ExtrinsicMetadata<PortableForm>) -> Self {
+		let version = extrinsic.version;
+		let signed_extensions = extrinsic
+			.signed_extensions
+			.iter()
+			.map(|e| ReducedSignedExtension { identifier: e.identifier.clone() })
+			.collect();
+
+		Self { version, signed_extensions, extensions_by_version: BTreeMap::new() }
+	}
+
+	/// V16 supports several extrinsic versions, `version` is the latest one
+	pub fn from_v16(extrinsic: &v16::ExtrinsicMetadata<PortableForm>) -> Self {
+		let version = extrinsic.versions.iter().copied().max().unwrap_or_default();
+		let signed_extensions = extrinsic
+			.transaction_extensions
+			.iter()
+			.map(|e| ReducedSignedExtension { identifier: e.identifier.clone() })
+			.collect();
+		let extensions_by_version = extrinsic
+			.transaction_extensions_by_version
+			.iter()
+			.map(|(version, indexes)| {
+				let identifiers = indexes
+					.iter()
+					.map(|index| match extrinsic.transaction_extensions.get(*index as usize) {
+						Some(extension) => extension.identifier.clone(),
+						None => index.to_string(),
+					})
+					.collect();
+				(*version, identifiers)
+			})
+			.collect();
+
+		Self { version, signed_extensions, extensions_by_version }
 	}
 }
 
 // TODO:
 #[derive(Debug, PartialEq, Hash, Comparable, Serialize, Clone)]
 pub struct ReducedSignedExtension {
-	identifier: String,
+	pub(crate) identifier: String,
 	// additional_signed: Unknown,
 	// type: ?
 }
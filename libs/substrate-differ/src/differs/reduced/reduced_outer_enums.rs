@@ -0,0 +1,38 @@
+use super::{calls::prelude::*, type_name::type_name};
+use comparable::Comparable;
+use frame_metadata::{v15::OuterEnums, v16};
+use scale_info::PortableRegistry;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// The types of the enums gathering the calls, events and errors of all pallets, as described starting with V15
+#[derive(Debug, PartialEq, Hash, Comparable, Serialize, Clone)]
+pub struct ReducedOuterEnums {
+	pub call: String,
+	pub event: String,
+	pub error: String,
+}
+
+impl ReducedOuterEnums {
+	pub fn from_v15(outer_enums: &OuterEnums<PortableForm>, registry: &PortableRegistry) -> Self {
+		Self {
+			call: type_name(registry, outer_enums.call_enum_ty.id),
+			event: type_name(registry, outer_enums.event_enum_ty.id),
+			error: type_name(registry, outer_enums.error_enum_ty.id),
+		}
+	}
+
+	pub fn from_v16(outer_enums: &v16::OuterEnums<PortableForm>, registry: &PortableRegistry) -> Self {
+		Self {
+			call: type_name(registry, outer_enums.call_enum_ty.id),
+			event: type_name(registry, outer_enums.event_enum_ty.id),
+			error: type_name(registry, outer_enums.error_enum_ty.id),
+		}
+	}
+}
+
+impl Display for ReducedOuterEnums {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "call: {}, event: {}, error: {}", self.call, self.event, self.error)
+	}
+}
@@ -21,6 +21,19 @@ pub struct ReducedPallet {
 
 	pub constants: BTreeMap<String, Constant>,
 	pub storages: BTreeMap<String, Storage>,
+
+	/// View functions, only described starting with V16
+	pub view_functions: BTreeMap<String, ViewFunction>,
+
+	/// Associated types of the config of the pallet, only described starting with V16
+	pub associated_types: BTreeMap<String, AssociatedType>,
+
+	/// The pallet and the items that are deprecated, only described starting with V16
+	pub deprecations: BTreeMap<String, Deprecation>,
+
+	/// Documentation of the pallet, only available starting with V15
+	#[comparable_ignore]
+	pub docs: Documentation,
 }
 
 impl PartialOrd for ReducedPallet {
@@ -49,6 +62,9 @@ impl Display for ReducedPallet {
 		display_pallet_items!(self, f, errors);
 		display_pallet_items!(self, f, constants);
 		display_pallet_items!(self, f, storages);
+		display_pallet_items!(self, f, view_functions);
+		display_pallet_items!(self, f, associated_types);
+		display_pallet_items!(self, f, deprecations);
 		Ok(())
 	}
 }
@@ -72,6 +88,10 @@ impl Default for ReducedPallet {
 			errors: BTreeMap::new(),
 			constants: BTreeMap::new(),
 			storages: BTreeMap::new(),
+			view_functions: BTreeMap::new(),
+			associated_types: BTreeMap::new(),
+			deprecations: BTreeMap::new(),
+			docs: Vec::new(),
 		}
 	}
 }
@@ -83,6 +103,10 @@ pub enum PalletItemType {
 
 	Constant,
 	Storage,
+
+	ViewFunction,
+	AssociatedType,
+	Deprecation,
 }
 
 impl Display for PalletItemType {
@@ -93,6 +117,9 @@ impl Display for PalletItemType {
 			PalletItemType::Error => f.write_str("Error"),
 			PalletItemType::Constant => f.write_str("Constant"),
 			PalletItemType::Storage => f.write_str("Storage"),
+			PalletItemType::ViewFunction => f.write_str("View function"),
+			PalletItemType::AssociatedType => f.write_str("Associated type"),
+			PalletItemType::Deprecation => f.write_str("Deprecation"),
 		}
 	}
 }
@@ -14,6 +14,9 @@ impl ReducedPalletChange {
 				ReducedPalletChange::Errors(_) => matches!(what, PalletItemType::Error),
 				ReducedPalletChange::Constants(_) => matches!(what, PalletItemType::Constant),
 				ReducedPalletChange::Storages(_) => matches!(what, PalletItemType::Storage),
+				ReducedPalletChange::ViewFunctions(_) => matches!(what, PalletItemType::ViewFunction),
+				ReducedPalletChange::AssociatedTypes(_) => matches!(what, PalletItemType::AssociatedType),
+				ReducedPalletChange::Deprecations(_) => matches!(what, PalletItemType::Deprecation),
 				_ => unreachable!(),
 			})
 			.collect()
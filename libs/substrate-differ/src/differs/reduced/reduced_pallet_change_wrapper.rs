@@ -88,6 +88,10 @@ impl<'a> Display for ReducedPalletChangeWrapper<'a> {
 
 			ReducedPalletChange::Constants(c) => fmt_vec_changes!(self, f, constants, c),
 			ReducedPalletChange::Storages(c) => fmt_vec_changes!(self, f, storages, c),
+
+			ReducedPalletChange::ViewFunctions(c) => fmt_vec_changes!(self, f, view_functions, c),
+			ReducedPalletChange::AssociatedTypes(c) => fmt_vec_changes!(self, f, associated_types, c),
+			ReducedPalletChange::Deprecations(c) => fmt_vec_changes!(self, f, deprecations, c),
 		}
 	}
 }
@@ -1,16 +1,21 @@
 use super::{
 	calls::{call::Call, error::Error, event::Event, prelude::PalletId},
+	reduced_custom::ReducedCustomValue,
 	reduced_extrinsic::ReducedExtrinsic,
+	reduced_outer_enums::ReducedOuterEnums,
 	reduced_pallet::ReducedPallet,
+	reduced_runtime_api::ReducedRuntimeApi,
 };
 use crate::differs::reduced::calls::{
-	call::variant_to_calls, constant::Constant, error::variant_to_errors, event::variant_to_events, storage::*,
+	associated_type::AssociatedType, call::variant_to_calls, constant::Constant, deprecation::Deprecation,
+	error::variant_to_errors, event::variant_to_events, storage::*, view_function::ViewFunction,
 };
 use crate::error::*;
 use comparable::Comparable;
 use frame_metadata::{
 	v14,
 	v14::PalletMetadata,
+	v15, v16,
 	RuntimeMetadata::{self, *},
 };
 use scale_info::{form::PortableForm, PortableRegistry};
@@ -28,11 +33,20 @@ pub type Result<T> = core::result::Result<T, ReducedRuntimeError>;
 pub struct ReducedRuntime {
 	pub extrinsic: ReducedExtrinsic,
 	pub pallets: HashMap<PalletId, ReducedPallet>,
+
+	/// The runtime APIs, only described starting with V15
+	pub apis: BTreeMap<String, ReducedRuntimeApi>,
+
+	/// The outer enums, only described starting with V15
+	pub outer_enums: Option<ReducedOuterEnums>,
+
+	/// The `custom` map of the metadata, only available starting with V15
+	pub custom: BTreeMap<String, ReducedCustomValue>,
 }
 
 impl ReducedRuntime {
 	pub fn new(extrinsic: ReducedExtrinsic, pallets: HashMap<PalletId, ReducedPallet>) -> Self {
-		Self { extrinsic, pallets }
+		Self { extrinsic, pallets, apis: BTreeMap::new(), outer_enums: None, custom: BTreeMap::new() }
 	}
 
 	#[cfg(feature = "v13")]
@@ -142,7 +156,19 @@ impl ReducedRuntime {
 			.map(|i| (i.name.clone(), Constant::new(&i.name, i.value.clone(), i.docs.clone())))
 			.collect();
 
-		Ok(ReducedPallet { index: p.index.into(), name: name.into(), calls, events, errors, constants, storages })
+		Ok(ReducedPallet {
+			index: p.index.into(),
+			name: name.into(),
+			calls,
+			events,
+			errors,
+			constants,
+			storages,
+			view_functions: BTreeMap::new(),
+			associated_types: BTreeMap::new(),
+			deprecations: BTreeMap::new(),
+			docs: Vec::new(),
+		})
 	}
 
 	#[cfg(feature = "v14")]
@@ -176,6 +202,164 @@ impl ReducedRuntime {
 		Ok(r_rtm)
 	}
 
+	#[cfg(feature = "v15")]
+	/// Reduce a RuntimeMetadataV15 into a normalized ReducedRuntime
+	pub fn from_v15(v15: &v15::RuntimeMetadataV15) -> Result<Self> {
+		let registry = &v15.types;
+
+		let reduced_pallets = v15
+			.pallets
+			.iter()
+			.map(|p| {
+				// Besides the docs, V15 pallets are made of the same items as in V14
+				let v14_pallet = PalletMetadata {
+					name: p.name.clone(),
+					storage: p.storage.clone(),
+					calls: p.calls.clone(),
+					event: p.event.clone(),
+					constants: p.constants.clone(),
+					error: p.error.clone(),
+					index: p.index,
+				};
+				let reduced_pallet = ReducedRuntime::get_reduced_pallet_from_v14_pallet(&v14_pallet, registry)
+					.map_err(|_| SubstrateDifferError::RegistryError("pallet".to_string(), p.index as u32))?;
+				Ok((reduced_pallet.index, ReducedPallet { docs: p.docs.clone(), ..reduced_pallet }))
+			})
+			.collect::<crate::error::Result<HashMap<PalletId, ReducedPallet>>>()?;
+
+		let apis = v15.apis.iter().map(|api| (api.name.clone(), ReducedRuntimeApi::from_v15(api, registry))).collect();
+		let custom = v15
+			.custom
+			.map
+			.iter()
+			.map(|(name, value)| (name.clone(), ReducedCustomValue::from_v15(name, value, registry)))
+			.collect();
+
+		Ok(Self {
+			extrinsic: ReducedExtrinsic::from_v15(&v15.extrinsic),
+			pallets: reduced_pallets,
+			apis,
+			outer_enums: Some(ReducedOuterEnums::from_v15(&v15.outer_enums, registry)),
+			custom,
+		})
+	}
+
+	#[cfg(feature = "v16")]
+	/// Reduce a RuntimeMetadataV16 into a normalized ReducedRuntime
+	pub fn from_v16(v16: &v16::RuntimeMetadataV16) -> Result<Self> {
+		let registry = &v16.types;
+
+		let reduced_pallets = v16
+			.pallets
+			.iter()
+			.map(|p| {
+				let reduced_pallet = ReducedRuntime::get_reduced_pallet_from_v16_pallet(p, registry)
+					.map_err(|_| SubstrateDifferError::RegistryError("pallet".to_string(), p.index as u32))?;
+				Ok((reduced_pallet.index, reduced_pallet))
+			})
+			.collect::<crate::error::Result<HashMap<PalletId, ReducedPallet>>>()?;
+
+		let apis = v16.apis.iter().map(|api| (api.name.clone(), ReducedRuntimeApi::from_v16(api, registry))).collect();
+		let custom = v16
+			.custom
+			.map
+			.iter()
+			.map(|(name, value)| (name.clone(), ReducedCustomValue::from_v16(name, value, registry)))
+			.collect();
+
+		Ok(Self {
+			extrinsic: ReducedExtrinsic::from_v16(&v16.extrinsic),
+			pallets: reduced_pallets,
+			apis,
+			outer_enums: Some(ReducedOuterEnums::from_v16(&v16.outer_enums, registry)),
+			custom,
+		})
+	}
+
+	#[cfg(feature = "v16")]
+	pub fn get_reduced_pallet_from_v16_pallet(
+		p: &v16::PalletMetadata<PortableForm>,
+		registry: &PortableRegistry,
+	) -> crate::error::Result<ReducedPallet> {
+		// Besides the docs and the items introduced in V16, pallets are made of the same items as in V14
+		let v14_pallet = PalletMetadata {
+			name: p.name.clone(),
+			storage: p.storage.as_ref().map(|storage| v14::PalletStorageMetadata {
+				prefix: storage.prefix.clone(),
+				entries: storage
+					.entries
+					.iter()
+					.map(|e| v14::StorageEntryMetadata {
+						name: e.name.clone(),
+						modifier: e.modifier.clone(),
+						ty: e.ty.clone(),
+						default: e.default.clone(),
+						docs: e.docs.clone(),
+					})
+					.collect(),
+			}),
+			calls: p.calls.as_ref().map(|calls| v14::PalletCallMetadata { ty: calls.ty }),
+			event: p.event.as_ref().map(|event| v14::PalletEventMetadata { ty: event.ty }),
+			constants: p
+				.constants
+				.iter()
+				.map(|c| v14::PalletConstantMetadata {
+					name: c.name.clone(),
+					ty: c.ty,
+					value: c.value.clone(),
+					docs: c.docs.clone(),
+				})
+				.collect(),
+			error: p.error.as_ref().map(|error| v14::PalletErrorMetadata { ty: error.ty }),
+			index: p.index,
+		};
+		let reduced_pallet = ReducedRuntime::get_reduced_pallet_from_v14_pallet(&v14_pallet, registry)?;
+
+		let view_functions =
+			p.view_functions.iter().map(|f| (f.name.clone(), ViewFunction::from_v16(f, registry))).collect();
+		let associated_types =
+			p.associated_types.iter().map(|t| (t.name.clone(), AssociatedType::from_v16(t, registry))).collect();
+
+		let mut deprecations = Vec::new();
+		deprecations.extend(Deprecation::from_v16("Pallet", &p.deprecation_info));
+		if let Some(calls) = &p.calls {
+			deprecations.extend(Deprecation::from_v16_info("Calls", &calls.deprecation_info, |index| {
+				reduced_pallet.calls.get(&index.into()).map(|call| call.name.clone())
+			}));
+		}
+		if let Some(event) = &p.event {
+			deprecations.extend(Deprecation::from_v16_info("Events", &event.deprecation_info, |index| {
+				reduced_pallet.events.get(&index.into()).map(|event| event.name.clone())
+			}));
+		}
+		if let Some(error) = &p.error {
+			deprecations.extend(Deprecation::from_v16_info("Errors", &error.deprecation_info, |index| {
+				reduced_pallet.errors.get(&index.into()).map(|error| error.name.clone())
+			}));
+		}
+		if let Some(storage) = &p.storage {
+			deprecations.extend(
+				storage
+					.entries
+					.iter()
+					.filter_map(|e| Deprecation::from_v16(&format!("Storage::{}", e.name), &e.deprecation_info)),
+			);
+		}
+		deprecations.extend(
+			p.constants
+				.iter()
+				.filter_map(|c| Deprecation::from_v16(&format!("Constants::{}", c.name), &c.deprecation_info)),
+		);
+		deprecations.extend(
+			p.view_functions
+				.iter()
+				.filter_map(|f| Deprecation::from_v16(&format!("ViewFunctions::{}", f.name), &f.deprecation_info)),
+		);
+		let deprecations = deprecations.into_iter().map(|d| (d.name.clone(), d)).collect();
+
+		Ok(ReducedPallet { view_functions, associated_types, deprecations, docs: p.docs.clone(), ..reduced_pallet })
+	}
+
 	/// Prefer using the more efficient [get_pallet_by_id](Self::get_pallet_by_id) if you can.
 	pub fn get_pallet_by_name(&self, pallet_name: &str) -> Option<&ReducedPallet> {
 		self.pallets
@@ -197,6 +381,10 @@ impl From<&RuntimeMetadata> for ReducedRuntime {
 			V13(v13) => ReducedRuntime::from_v13(v13).expect("Failed reducing runtime from V13"),
 			#[cfg(feature = "v14")]
 			V14(v14) => ReducedRuntime::from_v14(v14).expect("Failed reducing runtime from V14"),
+			#[cfg(feature = "v15")]
+			V15(v15) => ReducedRuntime::from_v15(v15).expect("Failed reducing runtime from V15"),
+			#[cfg(feature = "v16")]
+			V16(v16) => ReducedRuntime::from_v16(v16).expect("Failed reducing runtime from V16"),
 			_ => panic!("Unsupported metadata version"),
 		}
 	}
@@ -210,6 +398,21 @@ impl Display for ReducedRuntime {
 			let _ = writeln!(f, "{pallet}");
 		});
 
+		self.apis.values().for_each(|api| {
+			let _ = writeln!(f, "{api}");
+		});
+
+		if let Some(outer_enums) = &self.outer_enums {
+			let _ = writeln!(f, "Outer enums: {outer_enums}");
+		}
+
+		if !self.custom.is_empty() {
+			let _ = writeln!(f, "Custom:");
+			self.custom.values().for_each(|value| {
+				let _ = writeln!(f, "  - {value}");
+			});
+		}
+
 		Ok(())
 	}
 }
@@ -217,7 +420,44 @@ impl Display for ReducedRuntime {
 #[cfg(test)]
 mod test_reduced_runtime {
 	use super::*;
-	use crate::differs::test_runtimes::{Chain, RuntimeFile};
+	use crate::differs::test_runtimes::{core_api, metadata_v15, metadata_v16, Chain, RuntimeFile};
+
+	#[test]
+	#[cfg(feature = "v15")]
+	fn test_reduce_runtime_v15() {
+		let reduced_runtime =
+			ReducedRuntime::from_v15(&metadata_v15(vec![core_api(&["version"])])).expect("Failed reducing runtime");
+
+		let system = reduced_runtime.get_pallet_by_name("System").expect("Failed getting pallet by name");
+		assert_eq!(system.docs, ["Low-level operations of the runtime"]);
+		assert_eq!(system.calls[&0].name, "Remark");
+
+		let core = &reduced_runtime.apis["Core"];
+		assert_eq!(core.methods["version"].to_string(), "version(header: u32) -> Option<Vec<u8>>");
+		assert_eq!(reduced_runtime.outer_enums.as_ref().map(|e| e.call.as_str()), Some("Call"));
+		assert_eq!(reduced_runtime.custom["fee_asset"].to_string(), "fee_asset: u32 = [42, 0, 0, 0]");
+	}
+
+	#[test]
+	#[cfg(feature = "v16")]
+	fn test_reduce_runtime_v16() {
+		let reduced_runtime =
+			ReducedRuntime::from_v16(&metadata_v16(&["free_balance"], true, vec![])).expect("Failed reducing runtime");
+
+		let balances = reduced_runtime.get_pallet_by_name("Balances").expect("Failed getting pallet by name");
+		assert_eq!(balances.calls[&1].name, "TransferAllowDeath");
+		assert_eq!(balances.view_functions["free_balance"].to_string(), "free_balance(who: u32) -> u128");
+		assert_eq!(balances.associated_types["Balance"].to_string(), "Balance: u128");
+		assert_eq!(
+			balances.deprecations["Calls::Transfer"].to_string(),
+			"Calls::Transfer is deprecated: Use `transfer_allow_death`"
+		);
+		assert_eq!(balances.deprecations.len(), 1);
+
+		let extrinsic = serde_json::to_value(&reduced_runtime.extrinsic).expect("Failed serializing extrinsic");
+		assert_eq!(extrinsic["version"], 5);
+		assert_eq!(extrinsic["extensions_by_version"]["5"], serde_json::json!(["CheckWeight"]));
+	}
 
 	#[test]
 	#[cfg(feature = "v14")]
@@ -0,0 +1,89 @@
+use super::{
+	calls::{prelude::*, Arg, Signature},
+	type_name::type_name,
+};
+use comparable::Comparable;
+use frame_metadata::{v15::RuntimeApiMetadata, v16};
+use scale_info::PortableRegistry;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Display};
+
+/// A reduced runtime API, as described starting with V15
+#[derive(Debug, PartialEq, Hash, Comparable, Serialize, Clone)]
+pub struct ReducedRuntimeApi {
+	pub name: String,
+	pub methods: BTreeMap<String, ReducedRuntimeApiMethod>,
+
+	#[comparable_ignore]
+	pub docs: Documentation,
+}
+
+/// A method of a [ReducedRuntimeApi], the types are named as in the runtime
+#[derive(Debug, PartialEq, Hash, Comparable, Serialize, Clone)]
+pub struct ReducedRuntimeApiMethod {
+	pub name: String,
+	pub signature: Signature,
+	pub output: String,
+
+	#[comparable_ignore]
+	pub docs: Documentation,
+}
+
+impl ReducedRuntimeApi {
+	pub fn from_v15(api: &RuntimeApiMetadata<PortableForm>, registry: &PortableRegistry) -> Self {
+		let methods = api
+			.methods
+			.iter()
+			.map(|m| {
+				let args =
+					m.inputs.iter().map(|i| Arg { name: i.name.clone(), ty: type_name(registry, i.ty.id) }).collect();
+				let method = ReducedRuntimeApiMethod {
+					name: m.name.clone(),
+					signature: Signature { args },
+					output: type_name(registry, m.output.id),
+					docs: m.docs.clone(),
+				};
+				(m.name.clone(), method)
+			})
+			.collect();
+
+		Self { name: api.name.clone(), methods, docs: api.docs.clone() }
+	}
+
+	pub fn from_v16(api: &v16::RuntimeApiMetadata<PortableForm>, registry: &PortableRegistry) -> Self {
+		// Besides the deprecations and the version, V16 APIs are described as in V15
+		let v15_api = RuntimeApiMetadata {
+			name: api.name.clone(),
+			methods: api
+				.methods
+				.iter()
+				.map(|m| frame_metadata::v15::RuntimeApiMethodMetadata {
+					name: m.name.clone(),
+					inputs: m
+						.inputs
+						.iter()
+						.map(|i| frame_metadata::v15::RuntimeApiMethodParamMetadata { name: i.name.clone(), ty: i.ty })
+						.collect(),
+					output: m.output,
+					docs: m.docs.clone(),
+				})
+				.collect(),
+			docs: api.docs.clone(),
+		};
+		Self::from_v15(&v15_api, registry)
+	}
+}
+
+impl Display for ReducedRuntimeApi {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "Runtime API: {}", self.name)?;
+		self.methods.values().try_for_each(|method| writeln!(f, "    - {method}"))
+	}
+}
+
+impl Display for ReducedRuntimeApiMethod {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let args = self.signature.args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+		write!(f, "{}({}) -> {}", self.name, args.join(", "), self.output)
+	}
+}
@@ -1,8 +1,13 @@
 use super::{
-	reduced_pallet::*, reduced_pallet_change_wrapper::ReducedPalletChangeWrapper, reduced_runtime::*, ComparisonSide,
+	reduced_extrinsic::{ReducedExtrinsic, ReducedExtrinsicChange},
+	reduced_pallet::*,
+	reduced_pallet_change_wrapper::ReducedPalletChangeWrapper,
+	reduced_runtime::*,
+	reduced_runtime_api::ReducedRuntimeApiChange,
+	ComparisonSide,
 };
 use crate::differs::reduced::calls::PalletId;
-use comparable::MapChange;
+use comparable::{MapChange, StringChange};
 use serde::Serialize;
 use std::{fmt::Display, rc::Rc};
 
@@ -39,11 +44,72 @@ impl ReducedRuntimeChangeWrapper {
 	/// There are cases where it will return `None`. For instance, requesting the Left
 	/// pallet with `id=N` when pallet `N` was introduced first in the Right runtime.
 	fn get_pallet(&self, id: &PalletId, side: ComparisonSide) -> Option<&ReducedPallet> {
-		let reduced_runtime = match side {
+		self.get_runtime(side).pallets.get(id)
+	}
+
+	fn get_runtime(&self, side: ComparisonSide) -> &ReducedRuntime {
+		match side {
 			ComparisonSide::Left => &self.runtime_a,
 			ComparisonSide::Right => &self.runtime_b,
-		};
-		reduced_runtime.pallets.get(id)
+		}
+	}
+
+	/// Show the signed extensions that were added or removed rather than
+	/// their position in the list, which is what [ReducedExtrinsicChange] describes.
+	fn fmt_extrinsic_changes(
+		&self,
+		f: &mut std::fmt::Formatter<'_>,
+		changes: &[ReducedExtrinsicChange],
+	) -> std::fmt::Result {
+		let extrinsic_a = &self.runtime_a.extrinsic;
+		let extrinsic_b = &self.runtime_b.extrinsic;
+
+		changes.iter().try_for_each(|change| match change {
+			ReducedExtrinsicChange::Version(_) => {
+				writeln!(f, "[≠] extrinsic version: {} -> {}", extrinsic_a.version, extrinsic_b.version)
+			}
+			ReducedExtrinsicChange::SignedExtensions(_) => {
+				let identifiers = |extrinsic: &ReducedExtrinsic| -> Vec<String> {
+					extrinsic.signed_extensions.iter().map(|e| e.identifier.clone()).collect()
+				};
+				let (before, after) = (identifiers(extrinsic_a), identifiers(extrinsic_b));
+
+				writeln!(f, "[≠] extrinsic signed extensions")?;
+				after.iter().filter(|id| !before.contains(id)).try_for_each(|id| writeln!(f, "    [+] {id}"))?;
+				before.iter().filter(|id| !after.contains(id)).try_for_each(|id| writeln!(f, "    [-] {id}"))?;
+				if before.iter().all(|id| after.contains(id)) && after.iter().all(|id| before.contains(id)) {
+					// Same extensions, in a different order
+					Self::fmt_before_after(f, 4, Some(before.join(", ")), Some(after.join(", ")))?;
+				}
+				Ok(())
+			}
+			ReducedExtrinsicChange::ExtensionsByVersion(versions) => versions.iter().try_for_each(|mc| match mc {
+				MapChange::Added(version, ids) => {
+					writeln!(f, "[+] extensions of extrinsic v{version}: {}", ids.join(", "))
+				}
+				MapChange::Removed(version) => writeln!(f, "[-] extensions of extrinsic v{version}"),
+				MapChange::Changed(version, _) => {
+					let get_ids = |extrinsic: &ReducedExtrinsic| {
+						extrinsic.extensions_by_version.get(version).map(|ids| ids.join(", "))
+					};
+					writeln!(f, "[≠] extensions of extrinsic v{version}")?;
+					Self::fmt_before_after(f, 4, get_ids(extrinsic_a), get_ids(extrinsic_b))
+				}
+			}),
+		})
+	}
+
+	/// Write the `before` and `after` renders of an item that changed, or `n/a`
+	/// if it could not be found in one of the runtimes.
+	fn fmt_before_after<T: Display>(
+		f: &mut std::fmt::Formatter<'_>,
+		indent: usize,
+		before: Option<T>,
+		after: Option<T>,
+	) -> std::fmt::Result {
+		let render = |item: Option<T>| item.map(|item| item.to_string()).unwrap_or_else(|| "n/a".to_string());
+		writeln!(f, "{:indent$}before: {}", " ", render(before))?;
+		writeln!(f, "{:indent$}after : {}", " ", render(after))
 	}
 
 	/// We cannot just count the number of items in the Vec we get since the upper
@@ -62,6 +128,9 @@ impl ReducedRuntimeChangeWrapper {
 				ReducedPalletChange::Errors(x) => x.len(),
 				ReducedPalletChange::Constants(x) => x.len(),
 				ReducedPalletChange::Storages(x) => x.len(),
+				ReducedPalletChange::ViewFunctions(x) => x.len(),
+				ReducedPalletChange::AssociatedTypes(x) => x.len(),
+				ReducedPalletChange::Deprecations(x) => x.len(),
 			})
 			.sum();
 		val
@@ -72,8 +141,79 @@ impl Display for ReducedRuntimeChangeWrapper {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		self.changes.iter().for_each(|change| {
 			match change {
-				ReducedRuntimeChange::Extrinsic(_ex) => {
-					let _ = writeln!(f, "EX Change");
+				ReducedRuntimeChange::Extrinsic(ex) => {
+					let _ = self.fmt_extrinsic_changes(f, ex);
+				}
+				ReducedRuntimeChange::Apis(apis) => {
+					apis.iter().for_each(|mc| match mc {
+						MapChange::Added(name, _) => {
+							let _ = writeln!(f, "[+] new runtime api: {name}");
+						}
+						MapChange::Removed(name) => {
+							let _ = writeln!(f, "[-] runtime api: {name}");
+						}
+						MapChange::Changed(name, changes) => {
+							let _ = writeln!(f, "[≠] runtime api: {name}");
+							changes.iter().for_each(|change| match change {
+								ReducedRuntimeApiChange::Methods(methods) => {
+									methods.iter().for_each(|method| {
+										let _ = match method {
+											MapChange::Added(method, _) => writeln!(f, "    [+] {method}"),
+											MapChange::Removed(method) => writeln!(f, "    [-] {method}"),
+											MapChange::Changed(method, _) => {
+												let get_method = |side| {
+													self.get_runtime(side)
+														.apis
+														.get(name)
+														.and_then(|api| api.methods.get(method))
+												};
+												let _ = writeln!(f, "    [≠] {method}");
+												Self::fmt_before_after(
+													f,
+													8,
+													get_method(ComparisonSide::Left),
+													get_method(ComparisonSide::Right),
+												)
+											}
+										};
+									});
+								}
+								ReducedRuntimeApiChange::Name(StringChange(before, after)) => {
+									let _ = writeln!(f, "    [≠] name: {before} -> {after}");
+								}
+							});
+						}
+					});
+				}
+				ReducedRuntimeChange::OuterEnums(_) => {
+					let before = self.runtime_a.outer_enums.as_ref();
+					let after = self.runtime_b.outer_enums.as_ref();
+					let _ = match (before, after) {
+						(None, Some(after)) => writeln!(f, "[+] outer enums: {after}"),
+						(Some(before), None) => writeln!(f, "[-] outer enums: {before}"),
+						_ => {
+							let _ = writeln!(f, "[≠] outer enums");
+							Self::fmt_before_after(f, 4, before, after)
+						}
+					};
+				}
+				ReducedRuntimeChange::Custom(custom) => {
+					custom.iter().for_each(|mc| {
+						let _ = match mc {
+							MapChange::Added(name, _) => writeln!(f, "[+] new custom value: {name}"),
+							MapChange::Removed(name) => writeln!(f, "[-] custom value: {name}"),
+							MapChange::Changed(name, _) => {
+								let get_value = |side| self.get_runtime(side).custom.get(name);
+								let _ = writeln!(f, "[≠] custom value: {name}");
+								Self::fmt_before_after(
+									f,
+									4,
+									get_value(ComparisonSide::Left),
+									get_value(ComparisonSide::Right),
+								)
+							}
+						};
+					});
 				}
 				ReducedRuntimeChange::Pallets(pallets) => {
 					pallets.iter().for_each(|mc: &MapChange<PalletId, ReducedPalletDesc, Vec<ReducedPalletChange>>| {
@@ -0,0 +1,74 @@
+use scale_info::{PortableRegistry, TypeDef, TypeDefPrimitive};
+
+/// The name of a type as it would be written in the runtime, such as `Option<Vec<u8>>`
+pub fn type_name(registry: &PortableRegistry, id: u32) -> String {
+	let Some(ty) = registry.resolve(id) else {
+		return format!("<unknown type {id}>");
+	};
+	let names = |ids: &mut dyn Iterator<Item = u32>| ids.map(|id| type_name(registry, id)).collect::<Vec<_>>();
+
+	match &ty.type_def {
+		TypeDef::Sequence(s) => format!("Vec<{}>", type_name(registry, s.type_param.id)),
+		TypeDef::Array(a) => format!("[{}; {}]", type_name(registry, a.type_param.id), a.len),
+		TypeDef::Tuple(t) => format!("({})", names(&mut t.fields.iter().map(|f| f.id)).join(", ")),
+		TypeDef::Compact(c) => format!("Compact<{}>", type_name(registry, c.type_param.id)),
+		TypeDef::BitSequence(_) => "BitVec".to_string(),
+		TypeDef::Primitive(p) => primitive_name(p).to_string(),
+		TypeDef::Composite(_) | TypeDef::Variant(_) => {
+			let name = ty.path.ident().unwrap_or_else(|| format!("<anonymous type {id}>"));
+			let params = names(&mut ty.type_params.iter().filter_map(|p| p.ty.map(|t| t.id)));
+			match params.is_empty() {
+				true => name,
+				false => format!("{name}<{}>", params.join(", ")),
+			}
+		}
+	}
+}
+
+fn primitive_name(primitive: &TypeDefPrimitive) -> &'static str {
+	match primitive {
+		TypeDefPrimitive::Bool => "bool",
+		TypeDefPrimitive::Char => "char",
+		TypeDefPrimitive::Str => "String",
+		TypeDefPrimitive::U8 => "u8",
+		TypeDefPrimitive::U16 => "u16",
+		TypeDefPrimitive::U32 => "u32",
+		TypeDefPrimitive::U64 => "u64",
+		TypeDefPrimitive::U128 => "u128",
+		TypeDefPrimitive::U256 => "u256",
+		TypeDefPrimitive::I8 => "i8",
+		TypeDefPrimitive::I16 => "i16",
+		TypeDefPrimitive::I32 => "i32",
+		TypeDefPrimitive::I64 => "i64",
+		TypeDefPrimitive::I128 => "i128",
+		TypeDefPrimitive::I256 => "i256",
+	}
+}
+
+#[cfg(test)]
+mod test_type_name {
+	use super::*;
+	use scale_info::{meta_type, Registry, TypeInfo};
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	struct Header {
+		number: u32,
+	}
+
+	#[test]
+	fn test_type_name() {
+		let mut registry = Registry::new();
+		let ids = [
+			registry.register_type(&meta_type::<Option<Vec<u8>>>()).id,
+			registry.register_type(&meta_type::<Header>()).id,
+			registry.register_type(&meta_type::<(u32, [u8; 32])>()).id,
+			registry.register_type(&meta_type::<()>()).id,
+		];
+		let registry: PortableRegistry = registry.into();
+
+		let names = ids.iter().map(|id| type_name(&registry, *id)).collect::<Vec<_>>();
+		assert_eq!(names, ["Option<Vec<u8>>", "Header", "(u32, [u8; 32])", "()"]);
+		assert_eq!(type_name(&registry, 1000), "<unknown type 1000>");
+	}
+}
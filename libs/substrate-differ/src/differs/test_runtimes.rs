@@ -1,5 +1,3 @@
-#![cfg(test)]
-
 use std::{env, fmt::Display, path::PathBuf, str::FromStr};
 
 use crate::error::SubstrateDifferError;
@@ -84,3 +82,148 @@ impl TryInto<PathBuf> for RuntimeFile {
 		Err(SubstrateDifferError::RuntimeNotFound(candidate))
 	}
 }
+
+/// Build a `Core` runtime API with the given `methods`, all taking a `header: u32` and returning `Option<Vec<u8>>`
+pub fn core_api(methods: &[&'static str]) -> frame_metadata::v15::RuntimeApiMetadata {
+	use frame_metadata::v15::*;
+	use scale_info::meta_type;
+
+	let methods = methods
+		.iter()
+		.map(|&name| RuntimeApiMethodMetadata {
+			name,
+			inputs: vec![RuntimeApiMethodParamMetadata { name: "header", ty: meta_type::<u32>() }],
+			output: meta_type::<Option<Vec<u8>>>(),
+			docs: vec![],
+		})
+		.collect();
+	RuntimeApiMetadata { name: "Core", methods, docs: vec![] }
+}
+
+/// Build a small V15 runtime metadata: a `System` pallet and the given runtime `apis`
+pub fn metadata_v15(apis: Vec<frame_metadata::v15::RuntimeApiMetadata>) -> frame_metadata::v15::RuntimeMetadataV15 {
+	use frame_metadata::v15::*;
+	use scale_info::{meta_type, TypeInfo};
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	enum Call {
+		Remark { remark: Vec<u8> },
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	enum Event {
+		Remarked { hash: [u8; 32] },
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	enum Error {
+		CallFiltered,
+	}
+
+	let pallet = PalletMetadata {
+		name: "System",
+		storage: None,
+		calls: Some(PalletCallMetadata { ty: meta_type::<Call>() }),
+		event: Some(PalletEventMetadata { ty: meta_type::<Event>() }),
+		constants: vec![],
+		error: Some(PalletErrorMetadata { ty: meta_type::<Error>() }),
+		index: 0,
+		docs: vec!["Low-level operations of the runtime"],
+	};
+	let extrinsic = ExtrinsicMetadata {
+		version: 4,
+		address_ty: meta_type::<()>(),
+		call_ty: meta_type::<Call>(),
+		signature_ty: meta_type::<()>(),
+		extra_ty: meta_type::<()>(),
+		signed_extensions: vec![],
+	};
+	let outer_enums = OuterEnums {
+		call_enum_ty: meta_type::<Call>(),
+		event_enum_ty: meta_type::<()>(),
+		error_enum_ty: meta_type::<()>(),
+	};
+	let custom = CustomMetadata {
+		map: [("fee_asset", CustomValueMetadata { ty: meta_type::<u32>(), value: vec![42, 0, 0, 0] })].into(),
+	};
+	RuntimeMetadataV15::new(vec![pallet], extrinsic, meta_type::<()>(), apis, outer_enums, custom)
+}
+
+/// Build a small V16 runtime metadata: a `Balances` pallet with the given view `functions`,
+/// its `transfer` call is deprecated if `deprecated` is set, and the given runtime `apis`
+pub fn metadata_v16(
+	functions: &[&'static str],
+	deprecated: bool,
+	apis: Vec<frame_metadata::v16::RuntimeApiMetadata>,
+) -> frame_metadata::v16::RuntimeMetadataV16 {
+	use frame_metadata::v16::*;
+	use scale_info::{meta_type, TypeInfo};
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	enum Call {
+		Transfer { dest: u32, value: u128 },
+		TransferAllowDeath { dest: u32, value: u128 },
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	enum Event {
+		Transfer { from: u32, to: u32, amount: u128 },
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	enum Error {
+		InsufficientBalance,
+	}
+
+	let deprecation_info = if deprecated {
+		let status = DeprecationStatus::Deprecated { note: "Use `transfer_allow_death`", since: None };
+		DeprecationInfo::VariantsDeprecated([(0, status)].into())
+	} else {
+		DeprecationInfo::NotDeprecated
+	};
+	let view_functions = functions
+		.iter()
+		.map(|&name| PalletViewFunctionMetadata {
+			name,
+			id: [0; 32],
+			inputs: vec![PalletViewFunctionParamMetadata { name: "who", ty: meta_type::<u32>() }],
+			output: meta_type::<u128>(),
+			docs: vec![],
+			deprecation_info: DeprecationStatus::NotDeprecated,
+		})
+		.collect();
+	let pallet = PalletMetadata {
+		name: "Balances",
+		storage: None,
+		calls: Some(PalletCallMetadata { ty: meta_type::<Call>(), deprecation_info }),
+		event: Some(PalletEventMetadata { ty: meta_type::<Event>(), deprecation_info: DeprecationInfo::NotDeprecated }),
+		constants: vec![],
+		error: Some(PalletErrorMetadata { ty: meta_type::<Error>(), deprecation_info: DeprecationInfo::NotDeprecated }),
+		associated_types: vec![PalletAssociatedTypeMetadata { name: "Balance", ty: meta_type::<u128>(), docs: vec![] }],
+		view_functions,
+		index: 10,
+		docs: vec![],
+		deprecation_info: DeprecationStatus::NotDeprecated,
+	};
+	let extension =
+		|identifier| TransactionExtensionMetadata { identifier, ty: meta_type::<()>(), implicit: meta_type::<()>() };
+	let extrinsic = ExtrinsicMetadata {
+		versions: vec![4, 5],
+		address_ty: meta_type::<()>(),
+		signature_ty: meta_type::<()>(),
+		transaction_extensions_by_version: [(4, vec![0, 1]), (5, vec![1])].into(),
+		transaction_extensions: vec![extension("CheckNonce"), extension("CheckWeight")],
+	};
+	let outer_enums = OuterEnums {
+		call_enum_ty: meta_type::<Call>(),
+		event_enum_ty: meta_type::<()>(),
+		error_enum_ty: meta_type::<()>(),
+	};
+	RuntimeMetadataV16::new(vec![pallet], extrinsic, apis, outer_enums, CustomMetadata { map: Default::default() })
+}
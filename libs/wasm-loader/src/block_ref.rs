@@ -0,0 +1,122 @@
+use crate::error::{self, *};
+use std::{fmt::Display, str::FromStr};
+
+/// A block hash as returned by the node, hex encoded and `0x` prefixed.
+pub type BlockHash = String;
+
+/// A reference to a block. Only [BlockRef::Hash] can be used directly
+/// when querying the storage of a node, all other variants need to be
+/// resolved to a hash first, see [crate::OnchainBlock::block_hash].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockRef {
+	/// A block hash such as `0x4d6a...fe6c`
+	Hash(BlockHash),
+
+	/// A block number such as `18000000`
+	Number(u64),
+
+	/// A number of blocks before the best block, `-100` for instance
+	Relative(u64),
+
+	/// The latest finalized block
+	Finalized,
+
+	/// The best block
+	Best,
+}
+
+impl BlockRef {
+	/// Returns the hash if this reference does not need to be resolved
+	pub fn as_hash(&self) -> Option<&BlockHash> {
+		match self {
+			BlockRef::Hash(h) => Some(h),
+			_ => None,
+		}
+	}
+}
+
+impl Display for BlockRef {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			BlockRef::Hash(h) => write!(f, "{h}"),
+			BlockRef::Number(n) => write!(f, "{n}"),
+			BlockRef::Relative(n) => write!(f, "-{n}"),
+			BlockRef::Finalized => write!(f, "finalized"),
+			BlockRef::Best => write!(f, "best"),
+		}
+	}
+}
+
+impl FromStr for BlockRef {
+	type Err = WasmLoaderError;
+
+	/// Parse a block hash (`0x...`), a block number (`18000000`, `18_000_000` or `18,000,000`),
+	/// an offset from the best block (`-100`) or one of the `finalized` and `best` tags.
+	fn from_str(s: &str) -> error::Result<Self> {
+		let s = s.trim();
+		let parse_number = |n: &str| -> error::Result<u64> {
+			n.replace(['_', ','], "").parse::<u64>().map_err(|_e| WasmLoaderError::OnchainBlockParsing(s.to_string()))
+		};
+
+		match s.to_lowercase().as_str() {
+			"finalized" | "finalised" => Ok(BlockRef::Finalized),
+			"best" | "latest" => Ok(BlockRef::Best),
+			hash if hash.starts_with("0x") => {
+				let bytes =
+					array_bytes::hex2bytes(hash).map_err(|_e| WasmLoaderError::OnchainBlockParsing(s.into()))?;
+				if bytes.len() != 32 {
+					return Err(WasmLoaderError::OnchainBlockParsing(s.to_string()));
+				}
+				Ok(BlockRef::Hash(hash.to_string()))
+			}
+			offset if offset.starts_with('-') => Ok(BlockRef::Relative(parse_number(&offset[1..])?)),
+			number => Ok(BlockRef::Number(parse_number(number)?)),
+		}
+	}
+}
+
+impl From<u64> for BlockRef {
+	fn from(n: u64) -> Self {
+		BlockRef::Number(n)
+	}
+}
+
+#[cfg(test)]
+mod tests_block_ref {
+	use super::*;
+
+	const POLKADOT_BLOCK20: &str = "0x4d6a0bca208b85d41833a7f35cf73d1ae6974f4bad8ab576e2c3f751d691fe6c";
+
+	#[test]
+	fn it_parses_hashes() {
+		assert_eq!(BlockRef::from_str(POLKADOT_BLOCK20), Ok(BlockRef::Hash(POLKADOT_BLOCK20.to_string())));
+		assert!(BlockRef::from_str("0x4d6a").is_err());
+		assert!(BlockRef::from_str("0xzz6a0bca208b85d41833a7f35cf73d1ae6974f4bad8ab576e2c3f751d691fe6c").is_err());
+	}
+
+	#[test]
+	fn it_parses_numbers() {
+		assert_eq!(BlockRef::from_str("0"), Ok(BlockRef::Number(0)));
+		assert_eq!(BlockRef::from_str("18000000"), Ok(BlockRef::Number(18_000_000)));
+		assert_eq!(BlockRef::from_str("18_000_000"), Ok(BlockRef::Number(18_000_000)));
+		assert_eq!(BlockRef::from_str("18,000,000"), Ok(BlockRef::Number(18_000_000)));
+		assert_eq!(BlockRef::from_str("-100"), Ok(BlockRef::Relative(100)));
+		assert!(BlockRef::from_str("-").is_err());
+		assert!(BlockRef::from_str("--1").is_err());
+	}
+
+	#[test]
+	fn it_parses_tags() {
+		assert_eq!(BlockRef::from_str("finalized"), Ok(BlockRef::Finalized));
+		assert_eq!(BlockRef::from_str("Finalized"), Ok(BlockRef::Finalized));
+		assert_eq!(BlockRef::from_str("best"), Ok(BlockRef::Best));
+		assert!(BlockRef::from_str("foobar").is_err());
+	}
+
+	#[test]
+	fn it_displays_what_it_parses() {
+		for s in [POLKADOT_BLOCK20, "42", "-100", "finalized", "best"] {
+			assert_eq!(BlockRef::from_str(s).expect("Failed parsing BlockRef").to_string(), s);
+		}
+	}
+}
@@ -0,0 +1,232 @@
+use crate::{error::*, BlockHash, NodeEndpoint, WasmBytes};
+use serde::{Deserialize, Serialize};
+use sp_crypto_hashing::blake2_256;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// Environment variable allowing to override the location of the cache
+pub const CACHE_DIR_ENV: &str = "SUBWASM_CACHE_DIR";
+
+const BLOBS: &str = "blobs";
+const REFS: &str = "refs";
+
+/// A local, content-addressed, cache of runtimes.
+///
+/// Runtimes are stored once under their blake2-256 hash. Each way of
+/// referring to a runtime (an endpoint and a block hash, a URL) is a small
+/// ref file pointing to a blob. Only immutable references should be cached:
+/// a block hash will always point to the same runtime, `best` will not.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeCache {
+	root: PathBuf,
+}
+
+/// An entry of the cache, as stored in a ref file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+	/// What the runtime was cached for, see [RuntimeCache::chain_key], [RuntimeCache::url_key]
+	/// and [RuntimeCache::validation_code_key]
+	pub key: String,
+
+	/// The blake2-256 hash of the runtime as fetched
+	pub blake2_256: String,
+
+	/// Size of the runtime as fetched, in bytes
+	pub size: usize,
+}
+
+impl RuntimeCache {
+	pub fn new(root: PathBuf) -> Self {
+		Self { root }
+	}
+
+	/// The default location of the cache: `$SUBWASM_CACHE_DIR` if set,
+	/// then `$XDG_CACHE_HOME/subwasm` and finally `$HOME/.cache/subwasm`.
+	pub fn default_dir() -> PathBuf {
+		let env = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+
+		if let Some(dir) = env(CACHE_DIR_ENV) {
+			return dir;
+		}
+
+		env("XDG_CACHE_HOME")
+			.or_else(|| env("HOME").map(|home| home.join(".cache")))
+			.unwrap_or_else(std::env::temp_dir)
+			.join("subwasm")
+	}
+
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+
+	/// Key of a runtime fetched from a node at a given block
+	pub fn chain_key(endpoint: &NodeEndpoint, block_hash: &BlockHash) -> String {
+		format!("chain:{endpoint}@{block_hash}")
+	}
+
+	/// Key of a runtime downloaded from a URL, including GitHub releases
+	pub fn url_key(url: &str) -> String {
+		format!("url:{url}")
+	}
+
+	/// Key of the validation code of a parachain read from its relay chain, it is known by its hash
+	pub fn validation_code_key(code_hash: &str) -> String {
+		format!("validation_code:{code_hash}")
+	}
+
+	fn blob_path(&self, hash: &str) -> PathBuf {
+		self.root.join(BLOBS).join(format!("{hash}.wasm"))
+	}
+
+	fn ref_path(&self, key: &str) -> PathBuf {
+		self.root.join(REFS).join(format!("{}.json", array_bytes::bytes2hex("", blake2_256(key.as_bytes()))))
+	}
+
+	fn entry(&self, key: &str) -> Option<CacheEntry> {
+		let json = fs::read(self.ref_path(key)).ok()?;
+		serde_json::from_slice::<CacheEntry>(&json).ok().filter(|entry| entry.key == key)
+	}
+
+	/// Returns the path of the cached runtime for `key`, if any.
+	/// The content is checked and a corrupted entry is treated as missing.
+	pub fn get_path(&self, key: &str) -> Option<PathBuf> {
+		let entry = self.entry(key)?;
+		let path = self.get_blob_path(&entry.blake2_256)?;
+		log::debug!("Cache hit for {key}: {}", path.display());
+		Some(path)
+	}
+
+	/// Returns the path of the runtime with the given blake2-256 hash, if we have it
+	pub fn get_blob_path(&self, blake2_256: &str) -> Option<PathBuf> {
+		let path = self.blob_path(blake2_256);
+		let bytes = fs::read(&path).ok()?;
+
+		if Self::hash(&bytes) != blake2_256 {
+			log::warn!("Cached runtime {blake2_256} is corrupted, ignoring it");
+			let _ = fs::remove_file(&path);
+			return None;
+		}
+		Some(path)
+	}
+
+	/// Returns the cached runtime for `key`, if any
+	pub fn get(&self, key: &str) -> Option<WasmBytes> {
+		self.get_path(key).and_then(|path| fs::read(path).ok())
+	}
+
+	/// Store a runtime in the cache under `key` and return the path of the blob
+	pub fn insert(&self, key: &str, bytes: &[u8]) -> Result<PathBuf> {
+		let err = |e: std::io::Error| WasmLoaderError::Cache(format!("{}: {e}", self.root.display()));
+		fs::create_dir_all(self.root.join(BLOBS)).map_err(err)?;
+		fs::create_dir_all(self.root.join(REFS)).map_err(err)?;
+
+		let blake2_256 = Self::hash(bytes);
+		let path = self.blob_path(&blake2_256);
+		if !path.exists() {
+			// Write then rename so a concurrent reader never sees a partial blob
+			let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+			fs::write(&tmp, bytes).map_err(err)?;
+			fs::rename(&tmp, &path).map_err(err)?;
+		}
+
+		let entry = CacheEntry { key: key.to_string(), blake2_256, size: bytes.len() };
+		let json = serde_json::to_vec_pretty(&entry).map_err(|e| WasmLoaderError::Cache(e.to_string()))?;
+		fs::write(self.ref_path(key), json).map_err(err)?;
+
+		log::debug!("Cached {key} as {}", path.display());
+		Ok(path)
+	}
+
+	/// List all the entries of the cache
+	pub fn list(&self) -> Result<Vec<CacheEntry>> {
+		let dir = match fs::read_dir(self.root.join(REFS)) {
+			Ok(dir) => dir,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+			Err(e) => return Err(WasmLoaderError::Cache(format!("{}: {e}", self.root.display()))),
+		};
+
+		let mut entries: Vec<CacheEntry> = dir
+			.filter_map(|f| f.ok())
+			.filter_map(|f| fs::read(f.path()).ok())
+			.filter_map(|json| serde_json::from_slice(&json).ok())
+			.collect();
+		entries.sort_by(|a, b| a.key.cmp(&b.key));
+		Ok(entries)
+	}
+
+	/// Remove everything from the cache and return the number of entries that were removed
+	pub fn clear(&self) -> Result<usize> {
+		let count = self.list()?.len();
+		for dir in [BLOBS, REFS] {
+			match fs::remove_dir_all(self.root.join(dir)) {
+				Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+					return Err(WasmLoaderError::Cache(format!("{}: {e}", self.root.display())))
+				}
+				_ => {}
+			}
+		}
+		Ok(count)
+	}
+
+	fn hash(bytes: &[u8]) -> String {
+		array_bytes::bytes2hex("0x", blake2_256(bytes))
+	}
+}
+
+impl Default for RuntimeCache {
+	fn default() -> Self {
+		Self::new(Self::default_dir())
+	}
+}
+
+#[cfg(test)]
+mod tests_cache {
+	use super::*;
+
+	fn tmp_cache(name: &str) -> RuntimeCache {
+		let root = std::env::temp_dir().join(format!("subwasm-cache-test-{name}-{}", std::process::id()));
+		RuntimeCache::new(root)
+	}
+
+	#[test]
+	fn it_stores_and_retrieves() {
+		let cache = tmp_cache("stores-and-retrieves");
+		let endpoint = NodeEndpoint::WebSocket("wss://rpc.polkadot.io:443".into());
+		let key = RuntimeCache::chain_key(&endpoint, &"0x1234".to_string());
+
+		assert_eq!(cache.get(&key), None);
+		cache.insert(&key, b"runtime").expect("Failed caching");
+		assert_eq!(cache.get(&key), Some(b"runtime".to_vec()));
+		assert_eq!(cache.get(&RuntimeCache::url_key("https://foo.bar/runtime.wasm")), None);
+
+		cache.clear().expect("Failed clearing cache");
+	}
+
+	#[test]
+	fn it_deduplicates_blobs() {
+		let cache = tmp_cache("deduplicates-blobs");
+		let a = cache.insert("url:a", b"runtime").expect("Failed caching");
+		let b = cache.insert("url:b", b"runtime").expect("Failed caching");
+		assert_eq!(a, b);
+
+		let entries = cache.list().expect("Failed listing cache");
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].key, "url:a");
+		assert_eq!(entries[0].size, 7);
+
+		assert_eq!(cache.clear().expect("Failed clearing cache"), 2);
+		assert!(cache.list().expect("Failed listing cache").is_empty());
+	}
+
+	#[test]
+	fn it_ignores_corrupted_blobs() {
+		let cache = tmp_cache("ignores-corrupted-blobs");
+		let path = cache.insert("url:a", b"runtime").expect("Failed caching");
+		fs::write(path, b"corrupted").expect("Failed corrupting blob");
+		assert_eq!(cache.get("url:a"), None);
+
+		cache.clear().expect("Failed clearing cache");
+	}
+}
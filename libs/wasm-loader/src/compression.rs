@@ -1,7 +1,40 @@
 use serde::Serialize;
+use std::io::Write;
 
 use crate::{error, WasmBytes, CODE_BLOB_BOMB_LIMIT};
 
+/// The zstd level used by default, the same as `sp_maybe_compressed_blob`
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+/// The default maximum size of the code of a parachain, as set in the
+/// `HostConfiguration` of the Polkadot and Kusama relay chains
+pub const RELAY_MAX_CODE_SIZE: usize = 3 * 1024 * 1024;
+
+/// The prefix of compressed runtimes, see `sp_maybe_compressed_blob`
+const ZSTD_PREFIX: [u8; 8] = [82, 188, 83, 118, 70, 219, 142, 5];
+
+/// The magic number every wasm module starts with
+const WASM_MAGIC: [u8; 4] = [0x00, 0x61, 0x73, 0x6d];
+
+/// How runtimes are compressed and decompressed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CompressionOptions {
+	/// The zstd compression level
+	pub level: i32,
+
+	/// Runtimes larger than this, once decompressed, are rejected as possible compression bombs
+	pub bomb_limit: usize,
+
+	/// Whether to check that decompressing the compressed runtime gives back the original
+	pub verify: bool,
+}
+
+impl Default for CompressionOptions {
+	fn default() -> Self {
+		Self { level: DEFAULT_COMPRESSION_LEVEL, bomb_limit: CODE_BLOB_BOMB_LIMIT, verify: false }
+	}
+}
+
 /// Stores compression information
 #[derive(Debug, Clone, Copy, Serialize)]
 pub struct Compression {
@@ -32,17 +65,49 @@ impl Compression {
 		self.size_compressed as f32 / self.size_decompressed as f32
 	}
 
+	/// Whether `bytes` start like a runtime: a wasm module or a compressed one
+	pub fn is_runtime(bytes: &[u8]) -> bool {
+		bytes.starts_with(&WASM_MAGIC) || bytes.starts_with(&ZSTD_PREFIX)
+	}
+
 	/// Compress a runtime
 	pub fn compress(bytes: &[u8]) -> error::Result<WasmBytes> {
-		match sp_maybe_compressed_blob::compress(bytes, CODE_BLOB_BOMB_LIMIT) {
-			Some(bytes) => Ok(bytes.to_vec()),
-			None => Err(error::WasmLoaderError::CompressionError()),
+		Self::compress_with(bytes, &CompressionOptions::default())
+	}
+
+	/// Compress a runtime the same way `sp_maybe_compressed_blob` does, using the given options
+	pub fn compress_with(bytes: &[u8], options: &CompressionOptions) -> error::Result<WasmBytes> {
+		if !zstd::compression_level_range().contains(&options.level) {
+			return Err(error::WasmLoaderError::InvalidCompressionLevel(options.level));
+		}
+
+		// A larger runtime could not be told apart from a compression bomb when decompressing it
+		if bytes.len() > options.bomb_limit {
+			return Err(error::WasmLoaderError::BombLimitExceeded(bytes.len(), options.bomb_limit));
+		}
+
+		let mut compressed = ZSTD_PREFIX.to_vec();
+		{
+			let mut encoder = zstd::Encoder::new(&mut compressed, options.level)
+				.map_err(|_e| error::WasmLoaderError::CompressionError())?
+				.auto_finish();
+			encoder.write_all(bytes).map_err(|_e| error::WasmLoaderError::CompressionError())?;
+		}
+
+		if options.verify && Self::decompress_with(&compressed, options)? != bytes {
+			return Err(error::WasmLoaderError::CompressionMismatch());
 		}
+		Ok(compressed)
 	}
 
 	/// Decompress a runtime
 	pub fn decompress(bytes: &[u8]) -> error::Result<WasmBytes> {
-		sp_maybe_compressed_blob::decompress(bytes, CODE_BLOB_BOMB_LIMIT)
+		Self::decompress_with(bytes, &CompressionOptions::default())
+	}
+
+	/// Decompress a runtime, rejecting it if it is larger than the bomb limit of the options
+	pub fn decompress_with(bytes: &[u8], options: &CompressionOptions) -> error::Result<WasmBytes> {
+		sp_maybe_compressed_blob::decompress(bytes, options.bomb_limit)
 			.map(|res| res.to_vec())
 			.map_err(|_err| error::WasmLoaderError::DecompressionFailed())
 	}
@@ -74,4 +139,38 @@ mod tests_compression {
 		let decompressed = Compression::decompress(&compressed);
 		assert_eq!(bytes, decompressed.expect("Failed decompressing"));
 	}
+
+	#[test]
+	fn test_is_runtime() {
+		let wasm = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+		assert!(Compression::is_runtime(&wasm));
+		assert!(Compression::is_runtime(&Compression::compress(&wasm).expect("Should be able to compress")));
+		assert!(!Compression::is_runtime(b"<html>Not found</html>"));
+		assert!(!Compression::is_runtime(&[]));
+	}
+
+	#[test]
+	fn test_compression_options() {
+		let bytes = [42u8; 1024];
+		let default = Compression::compress(&bytes).expect("Should be able to compress");
+		assert_eq!(default, sp_maybe_compressed_blob::compress(&bytes, 1024).expect("Failed compressing"));
+
+		let options = CompressionOptions { level: 19, bomb_limit: 1024, verify: true };
+		let compressed = Compression::compress_with(&bytes, &options).expect("Should be able to compress");
+		assert_ne!(compressed, default);
+		assert_eq!(Compression::decompress_with(&compressed, &options).expect("Failed decompressing"), bytes);
+
+		let options = CompressionOptions { bomb_limit: 1023, ..options };
+		assert_eq!(
+			Compression::compress_with(&bytes, &options),
+			Err(error::WasmLoaderError::BombLimitExceeded(1024, 1023))
+		);
+		assert!(Compression::decompress_with(&compressed, &options).is_err());
+
+		let options = CompressionOptions { level: 1000, ..Default::default() };
+		assert_eq!(
+			Compression::compress_with(&bytes, &options),
+			Err(error::WasmLoaderError::InvalidCompressionLevel(1000))
+		);
+	}
 }
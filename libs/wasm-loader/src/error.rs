@@ -23,6 +23,15 @@ pub enum WasmLoaderError {
 	#[error("Decompression failed")]
 	DecompressionFailed(),
 
+	#[error("Invalid zstd compression level: {0}")]
+	InvalidCompressionLevel(i32),
+
+	#[error("The runtime is {0} bytes, over the bomb limit of {1} bytes")]
+	BombLimitExceeded(usize, usize),
+
+	#[error("Decompressing the compressed runtime does not give back the original")]
+	CompressionMismatch(),
+
 	#[error("URL Error: {0}")]
 	UrlParsingError(String),
 
@@ -31,6 +40,45 @@ pub enum WasmLoaderError {
 
 	#[error("WebSocket Client error, url: `{0}`")]
 	WsClient(String),
+
+	#[error("IPC Client error, socket: `{0}`")]
+	IpcClient(String),
+
+	#[error("HTTP error {0} from `{1}`")]
+	HttpStatus(u16, String),
+
+	#[error("The node returned an error {0}: {1}")]
+	RpcError(i64, String),
+
+	#[error("Timeout while waiting for `{0}`")]
+	RpcTimeout(String),
+
+	#[error("Unexpected response from the node: {0}")]
+	RpcUnexpectedResponse(String),
+
+	#[error("Storage item `{0}` not found at block {1}")]
+	StorageNotFound(String, String),
+
+	#[error("Failed reading `{0}`: {1}")]
+	FileRead(String, String),
+
+	#[error("Cache error: {0}")]
+	Cache(String),
+
+	#[error("Invalid header: {0}")]
+	InvalidHeader(String),
+
+	#[error("Invalid config: {0}")]
+	Config(String),
+
+	#[error("TLS error: {0}")]
+	Tls(String),
+
+	#[error("Invalid snapshot: {0}")]
+	Snapshot(String),
+
+	#[error("Failed decoding hex data")]
+	HexDecoding(),
 }
 
 impl From<sp_maybe_compressed_blob::Error> for WasmLoaderError {
@@ -0,0 +1,237 @@
+use crate::{error::*, NodeEndpoint};
+use serde::Deserialize;
+use std::{
+	collections::BTreeMap,
+	fmt::{Debug, Display},
+	fs,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+
+/// Environment variable allowing to override the location of the config file
+pub const CONFIG_FILE_ENV: &str = "SUBWASM_CONFIG";
+
+/// An HTTP header sent to the nodes, for instance the API key of a paid RPC provider.
+///
+/// A header is sent to all nodes unless it is restricted to a `host`.
+/// It is parsed from `NAME: VALUE` or `HOST=NAME: VALUE`.
+#[derive(Clone, PartialEq)]
+pub struct RpcHeader {
+	/// Only send the header to nodes on this host, optionally followed by a port
+	pub host: Option<String>,
+	pub name: String,
+	pub value: String,
+}
+
+/// The headers we send to the nodes, in the order they were given.
+/// For a given name, the last applicable header wins.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RpcHeaders(Vec<RpcHeader>);
+
+/// The part of the config file we care about
+#[derive(Debug, Default, Deserialize)]
+struct Config {
+	/// Headers by host, `*` for all hosts
+	#[serde(default)]
+	headers: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl RpcHeader {
+	pub fn new(host: Option<&str>, name: &str, value: &str) -> Result<Self> {
+		let is_token = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c);
+		if name.is_empty() || !name.chars().all(is_token) {
+			return Err(WasmLoaderError::InvalidHeader(format!("invalid name `{name}`")));
+		}
+		if value.contains(['\r', '\n']) {
+			return Err(WasmLoaderError::InvalidHeader(format!("invalid value for `{name}`")));
+		}
+
+		let host = host.filter(|h| *h != "*").map(|h| h.to_lowercase());
+		Ok(Self { host, name: name.to_string(), value: value.trim().to_string() })
+	}
+
+	/// Whether this header should be sent to `endpoint`
+	pub fn applies_to(&self, endpoint: &NodeEndpoint) -> bool {
+		let Some(host) = &self.host else { return true };
+		let Ok(url) = endpoint.as_url() else { return false };
+		let Some(url_host) = url.host_str() else { return false };
+
+		match host.split_once(':') {
+			Some((host, port)) => {
+				host == url_host && url.port_or_known_default().is_some_and(|p| p.to_string() == port)
+			}
+			None => host == url_host,
+		}
+	}
+
+	/// Whether `s` looks like a host, optionally followed by a port, and not like the start of a header
+	fn is_host(s: &str) -> bool {
+		let (host, port) = s.split_once(':').unwrap_or((s, "0"));
+		(host == "*" || !host.is_empty() && host.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-'))
+			&& !port.is_empty()
+			&& port.chars().all(|c| c.is_ascii_digit())
+	}
+}
+
+impl FromStr for RpcHeader {
+	type Err = WasmLoaderError;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		let (host, header) = match s.split_once('=') {
+			Some((host, header)) if Self::is_host(host) && header.contains(':') => (Some(host), header),
+			_ => (None, s),
+		};
+		let (name, value) = header
+			.split_once(':')
+			.ok_or_else(|| WasmLoaderError::InvalidHeader(format!("expected `NAME: VALUE`, got `{s}`")))?;
+		Self::new(host, name.trim(), value)
+	}
+}
+
+impl Display for RpcHeader {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		// We never show the value, it is likely a secret
+		match &self.host {
+			Some(host) => write!(f, "{host}={}: ***", self.name),
+			None => write!(f, "{}: ***", self.name),
+		}
+	}
+}
+
+/// Same as [Display], so secrets do not leak in debug logs
+impl Debug for RpcHeader {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "RpcHeader({self})")
+	}
+}
+
+impl RpcHeaders {
+	pub fn new(headers: Vec<RpcHeader>) -> Self {
+		Self(headers)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Add headers, they take precedence over the ones we already have
+	pub fn extend(&mut self, headers: impl IntoIterator<Item = RpcHeader>) {
+		self.0.extend(headers)
+	}
+
+	/// The headers to send to `endpoint`
+	pub fn for_endpoint(&self, endpoint: &NodeEndpoint) -> Vec<(String, String)> {
+		let mut headers: Vec<(String, String)> = Vec::new();
+		for header in self.0.iter().filter(|h| h.applies_to(endpoint)) {
+			headers.retain(|(name, _)| !name.eq_ignore_ascii_case(&header.name));
+			headers.push((header.name.clone(), header.value.clone()));
+		}
+		headers
+	}
+
+	/// The default location of the config file: `$SUBWASM_CONFIG` if set,
+	/// then `$XDG_CONFIG_HOME/subwasm/config.json` and finally `$HOME/.config/subwasm/config.json`.
+	pub fn default_config_file() -> Option<PathBuf> {
+		let env = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+
+		if let Some(file) = env(CONFIG_FILE_ENV) {
+			return Some(file);
+		}
+
+		env("XDG_CONFIG_HOME")
+			.or_else(|| env("HOME").map(|home| home.join(".config")))
+			.map(|dir| dir.join("subwasm").join("config.json"))
+	}
+
+	/// Load the headers from the `headers` section of a config file such as:
+	///
+	/// ```json
+	/// { "headers": { "rpc.example.com": { "X-Api-Key": "secret" }, "*": { "User-Agent": "subwasm" } } }
+	/// ```
+	pub fn from_config_file(path: &Path) -> Result<Self> {
+		let err = |e: String| WasmLoaderError::Config(format!("{}: {e}", path.display()));
+		let json = fs::read(path).map_err(|e| err(e.to_string()))?;
+		let config: Config = serde_json::from_slice(&json).map_err(|e| err(e.to_string()))?;
+
+		let mut headers = Vec::new();
+		for (host, values) in config.headers {
+			for (name, value) in values {
+				headers.push(RpcHeader::new(Some(&host), &name, &value).map_err(|e| err(e.to_string()))?);
+			}
+		}
+		// Host specific headers must win over the generic ones
+		headers.sort_by_key(|h| h.host.is_some());
+		Ok(Self(headers))
+	}
+}
+
+impl From<Vec<RpcHeader>> for RpcHeaders {
+	fn from(headers: Vec<RpcHeader>) -> Self {
+		Self::new(headers)
+	}
+}
+
+#[cfg(test)]
+mod tests_headers {
+	use super::*;
+
+	fn endpoint(url: &str) -> NodeEndpoint {
+		NodeEndpoint::from_str(url).expect("Failed parsing endpoint")
+	}
+
+	#[test]
+	fn it_parses_headers() {
+		let header = RpcHeader::from_str("X-Api-Key: abc").expect("Failed parsing header");
+		assert_eq!(header, RpcHeader { host: None, name: "X-Api-Key".into(), value: "abc".into() });
+
+		let header = RpcHeader::from_str("rpc.example.com=X-Api-Key: abc=").expect("Failed parsing header");
+		assert_eq!(header.host, Some("rpc.example.com".into()));
+		assert_eq!(header.value, "abc=");
+
+		let header = RpcHeader::from_str("localhost:9944=Authorization:Bearer a=b").expect("Failed parsing header");
+		assert_eq!(header.host, Some("localhost:9944".into()));
+		assert_eq!(header.name, "Authorization");
+
+		let header = RpcHeader::from_str("Authorization: Basic YTpi==").expect("Failed parsing header");
+		assert_eq!(header.host, None);
+		assert_eq!(header.value, "Basic YTpi==");
+
+		assert!(RpcHeader::from_str("X-Api-Key").is_err());
+		assert!(RpcHeader::from_str("X Api Key: abc").is_err());
+	}
+
+	#[test]
+	fn it_filters_headers_by_host() {
+		let headers = RpcHeaders::new(vec![
+			RpcHeader::from_str("User-Agent: subwasm").expect("Failed parsing header"),
+			RpcHeader::from_str("rpc.example.com=X-Api-Key: abc").expect("Failed parsing header"),
+			RpcHeader::from_str("rpc.example.com:8443=X-Api-Key: def").expect("Failed parsing header"),
+		]);
+
+		assert_eq!(
+			headers.for_endpoint(&endpoint("wss://rpc.example.com")),
+			vec![("User-Agent".to_string(), "subwasm".to_string()), ("X-Api-Key".to_string(), "abc".to_string())]
+		);
+		assert_eq!(
+			headers.for_endpoint(&endpoint("https://rpc.example.com:8443/ws")),
+			vec![("User-Agent".to_string(), "subwasm".to_string()), ("X-Api-Key".to_string(), "def".to_string())]
+		);
+		assert_eq!(headers.for_endpoint(&endpoint("wss://rpc.polkadot.io")).len(), 1);
+	}
+
+	#[test]
+	fn it_loads_headers_from_config() {
+		let path = std::env::temp_dir().join(format!("subwasm-config-test-{}.json", std::process::id()));
+		fs::write(
+			&path,
+			r#"{ "headers": { "rpc.example.com": { "X-Api-Key": "abc" }, "*": { "X-Api-Key": "none" } } }"#,
+		)
+		.expect("Failed writing config");
+
+		let headers = RpcHeaders::from_config_file(&path).expect("Failed loading config");
+		assert_eq!(headers.for_endpoint(&endpoint("wss://rpc.example.com")), vec![("X-Api-Key".into(), "abc".into())]);
+		assert_eq!(headers.for_endpoint(&endpoint("wss://rpc.polkadot.io")), vec![("X-Api-Key".into(), "none".into())]);
+
+		fs::remove_file(path).expect("Failed removing config");
+	}
+}
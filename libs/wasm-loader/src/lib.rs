@@ -1,26 +1,51 @@
 #![allow(clippy::derive_partial_eq_without_eq)]
 
+mod block_ref;
+mod cache;
 mod compression;
 pub mod error;
+mod headers;
 mod node_endpoint;
 mod onchain_block;
+mod onchain_metadata;
+mod retry;
+pub mod rpc;
+mod rpc_api;
+#[cfg(feature = "async")]
+mod rpc_async;
+mod runtime_history;
+mod snapshot;
 mod source;
-
-pub use compression::Compression;
+pub mod storage;
+mod tls;
+mod upgrade_status;
+mod validation_code;
+
+pub use block_ref::{BlockHash, BlockRef};
+pub use cache::{CacheEntry, RuntimeCache, CACHE_DIR_ENV};
+pub use compression::{Compression, CompressionOptions, DEFAULT_COMPRESSION_LEVEL, RELAY_MAX_CODE_SIZE};
 pub use error::*;
+pub use headers::{RpcHeader, RpcHeaders, CONFIG_FILE_ENV};
 pub use node_endpoint::NodeEndpoint;
-pub use onchain_block::{BlockRef, OnchainBlock};
+pub use onchain_block::OnchainBlock;
+pub use onchain_metadata::OnchainMetadata;
+pub use retry::RetryPolicy;
+pub use rpc::RpcClient;
+pub use rpc_api::RpcApi;
+#[cfg(feature = "async")]
+pub use rpc_async::AsyncRpcClient;
+pub use runtime_history::{RuntimeHistory, RuntimeUpgrade};
+pub use snapshot::{RawStorage, Snapshot};
 pub use source::Source;
+pub use tls::TlsConfig;
+pub use upgrade_status::{UpgradeAuthorization, UpgradeStatus};
+pub use validation_code::ValidationCode;
 
 use log::*;
-use serde::Deserialize;
-use std::fmt::Debug;
-use std::io::Read;
-use std::{fs::File, net::TcpStream, path::Path};
-use subrpcer::state;
-use tungstenite::{stream::MaybeTlsStream, Message, WebSocket};
-
-const CODE: &str = "0x3a636f6465"; // :code in hex
+use std::path::Path;
+
+/// Well known storage key of the runtime code
+pub const CODE: &[u8] = b":code";
 pub const CODE_BLOB_BOMB_LIMIT: usize = 50 * 1024 * 1024;
 pub type WasmBytes = Vec<u8>;
 
@@ -34,85 +59,117 @@ pub enum CompressedMaybe {
 pub struct WasmLoader {
 	bytes: CompressedMaybe,
 	compression: Compression,
-}
 
-#[derive(Deserialize)]
-struct Response {
-	result: String,
-}
+	/// The hash of the block the runtime was fetched at, if it came from a node
+	block_hash: Option<BlockHash>,
 
-fn map_err<O, E1, E2>(r: std::result::Result<O, E1>, e: E2) -> std::result::Result<O, E2>
-where
-	E1: Debug,
-{
-	r.map_err(|e_| {
-		eprintln!("{e_:?}");
-		e
-	})
+	/// The endpoint that served the runtime, if it came from a node
+	endpoint: Option<NodeEndpoint>,
+
+	/// The validation code hash as seen by the relay chain, if the runtime came from there
+	validation_code_hash: Option<String>,
 }
 
 impl WasmLoader {
-	fn read_wasm_hex_response_from_web_socket(
-		mut ws: WebSocket<MaybeTlsStream<TcpStream>>,
-		url: &String,
-	) -> Result<String> {
-		match map_err(ws.read(), WasmLoaderError::WsClient(url.to_string()))? {
-			Message::Text(t) => {
-				Ok(serde_json::from_str::<Response>(&t).map(|r| r.result).expect("unexpected response from node"))
-			}
-			Message::Ping(_) => {
-				log::debug!("Got ping from node, retrying.");
-				Self::read_wasm_hex_response_from_web_socket(ws, url)
-			}
-			m => {
-				log::warn!("Got unexpected message {:?} from node, retrying.", m);
-				Self::read_wasm_hex_response_from_web_socket(ws, url)
-			}
-		}
-	}
-
 	/// Fetch the wasm blob from a node
-	fn fetch_wasm_from_rpc(reference: &OnchainBlock) -> Result<WasmBytes> {
-		let block_ref = reference.block_ref.as_ref();
-		let data = state::get_storage(0, CODE, block_ref);
-		let wasm_hex = match &reference.endpoint {
-			NodeEndpoint::Http(url) => {
-				map_err(ureq::post(url).send_json(data), WasmLoaderError::HttpClient(url.to_string()))?
-					.into_json::<Response>()
-					.expect("unexpected response from node")
-					.result
-			}
-			NodeEndpoint::WebSocket(url) => {
-				let mut ws = map_err(tungstenite::connect(url), WasmLoaderError::WsClient(url.to_string()))?.0;
-
-				map_err(
-					ws.send(Message::Binary(serde_json::to_vec(&data).expect("invalid data"))),
-					WasmLoaderError::WsClient(url.to_string()),
-				)?;
-				Self::read_wasm_hex_response_from_web_socket(ws, url)?
-			}
-		};
-		let wasm = array_bytes::hex2bytes(wasm_hex).expect("Decoding bytes");
-
-		Ok(wasm)
+	fn fetch_wasm_from_rpc(client: &mut RpcClient, block_hash: &BlockHash) -> Result<WasmBytes> {
+		client
+			.get_storage(CODE, block_hash)?
+			.ok_or_else(|| WasmLoaderError::StorageNotFound(":code".into(), block_hash.into()))
 	}
 
 	/// Load some binary from a file
-	fn load_from_file(filename: &Path) -> WasmBytes {
-		let mut f = File::open(filename).unwrap_or_else(|_| panic!("File {} not found", filename.to_string_lossy()));
-		let mut buffer = Vec::new();
-		f.read_to_end(&mut buffer).expect("failed loading file");
+	fn load_from_file(filename: &Path) -> Result<WasmBytes> {
+		let buffer = std::fs::read(filename)
+			.map_err(|e| WasmLoaderError::FileRead(filename.to_string_lossy().to_string(), e.to_string()))?;
 		log::debug!("read data from file, buffer size: {:?}", buffer.len());
-		buffer
+		Ok(buffer)
 	}
 
 	pub fn compression(&self) -> Compression {
 		self.compression
 	}
 
-	/// Load wasm from a node
-	fn load_from_node(reference: &OnchainBlock) -> Result<WasmBytes> {
-		WasmLoader::fetch_wasm_from_rpc(reference)
+	/// Returns the hash of the block the runtime was fetched at.
+	/// This is only set when loading the runtime from a node.
+	pub fn block_hash(&self) -> Option<&BlockHash> {
+		self.block_hash.as_ref()
+	}
+
+	/// Returns the endpoint that served the runtime.
+	/// This is only set when loading the runtime from a node.
+	pub fn endpoint(&self) -> Option<&NodeEndpoint> {
+		self.endpoint.as_ref()
+	}
+
+	/// Returns the validation code hash of the runtime, as the relay chain sees it.
+	/// This is only set when loading the validation code of a parachain from its relay chain.
+	pub fn validation_code_hash(&self) -> Option<&String> {
+		self.validation_code_hash.as_ref()
+	}
+
+	/// Load wasm from a node, after resolving the block reference to a block hash.
+	/// The endpoints of the reference are tried in turn until one of them succeeds.
+	fn load_from_node(
+		reference: &OnchainBlock,
+		cache: Option<&RuntimeCache>,
+	) -> Result<(WasmBytes, BlockHash, NodeEndpoint)> {
+		let ((wasm, block_hash), endpoint) =
+			reference.with_failover(|client| Self::load_with_client(reference, client, cache))?;
+		log::debug!("Runtime served by {endpoint}");
+		Ok((wasm, block_hash, endpoint))
+	}
+
+	/// Load wasm from the node `client` talks to.
+	/// If a cache is provided, the runtime is only fetched if we do not have it yet.
+	fn load_with_client(
+		reference: &OnchainBlock,
+		client: &mut RpcClient,
+		cache: Option<&RuntimeCache>,
+	) -> Result<(WasmBytes, BlockHash)> {
+		let block_hash = reference.block_hash_with(client)?;
+		let key = RuntimeCache::chain_key(client.endpoint(), &block_hash);
+
+		if let Some(wasm) = cache.and_then(|c| c.get(&key)) {
+			return Ok((wasm, block_hash));
+		}
+
+		// The runtime may have been cached for another block, or another node.
+		// Asking for the hash of the code is much cheaper than fetching it.
+		if let Some(cache) = cache {
+			let code_hash = client.get_storage_hash(CODE, &block_hash);
+			if let Some(wasm) = Self::get_by_code_hash(cache, &key, code_hash) {
+				return Ok((wasm, block_hash));
+			}
+		}
+
+		let wasm = WasmLoader::fetch_wasm_from_rpc(client, &block_hash)?;
+		Self::cache_runtime(cache, &key, &wasm);
+		Ok((wasm, block_hash))
+	}
+
+	/// Look for a runtime in the cache by the hash of its code, as returned by the node.
+	/// If we have it, we also cache it under `key` for next time.
+	fn get_by_code_hash(cache: &RuntimeCache, key: &str, code_hash: Result<Option<String>>) -> Option<WasmBytes> {
+		let code_hash = match code_hash {
+			Ok(code_hash) => code_hash?,
+			Err(e) => {
+				log::debug!("Could not get the hash of the runtime: {e}");
+				return None;
+			}
+		};
+
+		let wasm = cache.get_blob_path(&code_hash).and_then(|p| std::fs::read(p).ok())?;
+		log::debug!("Runtime {code_hash} found in the cache");
+		Self::cache_runtime(Some(cache), key, &wasm);
+		Some(wasm)
+	}
+
+	/// Failing to cache a runtime is not a reason to fail loading it
+	fn cache_runtime(cache: Option<&RuntimeCache>, key: &str, wasm: &[u8]) {
+		if let Some(Err(e)) = cache.map(|c| c.insert(key, wasm)) {
+			log::warn!("Failed caching the runtime: {e}");
+		}
 	}
 
 	/// Returns the 'usable' uncompressed bytes. You get either the raw bytes if the
@@ -136,16 +193,45 @@ impl WasmLoader {
 	}
 
 	pub fn load_from_bytes(bytes: CompressedMaybe, compression: Compression) -> Result<Self> {
-		Ok(Self { bytes, compression })
+		Ok(Self { bytes, compression, block_hash: None, endpoint: None, validation_code_hash: None })
 	}
 
 	/// Load the binary wasm from a file or from a running node via rpc
 	pub fn load_from_source(source: &Source) -> Result<Self> {
-		log::debug!("Loading from {:?}", source);
-		let bytes = match source {
-			Source::File(f) => Ok(Self::load_from_file(f)),
-			Source::Chain(n) => Self::load_from_node(n),
-		}?;
+		Self::load_from_source_with_cache(source, None)
+	}
+
+	/// Same as [WasmLoader::load_from_source] but runtimes fetched from a node
+	/// are looked up in and stored into the given cache.
+	pub fn load_from_source_with_cache(source: &Source, cache: Option<&RuntimeCache>) -> Result<Self> {
+		log::debug!("Loading from {}", source);
+		let (bytes, block_hash, endpoint) = match source {
+			Source::File(f) => (Self::load_from_file(f)?, None, None),
+			Source::Bytes(b) => (b.to_owned(), None, None),
+			Source::Snapshot(f) => (Snapshot::load(f)?.code()?, None, None),
+			Source::Chain(n) => {
+				let (bytes, hash, endpoint) = Self::load_from_node(n, cache)?;
+				(bytes, Some(hash), Some(endpoint))
+			}
+			Source::Parachain(relay, para_id) => {
+				return Self::from_validation_code(ValidationCode::fetch(relay, *para_id, cache)?);
+			}
+		};
+		Self::from_fetched_bytes(bytes, block_hash, endpoint)
+	}
+
+	fn from_validation_code(code: ValidationCode) -> Result<Self> {
+		let ValidationCode { block_hash, endpoint, code_hash, code, .. } = code;
+		let loader = Self::from_fetched_bytes(code, Some(block_hash), Some(endpoint))?;
+		Ok(Self { validation_code_hash: Some(code_hash), ..loader })
+	}
+
+	/// Build the loader from the bytes we fetched, decompressing them if needed
+	fn from_fetched_bytes(
+		bytes: WasmBytes,
+		block_hash: Option<BlockHash>,
+		endpoint: Option<NodeEndpoint>,
+	) -> Result<Self> {
 		log::debug!("Loaded {:?} bytes", bytes.len());
 
 		debug!("code size before decompression: {:?}", bytes.len());
@@ -157,23 +243,96 @@ impl WasmLoader {
 		debug!(
 			"code size after decompression {:?}  {:?}",
 			bytes_decompressed.len(),
-			bytes_decompressed[0..64].to_vec()
+			bytes_decompressed[..bytes_decompressed.len().min(64)].to_vec()
 		);
 
-		match compression.compressed() {
+		let loader = match compression.compressed() {
 			true => Self::load_from_bytes(
 				CompressedMaybe::Compressed((bytes_decompressed.to_vec(), bytes.to_vec())),
 				compression,
 			),
 			false => Self::load_from_bytes(CompressedMaybe::Uncompressed(bytes.to_vec()), compression),
+		}?;
+
+		Ok(Self { block_hash, endpoint, ..loader })
+	}
+}
+
+/// The async counterparts of the functions loading a runtime, see [OnchainBlock::with_failover_async]
+#[cfg(feature = "async")]
+impl WasmLoader {
+	/// See [WasmLoader::load_from_source]
+	pub async fn load_from_source_async(source: &Source) -> Result<Self> {
+		Self::load_from_source_with_cache_async(source, None).await
+	}
+
+	/// See [WasmLoader::load_from_source_with_cache]
+	pub async fn load_from_source_with_cache_async(source: &Source, cache: Option<&RuntimeCache>) -> Result<Self> {
+		log::debug!("Loading from {}", source);
+		let (bytes, block_hash, endpoint) = match source {
+			Source::File(f) => {
+				let bytes = tokio::fs::read(f)
+					.await
+					.map_err(|e| WasmLoaderError::FileRead(f.to_string_lossy().to_string(), e.to_string()))?;
+				(bytes, None, None)
+			}
+			Source::Bytes(b) => (b.to_owned(), None, None),
+			Source::Snapshot(f) => {
+				// Snapshots are large, reading them must not block the runtime
+				let bytes =
+					tokio::fs::read(f).await.map_err(|e| WasmLoaderError::Snapshot(format!("{}: {e}", f.display())))?;
+				(Snapshot::decode(&bytes)?.code()?, None, None)
+			}
+			Source::Chain(n) => {
+				let ((bytes, hash), endpoint) = n
+					.with_failover_async(|mut client| async move {
+						Self::load_with_client_async(n, &mut client, cache).await
+					})
+					.await?;
+				log::debug!("Runtime served by {endpoint}");
+				(bytes, Some(hash), Some(endpoint))
+			}
+			Source::Parachain(relay, para_id) => {
+				return Self::from_validation_code(ValidationCode::fetch_async(relay, *para_id, cache).await?);
+			}
+		};
+
+		Self::from_fetched_bytes(bytes, block_hash, endpoint)
+	}
+
+	/// See [WasmLoader::load_with_client]
+	async fn load_with_client_async(
+		reference: &OnchainBlock,
+		client: &mut AsyncRpcClient,
+		cache: Option<&RuntimeCache>,
+	) -> Result<(WasmBytes, BlockHash)> {
+		let block_hash = reference.block_hash_with_async(client).await?;
+		let key = RuntimeCache::chain_key(client.endpoint(), &block_hash);
+
+		if let Some(wasm) = cache.and_then(|c| c.get(&key)) {
+			return Ok((wasm, block_hash));
 		}
+
+		if let Some(cache) = cache {
+			let code_hash = client.get_storage_hash(CODE, &block_hash).await;
+			if let Some(wasm) = Self::get_by_code_hash(cache, &key, code_hash) {
+				return Ok((wasm, block_hash));
+			}
+		}
+
+		let wasm = client
+			.get_storage(CODE, &block_hash)
+			.await?
+			.ok_or_else(|| WasmLoaderError::StorageNotFound(":code".into(), block_hash.to_string()))?;
+		Self::cache_runtime(cache, &key, &wasm);
+		Ok((wasm, block_hash))
 	}
 }
 
 #[cfg(test)]
 pub mod tests {
 	use super::*;
-	use std::env;
+	use std::{env, str::FromStr};
 
 	#[cfg(test)]
 	pub fn ensure_local_wasm() -> String {
@@ -211,15 +370,169 @@ pub mod tests {
 		}
 	}
 
+	#[test]
+	fn it_fails_loading_a_missing_file() {
+		let source = Source::File("/tmp/subwasm/does/not/exist.wasm".into());
+		assert!(matches!(WasmLoader::load_from_source(&source), Err(WasmLoaderError::FileRead(_, _))));
+	}
+
+	mod cache {
+		use super::*;
+		use crate::rpc::mock::mock_node_with;
+		use serde_json::Value;
+
+		const BLOCK: &str = "0x4d6a0bca208b85d41833a7f35cf73d1ae6974f4bad8ab576e2c3f751d691fe6c";
+		const RUNTIME: &[u8] = &[0x00, 0x61, 0x73, 0x6d, 0x01];
+
+		fn tmp_cache(name: &str) -> RuntimeCache {
+			let root = env::temp_dir().join(format!("subwasm-loader-test-{name}-{}", std::process::id()));
+			let cache = RuntimeCache::new(root);
+			cache.clear().expect("Failed clearing cache");
+			cache
+		}
+
+		/// A node knowing only our runtime, `serve_code` tells whether it lets us fetch it
+		fn node(serve_code: bool, code_hash: Option<String>) -> OnchainBlock {
+			let endpoint = mock_node_with(move |request: &Value| {
+				let result = match request["method"].as_str() {
+					Some("state_getStorage") if serve_code => format!(r#""{}""#, array_bytes::bytes2hex("0x", RUNTIME)),
+					Some("state_getStorageHash") => {
+						code_hash.as_ref().map(|h| format!(r#""{h}""#)).unwrap_or_else(|| "null".into())
+					}
+					_ => return vec![r#"{"jsonrpc":"2.0","id":{id},"error":{"code":-1,"message":"Nope"}}"#.into()],
+				};
+				vec![format!(r#"{{"jsonrpc":"2.0","id":{{id}},"result":{result}}}"#)]
+			});
+			OnchainBlock::from_endpoint(endpoint, Some(BlockRef::Hash(BLOCK.into())))
+		}
+
+		#[test]
+		fn it_caches_runtimes_from_nodes() {
+			let cache = tmp_cache("caches");
+			let block = node(true, None);
+
+			let loader = WasmLoader::load_from_source_with_cache(&Source::Chain(block.clone()), Some(&cache))
+				.expect("Failed loading wasm");
+			assert_eq!(loader.original_bytes(), RUNTIME);
+			assert_eq!(loader.block_hash(), Some(&BLOCK.to_string()));
+			assert_eq!(cache.get(&RuntimeCache::chain_key(&block.endpoint, &BLOCK.into())), Some(RUNTIME.to_vec()));
+		}
+
+		#[cfg(feature = "async")]
+		#[tokio::test]
+		async fn it_caches_runtimes_from_nodes_async() {
+			let cache = tmp_cache("caches-async");
+			let block = node(true, None);
+
+			let loader = WasmLoader::load_from_source_with_cache_async(&Source::Chain(block.clone()), Some(&cache))
+				.await
+				.expect("Failed loading wasm");
+			assert_eq!(loader.original_bytes(), RUNTIME);
+			assert_eq!(cache.get(&RuntimeCache::chain_key(&block.endpoint, &BLOCK.into())), Some(RUNTIME.to_vec()));
+		}
+
+		#[test]
+		fn it_reuses_runtimes_by_code_hash() {
+			let cache = tmp_cache("reuses");
+			cache.insert("url:https://foo.bar/runtime.wasm", RUNTIME).expect("Failed caching");
+			let code_hash = cache.list().expect("Failed listing cache")[0].blake2_256.clone();
+
+			// The node refuses to serve the code, we must find it in the cache
+			let block = node(false, Some(code_hash));
+			let loader = WasmLoader::load_from_source_with_cache(&Source::Chain(block), Some(&cache))
+				.expect("Failed loading wasm");
+			assert_eq!(loader.original_bytes(), RUNTIME);
+			assert_eq!(cache.list().expect("Failed listing cache").len(), 2);
+		}
+	}
+
+	mod failover {
+		use super::*;
+		use crate::rpc::mock::mock_node;
+		use std::time::Duration;
+
+		const BLOCK: &str = "0x4d6a0bca208b85d41833a7f35cf73d1ae6974f4bad8ab576e2c3f751d691fe6c";
+
+		/// An endpoint nobody listens on
+		fn dead_node() -> NodeEndpoint {
+			let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed binding");
+			let port = listener.local_addr().expect("Failed getting address").port();
+			NodeEndpoint::WebSocket(format!("ws://127.0.0.1:{port}"))
+		}
+
+		#[test]
+		fn it_falls_back_to_the_next_endpoint() {
+			let node = mock_node(vec![r#"{"jsonrpc":"2.0","id":{id},"result":"0x0061736d01"}"#]);
+			let reference = OnchainBlock {
+				endpoint: dead_node(),
+				block_ref: Some(BlockRef::Hash(BLOCK.into())),
+				fallbacks: vec![node.clone()],
+				retry: RetryPolicy { retries: 1, backoff: Duration::from_millis(1), ..Default::default() },
+				headers: RpcHeaders::default(),
+				tls: TlsConfig::default(),
+			};
+
+			let loader = WasmLoader::load_from_source(&Source::Chain(reference)).expect("Failed loading wasm");
+			assert_eq!(loader.original_bytes(), &[0x00, 0x61, 0x73, 0x6d, 0x01]);
+			assert_eq!(loader.endpoint(), Some(&node));
+		}
+
+		#[cfg(feature = "async")]
+		#[tokio::test]
+		async fn it_falls_back_to_the_next_endpoint_async() {
+			let node = crate::rpc::mock::mock_http_node(r#"{"jsonrpc":"2.0","id":{id},"result":"0x0061736d01"}"#);
+			let reference = OnchainBlock {
+				endpoint: dead_node(),
+				block_ref: Some(BlockRef::Hash(BLOCK.into())),
+				fallbacks: vec![node.clone()],
+				retry: RetryPolicy { retries: 1, backoff: Duration::from_millis(1), ..Default::default() },
+				headers: RpcHeaders::default(),
+				tls: TlsConfig::default(),
+			};
+
+			let loader =
+				WasmLoader::load_from_source_async(&Source::Chain(reference)).await.expect("Failed loading wasm");
+			assert_eq!(loader.original_bytes(), &[0x00, 0x61, 0x73, 0x6d, 0x01]);
+			assert_eq!(loader.endpoint(), Some(&node));
+		}
+
+		#[test]
+		fn it_fails_when_all_endpoints_fail() {
+			let reference = OnchainBlock {
+				endpoint: dead_node(),
+				block_ref: Some(BlockRef::Hash(BLOCK.into())),
+				fallbacks: vec![dead_node()],
+				retry: RetryPolicy { retries: 0, ..Default::default() },
+				headers: RpcHeaders::default(),
+				tls: TlsConfig::default(),
+			};
+			assert!(WasmLoader::load_from_source(&Source::Chain(reference)).is_err());
+		}
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn it_fetches_a_wasm_from_an_ipc_node() {
+		let node = crate::rpc::mock::mock_ipc_node(vec![r#"{"jsonrpc":"2.0","id":{id},"result":"0x0061736d01"}"#]);
+		let reference = OnchainBlock::from_str(&node.to_string()).expect("Failed parsing block");
+		assert_eq!(reference.endpoint, node);
+		let reference = OnchainBlock { block_ref: Some(BlockRef::Hash("0x4d6a0bca".into())), ..reference };
+
+		let loader = WasmLoader::load_from_source(&Source::Chain(reference)).expect("Failed loading wasm");
+		assert_eq!(loader.original_bytes(), &[0x00, 0x61, 0x73, 0x6d, 0x01]);
+	}
+
 	#[test]
 	#[ignore = "need node"]
 	fn fetch_should_work() {
-		assert!(WasmLoader::fetch_wasm_from_rpc(
-			&OnchainBlock::new("https://rpc.polkadot.io", None).expect("Can parse RPC node")
+		assert!(WasmLoader::load_from_node(
+			&OnchainBlock::new("https://rpc.polkadot.io", None).expect("Can parse RPC node"),
+			None
 		)
 		.is_ok());
-		assert!(WasmLoader::fetch_wasm_from_rpc(
-			&OnchainBlock::new("wss://rpc.polkadot.io", None).expect("Can parse RPC node")
+		assert!(WasmLoader::load_from_node(
+			&OnchainBlock::new("wss://rpc.polkadot.io", None).expect("Can parse RPC node"),
+			None
 		)
 		.is_ok());
 	}
@@ -229,7 +542,7 @@ pub mod tests {
 	fn it_fetches_a_wasm_from_node_via_ws() {
 		let url = get_ws_node(false);
 		println!("Connecting to {:?}", &url);
-		let reference = OnchainBlock { endpoint: NodeEndpoint::WebSocket(url), block_ref: None };
+		let reference = OnchainBlock::from_endpoint(NodeEndpoint::WebSocket(url), None);
 		let loader = WasmLoader::load_from_source(&Source::Chain(reference)).expect("Failed loading wasm");
 		let wasm = loader.uncompressed_bytes();
 		println!("uncompressed wasm size: {:?}", wasm.len());
@@ -241,7 +554,7 @@ pub mod tests {
 	fn it_fetches_the_compressed_runtime() {
 		let url = get_ws_node(false);
 		println!("Connecting to {:?}", &url);
-		let reference = OnchainBlock { endpoint: NodeEndpoint::WebSocket(url), block_ref: None };
+		let reference = OnchainBlock::from_endpoint(NodeEndpoint::WebSocket(url), None);
 		let loader = WasmLoader::load_from_source(&Source::Chain(reference)).expect("Failed loading wasm");
 		let uncompressed_bytes = loader.uncompressed_bytes();
 		let original_bytes = loader.original_bytes();
@@ -258,9 +571,11 @@ pub mod tests {
 
 		let url = get_ws_node(true);
 		println!("Connecting to {:?}", &url);
-		let latest = OnchainBlock { endpoint: NodeEndpoint::WebSocket(url.clone()), block_ref: None };
-		let older =
-			OnchainBlock { endpoint: NodeEndpoint::WebSocket(url), block_ref: Some(POLKADOT_BLOCK20.to_string()) };
+		let latest = OnchainBlock::from_endpoint(NodeEndpoint::WebSocket(url.clone()), None);
+		let older = OnchainBlock::from_endpoint(
+			NodeEndpoint::WebSocket(url),
+			Some(BlockRef::Hash(POLKADOT_BLOCK20.to_string())),
+		);
 
 		let loader_latest = WasmLoader::load_from_source(&Source::Chain(latest)).expect("Failed loading wasm");
 		let wasm_latest = loader_latest.uncompressed_bytes();
@@ -274,4 +589,25 @@ pub mod tests {
 		assert!(wasm_older.len() > 1_000_000);
 		assert!(wasm_older.len() != wasm_latest.len()); // this likely changed...
 	}
+
+	#[test]
+	#[ignore = "need archive node"]
+	fn it_resolves_block_refs() {
+		const POLKADOT_BLOCK20: &str = "0x4d6a0bca208b85d41833a7f35cf73d1ae6974f4bad8ab576e2c3f751d691fe6c"; // Polkadot Block #20
+
+		let url = get_ws_node(true);
+		let block = |block_ref: &str| {
+			OnchainBlock::from_endpoint(
+				NodeEndpoint::WebSocket(url.clone()),
+				Some(BlockRef::from_str(block_ref).expect("Failed parsing BlockRef")),
+			)
+		};
+
+		assert_eq!(POLKADOT_BLOCK20, block("20").block_hash().expect("Failed resolving block number"));
+		assert_eq!(POLKADOT_BLOCK20, block(POLKADOT_BLOCK20).block_hash().expect("Failed resolving block hash"));
+		assert!(block("finalized").block_hash().is_ok());
+		assert!(block("best").block_hash().is_ok());
+		assert!(block("-10").block_hash().is_ok());
+		assert!(block("999999999").block_hash().is_err());
+	}
 }
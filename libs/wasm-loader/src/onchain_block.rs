@@ -1,29 +1,248 @@
 use crate::{
 	error::{self},
-	NodeEndpoint,
+	BlockHash, BlockRef, NodeEndpoint, RetryPolicy, RpcClient, RpcHeaders, TlsConfig,
 };
+use serde::Deserialize;
 use std::str::FromStr;
-pub type BlockRef = String;
+#[cfg(feature = "async")]
+use {crate::AsyncRpcClient, std::future::Future, subrpcer::chain};
 
 use error::*;
 use url::Url;
 
 /// This structure points to a node url and an optional block reference.
+///
+/// Other nodes of the same chain can be provided as `fallbacks`, they are
+/// tried in turn when `endpoint` fails, see [OnchainBlock::with_failover].
+/// The `headers` applying to an endpoint are sent along with each request
+/// and `tls` applies to the `https` and `wss` endpoints.
 #[derive(Debug, Clone, PartialEq)]
 pub struct OnchainBlock {
 	pub endpoint: NodeEndpoint,
 	pub block_ref: Option<BlockRef>,
+	pub fallbacks: Vec<NodeEndpoint>,
+	pub retry: RetryPolicy,
+	pub headers: RpcHeaders,
+	pub tls: TlsConfig,
+}
+
+/// The only part of a header we care about
+#[derive(Deserialize)]
+pub(crate) struct Header {
+	number: String,
+}
+
+impl Header {
+	/// The number of the block, the node gives it as hex
+	pub(crate) fn number(&self) -> Result<u64> {
+		u64::from_str_radix(self.number.trim_start_matches("0x"), 16)
+			.map_err(|_e| WasmLoaderError::OnchainBlockParsing(self.number.clone()))
+	}
 }
 
 impl OnchainBlock {
 	pub fn new(url: &str, block_ref: Option<BlockRef>) -> Result<Self> {
 		let endpoint = NodeEndpoint::from_str(url)?;
-		Ok(Self { endpoint, block_ref })
+		Ok(Self::from_endpoint(endpoint, block_ref))
+	}
+
+	pub fn from_endpoint(endpoint: NodeEndpoint, block_ref: Option<BlockRef>) -> Self {
+		Self {
+			endpoint,
+			block_ref,
+			fallbacks: Vec::new(),
+			retry: RetryPolicy::default(),
+			headers: RpcHeaders::default(),
+			tls: TlsConfig::default(),
+		}
+	}
+
+	/// All the endpoints we may use, in the order we try them
+	pub fn endpoints(&self) -> impl Iterator<Item = &NodeEndpoint> {
+		std::iter::once(&self.endpoint).chain(self.fallbacks.iter())
+	}
+
+	/// Run `f` against our endpoints in turn until it succeeds. Transient errors
+	/// are retried on the same endpoint according to the [RetryPolicy].
+	/// Returns the result along with the endpoint that served it.
+	pub fn with_failover<T, F>(&self, mut f: F) -> Result<(T, NodeEndpoint)>
+	where
+		F: FnMut(&mut RpcClient) -> Result<T>,
+	{
+		let mut last_error = None;
+		let tls = self.tls.client_config()?;
+
+		for endpoint in self.endpoints() {
+			let mut client = RpcClient::new(endpoint.clone())
+				.with_timeout(self.retry.timeout)
+				.with_headers(self.headers.for_endpoint(endpoint));
+			if let Some(tls) = &tls {
+				client = client.with_tls(tls.clone());
+			}
+
+			for attempt in 0..=self.retry.retries {
+				if attempt > 0 {
+					let delay = self.retry.delay(attempt);
+					log::debug!("Retrying {endpoint} in {delay:?}");
+					std::thread::sleep(delay);
+				}
+
+				match f(&mut client) {
+					Ok(res) => return Ok((res, endpoint.clone())),
+					Err(e) => {
+						log::warn!("Request to {endpoint} failed: {e}");
+						let transient = e.is_transient();
+						last_error = Some(e);
+						if !transient {
+							break;
+						}
+					}
+				}
+			}
+		}
+
+		Err(last_error.unwrap_or_else(|| WasmLoaderError::EndpointParsing("No endpoint".into())))
 	}
 
 	pub fn as_url(&self) -> Result<Url> {
 		self.endpoint.as_url()
 	}
+
+	/// Ask the node for the hash of the block we point to.
+	/// Without a block reference, we get the hash of the best block.
+	pub fn block_hash(&self) -> Result<BlockHash> {
+		self.with_failover(|client| self.block_hash_with(client)).map(|(hash, _endpoint)| hash)
+	}
+
+	/// Same as [OnchainBlock::block_hash] but using an existing [RpcClient]
+	pub fn block_hash_with(&self, client: &mut RpcClient) -> Result<BlockHash> {
+		let block_ref = self.block_ref.as_ref().unwrap_or(&BlockRef::Best);
+		log::debug!("Resolving block {block_ref} with {}", self.endpoint);
+
+		let hash = match block_ref {
+			BlockRef::Hash(hash) => return Ok(hash.to_owned()),
+			BlockRef::Number(n) => Self::get_block_hash(client, *n)?,
+			BlockRef::Relative(offset) => {
+				let best = client.best_number()?;
+				Self::get_block_hash(client, Self::number_before(best, *offset)?)?
+			}
+			BlockRef::Finalized => client.finalized_hash()?,
+			BlockRef::Best => client.best_hash()?,
+		};
+
+		log::debug!("Block {block_ref} resolved to {hash}");
+		Ok(hash)
+	}
+
+	/// Returns a copy of this [OnchainBlock] where the block reference is a [BlockRef::Hash].
+	/// The endpoint that resolved the block becomes the main endpoint of the copy.
+	pub fn resolve(&self) -> Result<Self> {
+		let (hash, endpoint) = self.with_failover(|client| self.block_hash_with(client))?;
+		let fallbacks = self.endpoints().filter(|e| **e != endpoint).cloned().collect();
+		Ok(Self { endpoint, block_ref: Some(BlockRef::Hash(hash)), fallbacks, ..self.clone() })
+	}
+
+	pub(crate) fn get_block_hash(client: &mut RpcClient, number: u64) -> Result<BlockHash> {
+		// The node returns nothing for blocks it does not know about yet
+		client
+			.hash_at(number)?
+			.ok_or_else(|| WasmLoaderError::OnchainBlockParsing(format!("Block #{number} not found")))
+	}
+
+	/// The number of the block `offset` blocks before the block `number`
+	fn number_before(number: u64, offset: u64) -> Result<u64> {
+		number.checked_sub(offset).ok_or_else(|| {
+			WasmLoaderError::OnchainBlockParsing(format!("-{offset} is before genesis, best block is #{number}"))
+		})
+	}
+}
+
+/// The async counterparts of the methods above, using an [AsyncRpcClient]
+#[cfg(feature = "async")]
+impl OnchainBlock {
+	/// Same as [OnchainBlock::with_failover] but `f` gets a fresh [AsyncRpcClient] for each attempt
+	pub async fn with_failover_async<T, F, Fut>(&self, mut f: F) -> Result<(T, NodeEndpoint)>
+	where
+		F: FnMut(AsyncRpcClient) -> Fut,
+		Fut: Future<Output = Result<T>>,
+	{
+		let mut last_error = None;
+		let tls = self.tls.client_config()?;
+
+		for endpoint in self.endpoints() {
+			for attempt in 0..=self.retry.retries {
+				if attempt > 0 {
+					let delay = self.retry.delay(attempt);
+					log::debug!("Retrying {endpoint} in {delay:?}");
+					tokio::time::sleep(delay).await;
+				}
+
+				let mut client = AsyncRpcClient::new(endpoint.clone())
+					.with_timeout(self.retry.timeout)
+					.with_headers(self.headers.for_endpoint(endpoint));
+				if let Some(tls) = &tls {
+					client = client.with_tls(tls.clone());
+				}
+
+				match f(client).await {
+					Ok(res) => return Ok((res, endpoint.clone())),
+					Err(e) => {
+						log::warn!("Request to {endpoint} failed: {e}");
+						let transient = e.is_transient();
+						last_error = Some(e);
+						if !transient {
+							break;
+						}
+					}
+				}
+			}
+		}
+
+		Err(last_error.unwrap_or_else(|| WasmLoaderError::EndpointParsing("No endpoint".into())))
+	}
+
+	/// See [OnchainBlock::block_hash]
+	pub async fn block_hash_async(&self) -> Result<BlockHash> {
+		self.with_failover_async(|mut client| async move { self.block_hash_with_async(&mut client).await })
+			.await
+			.map(|(hash, _endpoint)| hash)
+	}
+
+	/// See [OnchainBlock::block_hash_with]
+	pub async fn block_hash_with_async(&self, client: &mut AsyncRpcClient) -> Result<BlockHash> {
+		let block_ref = self.block_ref.as_ref().unwrap_or(&BlockRef::Best);
+		log::debug!("Resolving block {block_ref} with {}", self.endpoint);
+
+		let number = match block_ref {
+			BlockRef::Hash(hash) => return Ok(hash.to_owned()),
+			BlockRef::Finalized => return client.request(chain::get_finalized_head_raw()).await,
+			BlockRef::Number(n) => Some(*n),
+			BlockRef::Relative(offset) => {
+				let header: Header = client.request(chain::get_header_raw(None::<BlockHash>)).await?;
+				Some(Self::number_before(header.number()?, *offset)?)
+			}
+			BlockRef::Best => None,
+		};
+
+		// The node returns `null` for blocks it does not know about yet
+		let hash = client.request::<Option<BlockHash>>(chain::get_block_hash_raw(number)).await?.ok_or_else(|| {
+			WasmLoaderError::OnchainBlockParsing(match number {
+				Some(number) => format!("Block #{number} not found"),
+				None => block_ref.to_string(),
+			})
+		})?;
+
+		log::debug!("Block {block_ref} resolved to {hash}");
+		Ok(hash)
+	}
+
+	/// See [OnchainBlock::resolve]
+	pub async fn resolve_async(&self) -> Result<Self> {
+		let (hash, endpoint) =
+			self.with_failover_async(|mut client| async move { self.block_hash_with_async(&mut client).await }).await?;
+		let fallbacks = self.endpoints().filter(|e| **e != endpoint).cloned().collect();
+		Ok(Self { endpoint, block_ref: Some(BlockRef::Hash(hash)), fallbacks, ..self.clone() })
+	}
 }
 
 impl FromStr for OnchainBlock {
@@ -46,6 +265,6 @@ impl TryFrom<Url> for OnchainBlock {
 
 impl From<NodeEndpoint> for OnchainBlock {
 	fn from(endpoint: NodeEndpoint) -> Self {
-		OnchainBlock { endpoint, block_ref: None }
+		OnchainBlock::from_endpoint(endpoint, None)
 	}
 }
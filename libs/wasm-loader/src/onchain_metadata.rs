@@ -0,0 +1,74 @@
+use crate::{error::*, BlockHash, NodeEndpoint, OnchainBlock, RpcClient};
+use sp_version::RuntimeVersion;
+use subrpcer::state;
+
+/// The metadata and version of a runtime as reported by a node.
+///
+/// This is what we need to inspect a runtime without fetching and
+/// executing the wasm itself, which is much faster and also works
+/// against light endpoints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnchainMetadata {
+	/// The block the metadata was read at
+	pub block_hash: BlockHash,
+
+	/// The endpoint that served the metadata
+	pub endpoint: NodeEndpoint,
+
+	/// The SCALE encoded `RuntimeMetadataPrefixed`, as returned by `state_getMetadata`
+	pub metadata: Vec<u8>,
+
+	/// The version of the runtime, as returned by `state_getRuntimeVersion`
+	pub runtime_version: RuntimeVersion,
+}
+
+impl OnchainMetadata {
+	/// Ask the node for the metadata and version of the runtime at the given block
+	pub fn fetch(reference: &OnchainBlock) -> Result<Self> {
+		let (metadata, endpoint) = reference.with_failover(|client| Self::fetch_with(reference, client))?;
+		Ok(Self { endpoint, ..metadata })
+	}
+
+	fn fetch_with(reference: &OnchainBlock, client: &mut RpcClient) -> Result<Self> {
+		let block_hash = reference.block_hash_with(client)?;
+		log::debug!("Fetching the metadata at {block_hash}");
+
+		let metadata: String = client.request(state::get_metadata_raw(Some(&block_hash)))?;
+		let metadata = array_bytes::hex2bytes(metadata).map_err(|_e| WasmLoaderError::HexDecoding())?;
+		let runtime_version: RuntimeVersion = client.request(state::get_runtime_version_raw(Some(&block_hash)))?;
+
+		Ok(Self { block_hash, endpoint: client.endpoint().clone(), metadata, runtime_version })
+	}
+}
+
+#[cfg(test)]
+mod tests_onchain_metadata {
+	use super::*;
+	use crate::{rpc::mock::mock_node_with, BlockRef};
+	use serde_json::Value;
+
+	const BLOCK: &str = "0x4d6a0bca208b85d41833a7f35cf73d1ae6974f4bad8ab576e2c3f751d691fe6c";
+
+	#[test]
+	fn it_fetches_metadata_and_version() {
+		let endpoint = mock_node_with(|request: &Value| {
+			assert_eq!(request["params"][0], BLOCK);
+			let result = match request["method"].as_str() {
+				Some("state_getMetadata") => r#""0x6d6574610e""#,
+				Some("state_getRuntimeVersion") => {
+					r#"{"specName":"polkadot","implName":"parity-polkadot","authoringVersion":0,"specVersion":1002000,"implVersion":0,"apis":[["0xdf6acb689907609b",5]],"transactionVersion":26,"stateVersion":1}"#
+				}
+				_ => "null",
+			};
+			vec![format!(r#"{{"jsonrpc":"2.0","id":{{id}},"result":{result}}}"#)]
+		});
+		let reference = OnchainBlock::from_endpoint(endpoint.clone(), Some(BlockRef::Hash(BLOCK.into())));
+
+		let metadata = OnchainMetadata::fetch(&reference).expect("Failed fetching metadata");
+		assert_eq!(metadata.metadata, b"meta\x0e");
+		assert_eq!(metadata.runtime_version.spec_name.to_string(), "polkadot");
+		assert_eq!(metadata.runtime_version.spec_version, 1_002_000);
+		assert_eq!(metadata.block_hash, BLOCK);
+		assert_eq!(metadata.endpoint, endpoint);
+	}
+}
@@ -0,0 +1,64 @@
+use crate::{error::*, rpc::DEFAULT_RPC_TIMEOUT};
+use std::time::Duration;
+
+/// How hard we try talking to an endpoint before moving on to the next one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+	/// Number of retries on each endpoint, after the first attempt
+	pub retries: u32,
+
+	/// Delay before the first retry, doubled after each retry
+	pub backoff: Duration,
+
+	/// Timeout applied to each request
+	pub timeout: Duration,
+}
+
+impl RetryPolicy {
+	/// Delay to wait before the given retry, starting at 1
+	pub fn delay(&self, retry: u32) -> Duration {
+		self.backoff.saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+	}
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self { retries: 2, backoff: Duration::from_millis(500), timeout: DEFAULT_RPC_TIMEOUT }
+	}
+}
+
+impl WasmLoaderError {
+	/// Whether the error may go away if we try again, such as a timeout or a node being overloaded.
+	/// Errors returned by the node itself, for instance about a pruned block, are not.
+	pub fn is_transient(&self) -> bool {
+		match self {
+			WasmLoaderError::HttpClient(_)
+			| WasmLoaderError::WsClient(_)
+			| WasmLoaderError::IpcClient(_)
+			| WasmLoaderError::RpcTimeout(_) => true,
+			WasmLoaderError::HttpStatus(status, _) => *status == 429 || *status >= 500,
+			_ => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_retry {
+	use super::*;
+
+	#[test]
+	fn it_backs_off_exponentially() {
+		let policy = RetryPolicy { backoff: Duration::from_millis(100), ..Default::default() };
+		assert_eq!(policy.delay(1), Duration::from_millis(100));
+		assert_eq!(policy.delay(2), Duration::from_millis(200));
+		assert_eq!(policy.delay(3), Duration::from_millis(400));
+	}
+
+	#[test]
+	fn it_only_retries_transient_errors() {
+		assert!(WasmLoaderError::RpcTimeout("ws://localhost:9944".into()).is_transient());
+		assert!(WasmLoaderError::HttpStatus(503, "http://localhost:9933".into()).is_transient());
+		assert!(!WasmLoaderError::HttpStatus(404, "http://localhost:9933".into()).is_transient());
+		assert!(!WasmLoaderError::RpcError(4003, "State already discarded".into()).is_transient());
+	}
+}
@@ -0,0 +1,779 @@
+//! A minimal JSON-RPC client able to talk to a Substrate node over HTTP, WebSocket or a Unix socket.
+
+use crate::{
+	error::*,
+	rpc_api::{ChainHeadFollow, StorageQuery},
+	BlockHash, NodeEndpoint, RpcApi,
+};
+use rustls::ClientConfig;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use std::{
+	error::Error,
+	io,
+	net::TcpStream,
+	path::Path,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+#[cfg(unix)]
+use std::{
+	io::{BufRead, BufReader, Write},
+	os::unix::net::UnixStream,
+};
+use tungstenite::{
+	client::IntoClientRequest,
+	handshake::client::Request,
+	http::{HeaderName, HeaderValue},
+	stream::MaybeTlsStream,
+	Connector, Message, WebSocket,
+};
+use url::Url;
+
+/// Default timeout applied to connections and requests
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+type WsStream = WebSocket<MaybeTlsStream<TcpStream>>;
+
+/// A connection kept open across requests, as subscriptions need
+enum Connection {
+	Ws(Box<WsStream>),
+
+	/// Messages are separated by new lines
+	#[cfg(unix)]
+	Ipc(BufReader<UnixStream>),
+}
+
+impl Connection {
+	fn send(&mut self, payload: &Value, url: &str) -> Result<()> {
+		let text = serde_json::to_string(payload).map_err(|e| WasmLoaderError::RpcUnexpectedResponse(e.to_string()))?;
+		match self {
+			Connection::Ws(ws) => ws.send(Message::Text(text)).map_err(|e| RpcClient::ws_error(e, url)),
+			#[cfg(unix)]
+			Connection::Ipc(stream) => {
+				let stream = stream.get_mut();
+				writeln!(stream, "{text}").and_then(|_| stream.flush()).map_err(|e| RpcClient::ipc_error(e, url))
+			}
+		}
+	}
+
+	/// Read the next message, `None` if it carries no data
+	fn read(&mut self, url: &str) -> Result<Option<String>> {
+		match self {
+			Connection::Ws(ws) => match ws.read().map_err(|e| RpcClient::ws_error(e, url))? {
+				Message::Text(t) => Ok(Some(t)),
+				Message::Binary(b) => Ok(Some(String::from_utf8_lossy(&b).to_string())),
+				Message::Close(_) => Err(WasmLoaderError::WsClient(format!("{url} closed the connection"))),
+				m => {
+					log::trace!("Ignoring {m:?}");
+					Ok(None)
+				}
+			},
+			#[cfg(unix)]
+			Connection::Ipc(stream) => {
+				let mut line = String::new();
+				match stream.read_line(&mut line).map_err(|e| RpcClient::ipc_error(e, url))? {
+					0 => Err(WasmLoaderError::IpcClient(format!("{url} closed the connection"))),
+					_ => Ok(Some(line.trim().to_string()).filter(|l| !l.is_empty())),
+				}
+			}
+		}
+	}
+}
+
+/// The error object a node returns when a request fails
+#[derive(Debug, Deserialize)]
+struct RpcErrorObject {
+	code: i64,
+	message: String,
+	data: Option<Value>,
+}
+
+/// Any message we may get from a node: a response to one of our requests
+/// or a notification we did not ask for.
+#[derive(Debug, Deserialize)]
+struct RpcMessage {
+	id: Option<Value>,
+	method: Option<String>,
+	params: Option<Value>,
+	result: Option<Value>,
+	error: Option<RpcErrorObject>,
+}
+
+/// A JSON-RPC client for a single [NodeEndpoint].
+///
+/// WebSocket and IPC connections are opened on the first request and reused
+/// for the following ones.
+///
+/// The methods used to read the chain depend on what the node offers, see [RpcApi].
+pub struct RpcClient {
+	endpoint: NodeEndpoint,
+	timeout: Duration,
+	headers: Vec<(String, String)>,
+	tls: Option<Arc<ClientConfig>>,
+	last_id: usize,
+	connection: Option<Connection>,
+	pub(crate) api: Option<RpcApi>,
+	pub(crate) follow: Option<ChainHeadFollow>,
+}
+
+impl RpcClient {
+	pub fn new(endpoint: NodeEndpoint) -> Self {
+		Self {
+			endpoint,
+			timeout: DEFAULT_RPC_TIMEOUT,
+			headers: Vec::new(),
+			tls: None,
+			last_id: 0,
+			connection: None,
+			api: None,
+			follow: None,
+		}
+	}
+
+	/// Set extra HTTP headers, sent with each HTTP request or when opening the WebSocket connection
+	pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+		self.headers = headers;
+		self
+	}
+
+	/// Set the timeout used when connecting and waiting for a response
+	pub fn with_timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	/// Set the TLS configuration used for `https` and `wss` endpoints, see [crate::TlsConfig]
+	pub fn with_tls(mut self, tls: Arc<ClientConfig>) -> Self {
+		self.tls = Some(tls);
+		self
+	}
+
+	/// Use the given methods instead of asking the node which ones it offers
+	pub fn with_api(mut self, api: RpcApi) -> Self {
+		self.api = Some(api);
+		self
+	}
+
+	pub fn endpoint(&self) -> &NodeEndpoint {
+		&self.endpoint
+	}
+
+	/// Send a request and return its decoded `result`.
+	///
+	/// The call is a `(method, params)` tuple, as returned by the `*_raw`
+	/// functions of [subrpcer], for instance `chain::get_finalized_head_raw()`.
+	pub fn request<T: DeserializeOwned>(&mut self, call: (&str, Value)) -> Result<T> {
+		let (method, params) = call;
+		self.last_id += 1;
+		let id = self.last_id;
+		let payload = subrpcer::rpc(id, method, params);
+		log::debug!("RPC request #{id} to {}: {method}", self.endpoint);
+
+		let result = match &self.endpoint {
+			NodeEndpoint::Http(_) => self.request_http(&payload, id)?,
+			NodeEndpoint::WebSocket(_) | NodeEndpoint::Ipc(_) => self.request_connected(&payload, id)?,
+		};
+
+		serde_json::from_value::<T>(result)
+			.map_err(|e| WasmLoaderError::RpcUnexpectedResponse(format!("{method}: {e}")))
+	}
+
+	/// Read the raw value stored under `key` at the given block.
+	/// Returns `None` if there is nothing stored there.
+	pub fn get_storage(&mut self, key: &[u8], block_hash: &BlockHash) -> Result<Option<Vec<u8>>> {
+		let value = self.query_storage(key, block_hash, StorageQuery::Value)?;
+		value.map(|v| array_bytes::hex2bytes(v).map_err(|_e| WasmLoaderError::HexDecoding())).transpose()
+	}
+
+	/// Get the hash of a storage value at the given block, without fetching the value itself
+	pub fn get_storage_hash(&mut self, key: &[u8], block_hash: &BlockHash) -> Result<Option<String>> {
+		self.query_storage(key, block_hash, StorageQuery::Hash)
+	}
+
+	/// Wait for the next notification of `subscription` and return its `result`.
+	/// Subscriptions need the connection of the request that created them.
+	pub(crate) fn notification(&mut self, subscription: &str) -> Result<Value> {
+		let url = self.endpoint.to_string();
+		let connection = self
+			.connection
+			.as_mut()
+			.ok_or_else(|| WasmLoaderError::NotSupported(format!("Subscriptions with {url}")))?;
+
+		let result = Self::read_messages(connection, &url, Instant::now() + self.timeout, |data| {
+			Self::notification_of(data, subscription)
+		});
+		if result.as_ref().is_err_and(|e| e.is_transient()) {
+			self.connection = None;
+			self.follow = None;
+		}
+		result
+	}
+
+	/// The headers to send: ours and the credentials of the url unless we already have an `Authorization` header
+	pub(crate) fn all_headers(endpoint: &NodeEndpoint, headers: &[(String, String)]) -> Vec<(String, String)> {
+		let mut headers = headers.to_vec();
+		if let Some(auth) = endpoint.basic_auth() {
+			if !headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("authorization")) {
+				headers.push(("Authorization".to_string(), auth));
+			}
+		}
+		headers
+	}
+
+	fn request_http(&self, payload: &Value, id: usize) -> Result<Value> {
+		// The url we show never contains credentials
+		let url = self.endpoint.to_string();
+		let mut agent = ureq::AgentBuilder::new().timeout(self.timeout);
+		if let Some(tls) = &self.tls {
+			agent = agent.tls_config(tls.clone());
+		}
+		let agent = agent.build();
+		let mut request = agent.post(self.endpoint.connection_url()?.as_str());
+		for (name, value) in Self::all_headers(&self.endpoint, &self.headers) {
+			request = request.set(&name, &value);
+		}
+
+		let response = request.send_json(payload).map_err(|e| match e {
+			ureq::Error::Status(status, _) => WasmLoaderError::HttpStatus(status, url.to_string()),
+			ureq::Error::Transport(t) if Self::is_timeout(t.source()) => WasmLoaderError::RpcTimeout(url.to_string()),
+			ureq::Error::Transport(t) => {
+				log::debug!("{t}");
+				WasmLoaderError::HttpClient(url.to_string())
+			}
+		})?;
+		let body = response.into_string().map_err(|_e| WasmLoaderError::HttpClient(url.to_string()))?;
+		let message = Self::parse_message(&body)?;
+
+		match Self::is_response_to(&message, id) {
+			true => Self::into_result(message),
+			false => Err(WasmLoaderError::RpcUnexpectedResponse(body)),
+		}
+	}
+
+	/// Send a request over the connection to the node, opening it if needed
+	fn request_connected(&mut self, payload: &Value, id: usize) -> Result<Value> {
+		let url = self.endpoint.to_string();
+		if self.connection.is_none() {
+			self.connection = Some(self.connect()?);
+		}
+		let connection = self.connection.as_mut().expect("We just connected");
+
+		let deadline = Instant::now() + self.timeout;
+		let result = connection
+			.send(payload, &url)
+			.and_then(|_| Self::read_messages(connection, &url, deadline, |data| Self::response_to(data, id)));
+		if result.as_ref().is_err_and(|e| e.is_transient()) {
+			// We do not know in which state the connection is, we will reconnect next time
+			self.connection = None;
+			self.follow = None;
+		}
+		result
+	}
+
+	/// Read messages until `f` finds the one we are waiting for
+	fn read_messages<F>(connection: &mut Connection, url: &str, deadline: Instant, mut f: F) -> Result<Value>
+	where
+		F: FnMut(&str) -> Result<Option<Value>>,
+	{
+		loop {
+			if Instant::now() > deadline {
+				return Err(WasmLoaderError::RpcTimeout(url.to_string()));
+			}
+
+			let Some(data) = connection.read(url)? else { continue };
+			if let Some(result) = f(&data)? {
+				return Ok(result);
+			}
+		}
+	}
+
+	/// Returns the result if `data` is the response to our request `id`, `None` if it is another message
+	pub(crate) fn response_to(data: &str, id: usize) -> Result<Option<Value>> {
+		let message = Self::parse_message(data)?;
+		if Self::is_response_to(&message, id) {
+			return Self::into_result(message).map(Some);
+		}
+
+		match &message.method {
+			Some(method) => log::debug!("Skipping notification for {method}"),
+			None => log::debug!("Skipping response to another request: {:?}", message.id),
+		}
+		Ok(None)
+	}
+
+	/// Returns the result if `data` is a notification of `subscription`, `None` if it is another message
+	fn notification_of(data: &str, subscription: &str) -> Result<Option<Value>> {
+		let message = Self::parse_message(data)?;
+		match message.params {
+			Some(mut params) if params["subscription"].as_str() == Some(subscription) => {
+				Ok(Some(params["result"].take()))
+			}
+			_ => {
+				log::debug!("Skipping message {:?} while waiting for {subscription}", message.method);
+				Ok(None)
+			}
+		}
+	}
+
+	fn connect(&self) -> Result<Connection> {
+		match &self.endpoint {
+			NodeEndpoint::Ipc(path) => self.connect_ipc(path),
+			_ => self.connect_ws().map(|ws| Connection::Ws(Box::new(ws))),
+		}
+	}
+
+	/// Connect to the Unix socket of a local node, with the same timeouts as the other connections
+	#[cfg(unix)]
+	fn connect_ipc(&self, path: &Path) -> Result<Connection> {
+		let url = self.endpoint.to_string();
+		let stream = UnixStream::connect(path).map_err(|e| Self::ipc_error(e, &url))?;
+		stream.set_read_timeout(Some(self.timeout)).map_err(|e| Self::ipc_error(e, &url))?;
+		stream.set_write_timeout(Some(self.timeout)).map_err(|e| Self::ipc_error(e, &url))?;
+		Ok(Connection::Ipc(BufReader::new(stream)))
+	}
+
+	#[cfg(not(unix))]
+	fn connect_ipc(&self, _path: &Path) -> Result<Connection> {
+		Err(WasmLoaderError::NotSupported(format!("IPC endpoints on this platform: {}", self.endpoint)))
+	}
+
+	/// Connect to a WebSocket endpoint, making sure we do not wait forever
+	/// neither when connecting nor when waiting for data.
+	fn connect_ws(&self) -> Result<WsStream> {
+		let (url, timeout) = (self.endpoint.to_string(), self.timeout);
+		let parsed: Url = self.endpoint.connection_url()?;
+		let addrs = parsed.socket_addrs(|| None).map_err(|_e| WasmLoaderError::WsClient(url.to_string()))?;
+
+		let stream = addrs
+			.iter()
+			.find_map(|addr| TcpStream::connect_timeout(addr, timeout).ok())
+			.ok_or_else(|| WasmLoaderError::WsClient(url.to_string()))?;
+		stream.set_read_timeout(Some(timeout)).map_err(|_e| WasmLoaderError::WsClient(url.to_string()))?;
+		stream.set_write_timeout(Some(timeout)).map_err(|_e| WasmLoaderError::WsClient(url.to_string()))?;
+
+		let request = Self::ws_request(&self.endpoint, &self.headers)?;
+		let connector = self.tls.clone().map(Connector::Rustls);
+		let (ws, _response) = tungstenite::client_tls_with_config(request, stream, None, connector).map_err(|e| {
+			log::debug!("{e}");
+			WasmLoaderError::WsClient(url.to_string())
+		})?;
+		Ok(ws)
+	}
+
+	/// The request opening a WebSocket connection to `endpoint`, with our headers
+	pub(crate) fn ws_request(endpoint: &NodeEndpoint, headers: &[(String, String)]) -> Result<Request> {
+		let url = endpoint.connection_url()?;
+		let mut request = url.as_str().into_client_request().map_err(|e| Self::ws_error(e, &endpoint.to_string()))?;
+		for (name, value) in Self::all_headers(endpoint, headers) {
+			let header = HeaderName::from_bytes(name.as_bytes()).ok().zip(HeaderValue::from_str(&value).ok());
+			let (name, value) = header.ok_or_else(|| WasmLoaderError::InvalidHeader(name.to_string()))?;
+			request.headers_mut().insert(name, value);
+		}
+		Ok(request)
+	}
+
+	pub(crate) fn is_timeout(e: Option<&(dyn Error + 'static)>) -> bool {
+		e.and_then(|e| e.downcast_ref::<io::Error>())
+			.is_some_and(|e| matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut))
+	}
+
+	pub(crate) fn ws_error(e: tungstenite::Error, url: &str) -> WasmLoaderError {
+		match e {
+			tungstenite::Error::Io(io) if Self::is_timeout(Some(&io)) => WasmLoaderError::RpcTimeout(url.to_string()),
+			e => {
+				log::debug!("{e}");
+				WasmLoaderError::WsClient(url.to_string())
+			}
+		}
+	}
+
+	#[cfg(unix)]
+	pub(crate) fn ipc_error(e: io::Error, url: &str) -> WasmLoaderError {
+		match Self::is_timeout(Some(&e)) {
+			true => WasmLoaderError::RpcTimeout(url.to_string()),
+			false => {
+				log::debug!("{e}");
+				WasmLoaderError::IpcClient(url.to_string())
+			}
+		}
+	}
+
+	fn parse_message(data: &str) -> Result<RpcMessage> {
+		serde_json::from_str::<RpcMessage>(data).map_err(|_e| WasmLoaderError::RpcUnexpectedResponse(data.to_string()))
+	}
+
+	fn is_response_to(message: &RpcMessage, id: usize) -> bool {
+		match &message.id {
+			Some(Value::Number(n)) => n.as_u64() == Some(id as u64),
+			Some(Value::String(s)) => s == &id.to_string(),
+			_ => false,
+		}
+	}
+
+	fn into_result(message: RpcMessage) -> Result<Value> {
+		match message.error {
+			Some(error) => {
+				let message = match error.data {
+					Some(data) => format!("{} ({data})", error.message),
+					None => error.message,
+				};
+				Err(WasmLoaderError::RpcError(error.code, message))
+			}
+			None => Ok(message.result.unwrap_or(Value::Null)),
+		}
+	}
+}
+
+/// A fake node for the tests, also available to the dependent crates with the `test-utils` feature
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock {
+	use crate::NodeEndpoint;
+	use serde_json::Value;
+	use tungstenite::{
+		handshake::server::{ErrorResponse, Request, Response},
+		http::StatusCode,
+		Message,
+	};
+
+	/// Start a WebSocket server answering each request with the messages returned by `handler`.
+	/// `{id}` is replaced by the id of the request.
+	pub fn mock_node_with<F>(handler: F) -> NodeEndpoint
+	where
+		F: Fn(&Value) -> Vec<String> + Send + 'static,
+	{
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed binding");
+		let port = listener.local_addr().expect("Failed getting address").port();
+
+		std::thread::spawn(move || {
+			let (stream, _) = listener.accept().expect("Failed accepting connection");
+			let mut ws = tungstenite::accept(stream).expect("Failed handshake");
+			while let Ok(Message::Text(request)) = ws.read() {
+				let request: Value = serde_json::from_str(&request).expect("Failed parsing request");
+				for reply in handler(&request) {
+					let reply = reply.replace("{id}", &request["id"].to_string());
+					ws.send(Message::Text(reply)).expect("Failed sending reply");
+				}
+			}
+		});
+
+		NodeEndpoint::WebSocket(format!("ws://127.0.0.1:{port}"))
+	}
+
+	/// Start a WebSocket server answering each request with the provided messages.
+	pub fn mock_node(replies: Vec<&'static str>) -> NodeEndpoint {
+		mock_node_with(move |_request| replies.iter().map(|r| r.to_string()).collect())
+	}
+
+	/// Start a node listening on a Unix socket, answering each request with the provided messages.
+	/// `{id}` is replaced by the id of the request.
+	#[cfg(unix)]
+	pub fn mock_ipc_node(replies: Vec<&'static str>) -> NodeEndpoint {
+		use std::{
+			io::{BufRead, BufReader, Write},
+			os::unix::net::UnixListener,
+			sync::atomic::{AtomicUsize, Ordering},
+		};
+
+		static SOCKETS: AtomicUsize = AtomicUsize::new(0);
+		let name = format!("wasm-loader-{}-{}.sock", std::process::id(), SOCKETS.fetch_add(1, Ordering::Relaxed));
+		let path = std::env::temp_dir().join(name);
+		let _ = std::fs::remove_file(&path);
+		let listener = UnixListener::bind(&path).expect("Failed binding");
+
+		std::thread::spawn(move || {
+			let (mut stream, _) = listener.accept().expect("Failed accepting connection");
+			let mut reader = BufReader::new(stream.try_clone().expect("Failed cloning stream"));
+			let mut line = String::new();
+			while reader.read_line(&mut line).is_ok_and(|n| n > 0) {
+				let request: Value = serde_json::from_str(&line).expect("Failed parsing request");
+				for reply in &replies {
+					let reply = reply.replace("{id}", &request["id"].to_string());
+					writeln!(stream, "{reply}").expect("Failed sending reply");
+				}
+				line.clear();
+			}
+		});
+
+		NodeEndpoint::Ipc(path)
+	}
+
+	/// Start an HTTP server answering each request with `reply`.
+	#[cfg(feature = "async")]
+	pub fn mock_http_node(reply: &'static str) -> NodeEndpoint {
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed binding");
+		let port = listener.local_addr().expect("Failed getting address").port();
+
+		std::thread::spawn(move || {
+			for stream in listener.incoming() {
+				let Ok(mut stream) = stream else { return };
+				serve_http(&mut stream, reply).expect("Failed serving request");
+			}
+		});
+
+		NodeEndpoint::Http(format!("http://127.0.0.1:{port}"))
+	}
+
+	/// Answer one HTTP request with `reply`
+	fn serve_http<S: std::io::Read + std::io::Write>(stream: &mut S, reply: &str) -> std::io::Result<()> {
+		use std::io::{BufRead, BufReader, Read};
+
+		let mut reader = BufReader::new(&mut *stream);
+		let mut length = 0;
+		let mut line = String::new();
+		while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+			if let Some((name, value)) = line.split_once(':') {
+				if name.eq_ignore_ascii_case("content-length") {
+					length = value.trim().parse().expect("Failed parsing length");
+				}
+			}
+			line.clear();
+		}
+		let mut body = vec![0; length];
+		reader.read_exact(&mut body)?;
+
+		let request: Value = serde_json::from_slice(&body).expect("Failed parsing request");
+		let reply = reply.replace("{id}", &request["id"].to_string());
+		let response = format!(
+			"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+			reply.len()
+		);
+		stream.write_all(response.as_bytes())?;
+		stream.flush()
+	}
+
+	/// Start a node serving `https`, or `wss` if `ws` is set, with a certificate of our test CA, see `data/tls`.
+	/// If `client_auth` is set, clients must present a certificate of that CA.
+	/// It answers each request with `reply`.
+	pub fn mock_tls_node(ws: bool, client_auth: bool, reply: &'static str) -> NodeEndpoint {
+		use rustls::{
+			crypto::ring, server::WebPkiClientVerifier, RootCertStore, ServerConfig, ServerConnection, StreamOwned,
+		};
+		use std::sync::Arc;
+
+		let pem = |name: &str| std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/tls").join(name);
+		let read = |name: &str| std::fs::read(pem(name)).expect("Failed reading test certificate");
+		let certs = rustls_pemfile::certs(&mut read("node.pem").as_slice())
+			.collect::<Result<Vec<_>, _>>()
+			.expect("Failed parsing certificate");
+		let key = rustls_pemfile::private_key(&mut read("node.key").as_slice())
+			.expect("Failed parsing key")
+			.expect("Missing key");
+
+		let provider = Arc::new(ring::default_provider());
+		let builder = ServerConfig::builder_with_provider(provider.clone())
+			.with_safe_default_protocol_versions()
+			.expect("Failed building config");
+		let builder = match client_auth {
+			true => {
+				let mut roots = RootCertStore::empty();
+				roots.add_parsable_certificates(
+					rustls_pemfile::certs(&mut read("ca.pem").as_slice()).map(|c| c.expect("Failed parsing CA")),
+				);
+				let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+					.build()
+					.expect("Failed building verifier");
+				builder.with_client_cert_verifier(verifier)
+			}
+			false => builder.with_no_client_auth(),
+		};
+		let config = Arc::new(builder.with_single_cert(certs, key).expect("Failed building config"));
+
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed binding");
+		let port = listener.local_addr().expect("Failed getting address").port();
+
+		std::thread::spawn(move || {
+			for stream in listener.incoming() {
+				let Ok(stream) = stream else { return };
+				let mut tls =
+					StreamOwned::new(ServerConnection::new(config.clone()).expect("Failed accepting"), stream);
+
+				// Clients rejecting our certificate, or rejected, show up as failed handshakes
+				if !ws {
+					let _ = serve_http(&mut tls, reply);
+					continue;
+				}
+				let Ok(mut ws) = tungstenite::accept(tls) else { continue };
+				while let Ok(Message::Text(request)) = ws.read() {
+					let request: Value = serde_json::from_str(&request).expect("Failed parsing request");
+					let reply = reply.replace("{id}", &request["id"].to_string());
+					ws.send(Message::Text(reply)).expect("Failed sending reply");
+				}
+			}
+		});
+
+		match ws {
+			true => NodeEndpoint::WebSocket(format!("wss://127.0.0.1:{port}")),
+			false => NodeEndpoint::Http(format!("https://127.0.0.1:{port}")),
+		}
+	}
+
+	/// Start a WebSocket server refusing the handshake with a 401 unless the header `name` is `value`.
+	/// It then answers each request with `reply`.
+	#[allow(clippy::result_large_err)] // The signature of the callback is imposed by tungstenite
+	pub fn mock_auth_node(name: &'static str, value: &'static str, reply: &'static str) -> NodeEndpoint {
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed binding");
+		let port = listener.local_addr().expect("Failed getting address").port();
+
+		std::thread::spawn(move || {
+			let (stream, _) = listener.accept().expect("Failed accepting connection");
+			let check = |request: &Request, response: Response| match request.headers().get(name) {
+				Some(v) if v == value => Ok(response),
+				_ => {
+					let mut refusal = ErrorResponse::new(None);
+					*refusal.status_mut() = StatusCode::UNAUTHORIZED;
+					Err(refusal)
+				}
+			};
+			let Ok(mut ws) = tungstenite::accept_hdr(stream, check) else { return };
+			while let Ok(Message::Text(request)) = ws.read() {
+				let request: Value = serde_json::from_str(&request).expect("Failed parsing request");
+				let reply = reply.replace("{id}", &request["id"].to_string());
+				ws.send(Message::Text(reply)).expect("Failed sending reply");
+			}
+		});
+
+		NodeEndpoint::WebSocket(format!("ws://127.0.0.1:{port}"))
+	}
+}
+
+#[cfg(test)]
+mod tests_rpc {
+	use super::{
+		mock::{mock_auth_node, mock_node},
+		*,
+	};
+	use std::str::FromStr;
+
+	#[test]
+	fn it_matches_request_ids() {
+		let message =
+			RpcClient::parse_message(r#"{"jsonrpc":"2.0","id":3,"result":"0x1234"}"#).expect("Failed parsing message");
+		assert!(RpcClient::is_response_to(&message, 3));
+		assert!(!RpcClient::is_response_to(&message, 2));
+
+		let message = RpcClient::parse_message(r#"{"jsonrpc":"2.0","id":"3","result":"0x1234"}"#)
+			.expect("Failed parsing message");
+		assert!(RpcClient::is_response_to(&message, 3));
+	}
+
+	#[test]
+	fn it_skips_notifications() {
+		let message = RpcClient::parse_message(
+			r#"{"jsonrpc":"2.0","method":"chain_newHead","params":{"subscription":"abc","result":{}}}"#,
+		)
+		.expect("Failed parsing message");
+		assert!(!RpcClient::is_response_to(&message, 1));
+	}
+
+	#[test]
+	fn it_returns_results() {
+		let message =
+			RpcClient::parse_message(r#"{"jsonrpc":"2.0","id":1,"result":"0x1234"}"#).expect("Failed parsing message");
+		assert_eq!(RpcClient::into_result(message), Ok(Value::String("0x1234".to_string())));
+
+		let message =
+			RpcClient::parse_message(r#"{"jsonrpc":"2.0","id":1,"result":null}"#).expect("Failed parsing message");
+		assert_eq!(RpcClient::into_result(message), Ok(Value::Null));
+	}
+
+	#[test]
+	fn it_returns_error_objects() {
+		let message = RpcClient::parse_message(
+			r#"{"jsonrpc":"2.0","id":1,"error":{"code":4003,"message":"State already discarded","data":"0xabcd"}}"#,
+		)
+		.expect("Failed parsing message");
+		assert_eq!(
+			RpcClient::into_result(message),
+			Err(WasmLoaderError::RpcError(4003, "State already discarded (\"0xabcd\")".to_string()))
+		);
+	}
+
+	#[test]
+	fn it_rejects_garbage() {
+		assert!(RpcClient::parse_message("foobar").is_err());
+	}
+
+	#[test]
+	fn it_talks_to_a_ws_node() {
+		let endpoint = mock_node(vec![
+			r#"{"jsonrpc":"2.0","method":"chain_newHead","params":{"subscription":"abc","result":{}}}"#,
+			r#"{"jsonrpc":"2.0","id":999,"result":"0xdead"}"#,
+			r#"{"jsonrpc":"2.0","id":{id},"result":"0x1234"}"#,
+		]);
+		let mut client = RpcClient::new(endpoint);
+
+		let first: String = client.request(subrpcer::chain::get_finalized_head_raw()).expect("Request failed");
+		let second: String = client.request(subrpcer::chain::get_finalized_head_raw()).expect("Request failed");
+		assert_eq!(first, "0x1234");
+		assert_eq!(second, "0x1234");
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn it_talks_to_an_ipc_node() {
+		let endpoint = super::mock::mock_ipc_node(vec![
+			r#"{"jsonrpc":"2.0","method":"chain_newHead","params":{"subscription":"abc","result":{}}}"#,
+			r#"{"jsonrpc":"2.0","id":{id},"result":"0x1234"}"#,
+			r#"{"jsonrpc":"2.0","method":"chain_newHead","params":{"subscription":"abc","result":"0x5678"}}"#,
+		]);
+		let mut client = RpcClient::new(endpoint);
+
+		let first: String = client.request(subrpcer::chain::get_finalized_head_raw()).expect("Request failed");
+		assert_eq!(client.notification("abc"), Ok(Value::String("0x5678".into())));
+		let second: String = client.request(subrpcer::chain::get_finalized_head_raw()).expect("Request failed");
+		assert_eq!(first, "0x1234");
+		assert_eq!(second, "0x1234");
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn it_fails_on_a_missing_socket() {
+		let endpoint = NodeEndpoint::from_str("ipc:///nonexistent/node.sock").expect("Failed parsing endpoint");
+		let res = RpcClient::new(endpoint).request::<String>(subrpcer::chain::get_finalized_head_raw());
+		assert_eq!(res, Err(WasmLoaderError::IpcClient("ipc:///nonexistent/node.sock".into())));
+	}
+
+	#[test]
+	fn it_reports_ws_errors() {
+		let endpoint =
+			mock_node(vec![r#"{"jsonrpc":"2.0","id":{id},"error":{"code":-32601,"message":"Method not found"}}"#]);
+		let mut client = RpcClient::new(endpoint);
+
+		let res = client.request::<String>(subrpcer::chain::get_finalized_head_raw());
+		assert_eq!(res, Err(WasmLoaderError::RpcError(-32601, "Method not found".to_string())));
+	}
+
+	#[test]
+	fn it_times_out() {
+		let endpoint = mock_node(vec![]);
+		let mut client = RpcClient::new(endpoint).with_timeout(Duration::from_millis(200));
+
+		let res = client.request::<String>(subrpcer::chain::get_finalized_head_raw());
+		assert!(matches!(res, Err(WasmLoaderError::RpcTimeout(_))));
+	}
+
+	#[test]
+	fn it_sends_headers() {
+		const REPLY: &str = r#"{"jsonrpc":"2.0","id":{id},"result":"0x1234"}"#;
+
+		let endpoint = mock_auth_node("x-api-key", "secret", REPLY);
+		let mut client = RpcClient::new(endpoint).with_headers(vec![("X-Api-Key".into(), "secret".into())]);
+		let res: String = client.request(subrpcer::chain::get_finalized_head_raw()).expect("Request failed");
+		assert_eq!(res, "0x1234");
+
+		let endpoint = mock_auth_node("x-api-key", "secret", REPLY);
+		let mut client = RpcClient::new(endpoint);
+		assert!(client.request::<String>(subrpcer::chain::get_finalized_head_raw()).is_err());
+	}
+
+	#[test]
+	fn it_sends_url_credentials() {
+		let endpoint =
+			mock_auth_node("authorization", "Basic dXNlcjpwYXNz", r#"{"jsonrpc":"2.0","id":{id},"result":"0x1234"}"#);
+		let url = endpoint.to_string().replace("ws://", "ws://user:pass@");
+		let mut client = RpcClient::new(NodeEndpoint::from_str(&url).expect("Failed parsing endpoint"));
+		let res: String = client.request(subrpcer::chain::get_finalized_head_raw()).expect("Request failed");
+		assert_eq!(res, "0x1234");
+	}
+}
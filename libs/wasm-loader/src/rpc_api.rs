@@ -0,0 +1,430 @@
+//! Reading the chain with either the legacy JSON-RPC methods or the ones of the new spec,
+//! see <https://paritytech.github.io/json-rpc-interface-spec/>.
+
+use crate::{error::*, onchain_block::Header, BlockHash, RpcClient};
+use scale::Decode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sp_version::RuntimeVersion;
+use subrpcer::{chain, state};
+
+/// The family of JSON-RPC methods used to read the chain of a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcApi {
+	/// The legacy `chain_*` and `state_*` methods
+	Legacy,
+
+	/// The `archive_v1_*` methods of the new spec, served by archive nodes.
+	/// They have no notion of best block, the finalized block is used instead.
+	Archive,
+
+	/// The `chainHead_v1_*` methods of the new spec. They only give access to the blocks
+	/// the node pinned for us: the latest finalized block and its descendants.
+	ChainHead,
+}
+
+impl RpcApi {
+	/// Pick the methods to use among the ones the node offers, as listed by `rpc_methods`.
+	/// We stick to the legacy methods as long as the node offers them.
+	pub fn from_methods(methods: &[String]) -> Self {
+		let offers = |method: &str| methods.iter().any(|m| m == method);
+
+		if offers("state_getStorage") {
+			RpcApi::Legacy
+		} else if offers("archive_v1_storage") {
+			RpcApi::Archive
+		} else if offers("chainHead_v1_follow") {
+			RpcApi::ChainHead
+		} else {
+			RpcApi::Legacy
+		}
+	}
+}
+
+/// What we want to know about a storage item
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum StorageQuery {
+	Value,
+	Hash,
+}
+
+impl StorageQuery {
+	/// The `type` of the item in the queries of the new spec, also the field holding the answer
+	fn as_str(&self) -> &'static str {
+		match self {
+			StorageQuery::Value => "value",
+			StorageQuery::Hash => "hash",
+		}
+	}
+}
+
+/// A `chainHead_v1_follow` subscription and what it told us about the chain so far
+#[derive(Debug)]
+pub(crate) struct ChainHeadFollow {
+	subscription: String,
+	finalized: Option<BlockHash>,
+	best: Option<BlockHash>,
+}
+
+impl ChainHeadFollow {
+	fn update(&mut self, event: &Value) -> Result<()> {
+		let last = |hashes: &Value| hashes.as_array().and_then(|h| h.last()).and_then(|h| h.as_str()).map(String::from);
+
+		match event["event"].as_str() {
+			Some("initialized") | Some("finalized") => {
+				if let Some(hash) = last(&event["finalizedBlockHashes"]) {
+					self.finalized = Some(hash);
+				}
+			}
+			Some("bestBlockChanged") => self.best = event["bestBlockHash"].as_str().map(String::from),
+			Some("stop") => return Err(WasmLoaderError::RpcUnexpectedResponse("chainHead_v1_follow stopped".into())),
+			_ => log::trace!("Ignoring {event}"),
+		}
+		Ok(())
+	}
+}
+
+#[derive(Deserialize)]
+struct RpcMethods {
+	methods: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OperationStarted {
+	result: String,
+	operation_id: Option<String>,
+}
+
+impl RpcClient {
+	/// The methods used with this node, asking it with `rpc_methods` the first time
+	pub fn api(&mut self) -> Result<RpcApi> {
+		if let Some(api) = self.api {
+			return Ok(api);
+		}
+
+		let api = match self.request::<RpcMethods>(("rpc_methods", json!([]))) {
+			Ok(res) => RpcApi::from_methods(&res.methods),
+			// A node that cannot list its methods predates the new spec
+			Err(e @ (WasmLoaderError::RpcError(_, _) | WasmLoaderError::RpcUnexpectedResponse(_))) => {
+				log::debug!("Could not list the methods of {}: {e}", self.endpoint());
+				RpcApi::Legacy
+			}
+			Err(e) => return Err(e),
+		};
+		log::debug!("Using the {api:?} methods with {}", self.endpoint());
+		self.api = Some(api);
+		Ok(api)
+	}
+
+	/// The hash of the best block
+	pub fn best_hash(&mut self) -> Result<BlockHash> {
+		match self.api()? {
+			RpcApi::Legacy => self
+				.request::<Option<BlockHash>>(chain::get_block_hash_raw(None::<u64>))?
+				.ok_or_else(|| WasmLoaderError::OnchainBlockParsing("best".into())),
+			RpcApi::Archive => self.finalized_hash(),
+			RpcApi::ChainHead => {
+				let follow = self.chain_head_follow()?;
+				follow
+					.best
+					.clone()
+					.or_else(|| follow.finalized.clone())
+					.ok_or_else(|| WasmLoaderError::RpcUnexpectedResponse("chainHead_v1_follow gave no block".into()))
+			}
+		}
+	}
+
+	/// The hash of the latest finalized block
+	pub fn finalized_hash(&mut self) -> Result<BlockHash> {
+		match self.api()? {
+			RpcApi::Legacy => self.request(chain::get_finalized_head_raw()),
+			RpcApi::Archive => {
+				let number: u64 = self.request(("archive_v1_finalizedHeight", json!([])))?;
+				self.hash_at(number)?
+					.ok_or_else(|| WasmLoaderError::OnchainBlockParsing(format!("Block #{number} not found")))
+			}
+			RpcApi::ChainHead => self.chain_head_follow()?.finalized.clone().ok_or_else(|| {
+				WasmLoaderError::RpcUnexpectedResponse("chainHead_v1_follow gave no finalized block".into())
+			}),
+		}
+	}
+
+	/// The hash of the block `number`, `None` if the node does not know it
+	pub fn hash_at(&mut self, number: u64) -> Result<Option<BlockHash>> {
+		match self.api()? {
+			RpcApi::Legacy => self.request(chain::get_block_hash_raw(Some(number))),
+			// Above the finalized block, there may be a block per fork
+			RpcApi::Archive => self
+				.request::<Vec<BlockHash>>(("archive_v1_hashByHeight", json!([number])))
+				.map(|h| h.into_iter().next()),
+			RpcApi::ChainHead => Err(WasmLoaderError::NotSupported("Blocks by number with chainHead_v1".into())),
+		}
+	}
+
+	/// The number of the best block
+	pub fn best_number(&mut self) -> Result<u64> {
+		match self.api()? {
+			RpcApi::Legacy => self.request::<Header>(chain::get_header_raw(None::<BlockHash>))?.number(),
+			RpcApi::Archive => self.request(("archive_v1_finalizedHeight", json!([]))),
+			RpcApi::ChainHead => Err(WasmLoaderError::NotSupported("Block numbers with chainHead_v1".into())),
+		}
+	}
+
+	/// The number of the finalized block
+	pub fn finalized_number(&mut self) -> Result<u64> {
+		match self.api()? {
+			RpcApi::Legacy => {
+				let hash = self.finalized_hash()?;
+				self.request::<Header>(chain::get_header_raw(Some(hash)))?.number()
+			}
+			RpcApi::Archive => self.request(("archive_v1_finalizedHeight", json!([]))),
+			RpcApi::ChainHead => Err(WasmLoaderError::NotSupported("Block numbers with chainHead_v1".into())),
+		}
+	}
+
+	/// The version of the runtime in place at the given block
+	pub fn runtime_version(&mut self, block_hash: &BlockHash) -> Result<RuntimeVersion> {
+		match self.api()? {
+			RpcApi::Legacy => self.request(state::get_runtime_version_raw(Some(block_hash))),
+			RpcApi::Archive => {
+				let result: Value = self.request(("archive_v1_call", json!([block_hash, "Core_version", "0x"])))?;
+				let value = match result["success"].as_bool() {
+					Some(true) => result["value"].as_str(),
+					_ => return Err(WasmLoaderError::RpcUnexpectedResponse(result["error"].to_string())),
+				};
+				let bytes = value
+					.and_then(|v| array_bytes::hex2bytes(v).ok())
+					.ok_or_else(|| WasmLoaderError::RpcUnexpectedResponse(result.to_string()))?;
+				RuntimeVersion::decode(&mut &bytes[..])
+					.map_err(|e| WasmLoaderError::RpcUnexpectedResponse(format!("Invalid runtime version: {e}")))
+			}
+			RpcApi::ChainHead => Err(WasmLoaderError::NotSupported("Runtime versions with chainHead_v1".into())),
+		}
+	}
+
+	/// Read the value, or its hash, stored under `key` at the given block, hex encoded
+	pub(crate) fn query_storage(
+		&mut self,
+		key: &[u8],
+		block_hash: &BlockHash,
+		query: StorageQuery,
+	) -> Result<Option<String>> {
+		let key = array_bytes::bytes2hex("0x", key);
+
+		match (self.api()?, query) {
+			(RpcApi::Legacy, StorageQuery::Value) => self.request(state::get_storage_raw(key, Some(block_hash))),
+			(RpcApi::Legacy, StorageQuery::Hash) => self.request(state::get_storage_hash_raw(key, Some(block_hash))),
+			(RpcApi::Archive, query) => self.archive_storage(&key, block_hash, query),
+			(RpcApi::ChainHead, query) => self.chain_head_storage(&key, block_hash, query),
+		}
+	}
+
+	/// The items come as `archive_v1_storageEvent` notifications until `storageDone`
+	fn archive_storage(&mut self, key: &str, block_hash: &BlockHash, query: StorageQuery) -> Result<Option<String>> {
+		let items = json!([{ "key": key, "type": query.as_str() }]);
+		let subscription: String = self.request(("archive_v1_storage", json!([block_hash, items, null])))?;
+
+		let mut found = None;
+		loop {
+			let event = self.notification(&subscription)?;
+			match event["event"].as_str() {
+				Some("storage") if event["key"] == key => found = event[query.as_str()].as_str().map(String::from),
+				Some("storageDone") => return Ok(found),
+				Some("storageError") => return Err(WasmLoaderError::RpcUnexpectedResponse(event["error"].to_string())),
+				_ => log::trace!("Ignoring {event}"),
+			}
+		}
+	}
+
+	/// The items come as events of our `chainHead_v1_follow` subscription until `operationStorageDone`
+	fn chain_head_storage(&mut self, key: &str, block_hash: &BlockHash, query: StorageQuery) -> Result<Option<String>> {
+		let subscription = self.chain_head_follow()?.subscription.clone();
+		let items = json!([{ "key": key, "type": query.as_str() }]);
+		let started: OperationStarted =
+			self.request(("chainHead_v1_storage", json!([subscription, block_hash, items, null])))?;
+		let operation = match started {
+			OperationStarted { result, operation_id: Some(id) } if result == "started" => id,
+			OperationStarted { result, .. } => return Err(WasmLoaderError::RpcUnexpectedResponse(result)),
+		};
+
+		let mut found = None;
+		loop {
+			let event = self.notification(&subscription)?;
+			if event["operationId"].as_str() != Some(&operation) {
+				if let Err(e) = self.follow.as_mut().map_or(Ok(()), |f| f.update(&event)) {
+					self.follow = None;
+					return Err(e);
+				}
+				continue;
+			}
+
+			match event["event"].as_str() {
+				Some("operationStorageItems") => {
+					let item = event["items"].as_array().and_then(|items| items.iter().find(|i| i["key"] == key));
+					if let Some(value) = item.and_then(|i| i[query.as_str()].as_str()) {
+						found = Some(value.to_string());
+					}
+				}
+				Some("operationWaitingForContinue") => {
+					self.request::<Value>(("chainHead_v1_continue", json!([subscription, operation])))?;
+				}
+				Some("operationStorageDone") => return Ok(found),
+				_ => return Err(WasmLoaderError::RpcUnexpectedResponse(event.to_string())),
+			}
+		}
+	}
+
+	/// Follow the chain, once per connection, and wait until the node told us about its finalized and best blocks
+	fn chain_head_follow(&mut self) -> Result<&ChainHeadFollow> {
+		if self.follow.is_none() {
+			let subscription: String = self.request(("chainHead_v1_follow", json!([false])))?;
+			let mut follow = ChainHeadFollow { subscription, finalized: None, best: None };
+
+			// The best block is reported after the blocks the node pinned for us
+			while follow.best.is_none() {
+				let event = self.notification(&follow.subscription)?;
+				follow.update(&event)?;
+			}
+			self.follow = Some(follow);
+		}
+		Ok(self.follow.as_ref().expect("We just followed the chain"))
+	}
+}
+
+#[cfg(test)]
+mod tests_rpc_api {
+	use super::*;
+	use crate::rpc::mock::mock_node_with;
+	use scale::Encode;
+
+	const BLOCK: &str = "0x4d6a0bca208b85d41833a7f35cf73d1ae6974f4bad8ab576e2c3f751d691fe6c";
+
+	fn reply(result: &str) -> String {
+		format!(r#"{{"jsonrpc":"2.0","id":{{id}},"result":{result}}}"#)
+	}
+
+	fn notification(method: &str, subscription: &str, result: &str) -> String {
+		format!(
+			r#"{{"jsonrpc":"2.0","method":"{method}","params":{{"subscription":"{subscription}","result":{result}}}}}"#
+		)
+	}
+
+	fn methods(methods: &[&str]) -> Vec<String> {
+		vec![reply(&format!(r#"{{"methods":{}}}"#, serde_json::to_string(methods).expect("Failed serializing")))]
+	}
+
+	/// A node only offering the `archive_v1` methods
+	fn archive_node() -> RpcClient {
+		let endpoint = mock_node_with(|request: &Value| match request["method"].as_str() {
+			Some("rpc_methods") => methods(&["archive_v1_storage", "archive_v1_hashByHeight"]),
+			Some("archive_v1_finalizedHeight") => vec![reply("42")],
+			Some("archive_v1_call") => {
+				let version = RuntimeVersion { spec_version: 1_002_000, ..Default::default() };
+				let value = array_bytes::bytes2hex("0x", version.encode());
+				vec![reply(&format!(r#"{{"success":true,"value":"{value}"}}"#))]
+			}
+			Some("archive_v1_hashByHeight") => vec![reply(&format!(r#"["{BLOCK}"]"#))],
+			Some("archive_v1_storage") => {
+				let item = &request["params"][1][0];
+				let field = item["type"].as_str().expect("Missing type");
+				vec![
+					reply(r#""sub""#),
+					notification(
+						"archive_v1_storageEvent",
+						"sub",
+						&format!(r#"{{"event":"storage","key":{},"{field}":"0x1234"}}"#, item["key"]),
+					),
+					notification("archive_v1_storageEvent", "sub", r#"{"event":"storageDone"}"#),
+				]
+			}
+			_ => vec![r#"{"jsonrpc":"2.0","id":{id},"error":{"code":-32601,"message":"Method not found"}}"#.into()],
+		});
+		RpcClient::new(endpoint)
+	}
+
+	/// A node only offering the `chainHead_v1` methods
+	fn chain_head_node() -> RpcClient {
+		let endpoint = mock_node_with(|request: &Value| match request["method"].as_str() {
+			Some("rpc_methods") => methods(&["chainHead_v1_follow", "chainHead_v1_storage"]),
+			Some("chainHead_v1_follow") => vec![
+				reply(r#""follow""#),
+				notification(
+					"chainHead_v1_followEvent",
+					"follow",
+					&format!(r#"{{"event":"initialized","finalizedBlockHashes":["{BLOCK}"]}}"#),
+				),
+				notification("chainHead_v1_followEvent", "follow", r#"{"event":"newBlock","blockHash":"0x02"}"#),
+				notification(
+					"chainHead_v1_followEvent",
+					"follow",
+					r#"{"event":"bestBlockChanged","bestBlockHash":"0x02"}"#,
+				),
+			],
+			Some("chainHead_v1_storage") => {
+				let item = &request["params"][2][0];
+				let field = item["type"].as_str().expect("Missing type");
+				vec![
+					reply(r#"{"result":"started","operationId":"op1","discardedItems":0}"#),
+					notification("chainHead_v1_followEvent", "follow", r#"{"event":"newBlock","blockHash":"0x03"}"#),
+					notification(
+						"chainHead_v1_followEvent",
+						"follow",
+						&format!(
+							r#"{{"event":"operationStorageItems","operationId":"op1","items":[{{"key":{},"{field}":"0x1234"}}]}}"#,
+							item["key"]
+						),
+					),
+					notification(
+						"chainHead_v1_followEvent",
+						"follow",
+						r#"{"event":"operationStorageDone","operationId":"op1"}"#,
+					),
+				]
+			}
+			_ => vec![r#"{"jsonrpc":"2.0","id":{id},"error":{"code":-32601,"message":"Method not found"}}"#.into()],
+		});
+		RpcClient::new(endpoint)
+	}
+
+	#[test]
+	fn it_picks_the_api() {
+		let methods = |m: &[&str]| m.iter().map(|m| m.to_string()).collect::<Vec<_>>();
+		assert_eq!(RpcApi::from_methods(&methods(&["state_getStorage", "archive_v1_storage"])), RpcApi::Legacy);
+		assert_eq!(RpcApi::from_methods(&methods(&["archive_v1_storage", "chainHead_v1_follow"])), RpcApi::Archive);
+		assert_eq!(RpcApi::from_methods(&methods(&["chainHead_v1_follow"])), RpcApi::ChainHead);
+		assert_eq!(RpcApi::from_methods(&[]), RpcApi::Legacy);
+	}
+
+	#[test]
+	fn it_falls_back_to_legacy() {
+		let endpoint = mock_node_with(|_request: &Value| {
+			vec![r#"{"jsonrpc":"2.0","id":{id},"error":{"code":-32601,"message":"Method not found"}}"#.into()]
+		});
+		assert_eq!(RpcClient::new(endpoint).api(), Ok(RpcApi::Legacy));
+	}
+
+	#[test]
+	fn it_reads_storage_with_archive() {
+		let mut client = archive_node();
+		assert_eq!(client.api(), Ok(RpcApi::Archive));
+		assert_eq!(client.finalized_hash(), Ok(BLOCK.to_string()));
+		assert_eq!(client.best_number(), Ok(42));
+		assert_eq!(client.finalized_number(), Ok(42));
+		assert_eq!(client.runtime_version(&BLOCK.into()).map(|v| v.spec_version), Ok(1_002_000));
+		assert_eq!(client.get_storage(b":code", &BLOCK.into()), Ok(Some(vec![0x12, 0x34])));
+		assert_eq!(client.get_storage_hash(b":code", &BLOCK.into()), Ok(Some("0x1234".into())));
+	}
+
+	#[test]
+	fn it_reads_storage_with_chain_head() {
+		let mut client = chain_head_node();
+		assert_eq!(client.api(), Ok(RpcApi::ChainHead));
+		assert_eq!(client.finalized_hash(), Ok(BLOCK.to_string()));
+		assert_eq!(client.best_hash(), Ok("0x02".to_string()));
+		assert_eq!(client.get_storage(b":code", &BLOCK.into()), Ok(Some(vec![0x12, 0x34])));
+		assert_eq!(client.get_storage_hash(b":code", &BLOCK.into()), Ok(Some("0x1234".into())));
+		assert!(matches!(client.hash_at(1), Err(WasmLoaderError::NotSupported(_))));
+		assert!(matches!(client.finalized_number(), Err(WasmLoaderError::NotSupported(_))));
+	}
+}
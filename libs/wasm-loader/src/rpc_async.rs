@@ -0,0 +1,311 @@
+//! The async counterpart of [RpcClient], available with the `async` feature.
+
+use crate::{error::*, rpc::DEFAULT_RPC_TIMEOUT, BlockHash, NodeEndpoint, RpcClient};
+use futures_util::{SinkExt, StreamExt};
+use rustls::ClientConfig;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::{path::Path, sync::Arc, time::Duration};
+#[cfg(unix)]
+use tokio::{
+	io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+	net::UnixStream,
+};
+use tokio::{net::TcpStream, time::Instant};
+use tokio_tungstenite::{tungstenite::Message, Connector, MaybeTlsStream, WebSocketStream};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+#[cfg(unix)]
+type IpcStream = BufReader<UnixStream>;
+
+/// An async JSON-RPC client for a single [NodeEndpoint], see [RpcClient].
+/// It only speaks the legacy methods, see [crate::RpcApi::Legacy].
+pub struct AsyncRpcClient {
+	endpoint: NodeEndpoint,
+	timeout: Duration,
+	headers: Vec<(String, String)>,
+	tls: Option<Arc<ClientConfig>>,
+	last_id: usize,
+	ws: Option<WsStream>,
+	#[cfg(unix)]
+	ipc: Option<IpcStream>,
+}
+
+impl AsyncRpcClient {
+	pub fn new(endpoint: NodeEndpoint) -> Self {
+		Self {
+			endpoint,
+			timeout: DEFAULT_RPC_TIMEOUT,
+			headers: Vec::new(),
+			tls: None,
+			last_id: 0,
+			ws: None,
+			#[cfg(unix)]
+			ipc: None,
+		}
+	}
+
+	/// Set extra HTTP headers, sent with each HTTP request or when opening the WebSocket connection
+	pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+		self.headers = headers;
+		self
+	}
+
+	/// Set the timeout used when connecting and waiting for a response
+	pub fn with_timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	/// Set the TLS configuration used for `https` and `wss` endpoints, see [crate::TlsConfig]
+	pub fn with_tls(mut self, tls: Arc<ClientConfig>) -> Self {
+		self.tls = Some(tls);
+		self
+	}
+
+	pub fn endpoint(&self) -> &NodeEndpoint {
+		&self.endpoint
+	}
+
+	/// Send a request and return its decoded `result`, see [RpcClient::request]
+	pub async fn request<T: DeserializeOwned>(&mut self, call: (&str, Value)) -> Result<T> {
+		let (method, params) = call;
+		self.last_id += 1;
+		let id = self.last_id;
+		let payload = subrpcer::rpc(id, method, params);
+		log::debug!("RPC request #{id} to {}: {method}", self.endpoint);
+
+		let result = match &self.endpoint {
+			NodeEndpoint::Http(_) => self.request_http(&payload, id).await?,
+			NodeEndpoint::WebSocket(_) => self.request_ws(&payload, id).await?,
+			NodeEndpoint::Ipc(path) => {
+				let path = path.clone();
+				self.request_ipc(&path, &payload, id).await?
+			}
+		};
+
+		serde_json::from_value::<T>(result)
+			.map_err(|e| WasmLoaderError::RpcUnexpectedResponse(format!("{method}: {e}")))
+	}
+
+	/// Read the raw value stored under `key` at the given block.
+	/// Returns `None` if there is nothing stored there.
+	pub async fn get_storage(&mut self, key: &[u8], block_hash: &BlockHash) -> Result<Option<Vec<u8>>> {
+		let key = array_bytes::bytes2hex("0x", key);
+		let value: Option<String> = self.request(subrpcer::state::get_storage_raw(key, Some(block_hash))).await?;
+		value.map(|v| array_bytes::hex2bytes(v).map_err(|_e| WasmLoaderError::HexDecoding())).transpose()
+	}
+
+	/// Get the hash of a storage value at the given block, without fetching the value itself
+	pub async fn get_storage_hash(&mut self, key: &[u8], block_hash: &BlockHash) -> Result<Option<String>> {
+		let key = array_bytes::bytes2hex("0x", key);
+		self.request(subrpcer::state::get_storage_hash_raw(key, Some(block_hash))).await
+	}
+
+	async fn request_http(&self, payload: &Value, id: usize) -> Result<Value> {
+		// The url we show never contains credentials
+		let url = self.endpoint.to_string();
+		let transport_error = |e: reqwest::Error| match e.is_timeout() {
+			true => WasmLoaderError::RpcTimeout(url.to_string()),
+			false => {
+				log::debug!("{e}");
+				WasmLoaderError::HttpClient(url.to_string())
+			}
+		};
+
+		let mut client = reqwest::Client::builder().timeout(self.timeout);
+		if let Some(tls) = &self.tls {
+			client = client.use_preconfigured_tls(ClientConfig::clone(tls));
+		}
+		let client = client.build().map_err(transport_error)?;
+		let mut request = client.post(self.endpoint.connection_url()?).json(payload);
+		for (name, value) in RpcClient::all_headers(&self.endpoint, &self.headers) {
+			request = request.header(name, value);
+		}
+
+		let response = request.send().await.map_err(transport_error)?;
+		if !response.status().is_success() {
+			return Err(WasmLoaderError::HttpStatus(response.status().as_u16(), url));
+		}
+		let body = response.text().await.map_err(transport_error)?;
+		RpcClient::response_to(&body, id)?.ok_or(WasmLoaderError::RpcUnexpectedResponse(body))
+	}
+
+	async fn request_ws(&mut self, payload: &Value, id: usize) -> Result<Value> {
+		let url = self.endpoint.to_string();
+		let deadline = Instant::now() + self.timeout;
+
+		if self.ws.is_none() {
+			let request = RpcClient::ws_request(&self.endpoint, &self.headers)?;
+			let connector = self.tls.clone().map(Connector::Rustls);
+			let connect = tokio_tungstenite::connect_async_tls_with_config(request, None, false, connector);
+			let (ws, _response) = tokio::time::timeout_at(deadline, connect)
+				.await
+				.map_err(|_elapsed| WasmLoaderError::RpcTimeout(url.to_string()))?
+				.map_err(|e| RpcClient::ws_error(e, &url))?;
+			self.ws = Some(ws);
+		}
+		let ws = self.ws.as_mut().expect("We just connected");
+
+		let result = tokio::time::timeout_at(deadline, Self::exchange_ws(ws, &url, payload, id))
+			.await
+			.unwrap_or_else(|_elapsed| Err(WasmLoaderError::RpcTimeout(url.to_string())));
+		if result.is_err() {
+			// We do not know in which state the connection is, we will reconnect next time
+			self.ws = None;
+		}
+		result
+	}
+
+	async fn exchange_ws(ws: &mut WsStream, url: &str, payload: &Value, id: usize) -> Result<Value> {
+		let text = serde_json::to_string(payload).map_err(|e| WasmLoaderError::RpcUnexpectedResponse(e.to_string()))?;
+		ws.send(Message::Text(text)).await.map_err(|e| RpcClient::ws_error(e, url))?;
+
+		loop {
+			let closed = || WasmLoaderError::WsClient(format!("{url} closed the connection"));
+			let data = match ws.next().await.ok_or_else(closed)?.map_err(|e| RpcClient::ws_error(e, url))? {
+				Message::Text(t) => t,
+				Message::Binary(b) => String::from_utf8_lossy(&b).to_string(),
+				Message::Close(_) => return Err(closed()),
+				m => {
+					log::trace!("Ignoring {m:?}");
+					continue;
+				}
+			};
+
+			if let Some(result) = RpcClient::response_to(&data, id)? {
+				return Ok(result);
+			}
+		}
+	}
+
+	/// Send a request over the Unix socket of a local node, one message per line
+	#[cfg(unix)]
+	async fn request_ipc(&mut self, path: &Path, payload: &Value, id: usize) -> Result<Value> {
+		let url = self.endpoint.to_string();
+		let deadline = Instant::now() + self.timeout;
+
+		if self.ipc.is_none() {
+			let stream = tokio::time::timeout_at(deadline, UnixStream::connect(path))
+				.await
+				.map_err(|_elapsed| WasmLoaderError::RpcTimeout(url.to_string()))?
+				.map_err(|e| RpcClient::ipc_error(e, &url))?;
+			self.ipc = Some(BufReader::new(stream));
+		}
+		let stream = self.ipc.as_mut().expect("We just connected");
+
+		let result = tokio::time::timeout_at(deadline, Self::exchange_ipc(stream, &url, payload, id))
+			.await
+			.unwrap_or_else(|_elapsed| Err(WasmLoaderError::RpcTimeout(url.to_string())));
+		if result.is_err() {
+			// We do not know in which state the connection is, we will reconnect next time
+			self.ipc = None;
+		}
+		result
+	}
+
+	#[cfg(not(unix))]
+	async fn request_ipc(&mut self, _path: &Path, _payload: &Value, _id: usize) -> Result<Value> {
+		Err(WasmLoaderError::NotSupported(format!("IPC endpoints on this platform: {}", self.endpoint)))
+	}
+
+	#[cfg(unix)]
+	async fn exchange_ipc(stream: &mut IpcStream, url: &str, payload: &Value, id: usize) -> Result<Value> {
+		let text = serde_json::to_string(payload).map_err(|e| WasmLoaderError::RpcUnexpectedResponse(e.to_string()))?;
+		stream.get_mut().write_all(format!("{text}\n").as_bytes()).await.map_err(|e| RpcClient::ipc_error(e, url))?;
+
+		let mut line = String::new();
+		loop {
+			line.clear();
+			if stream.read_line(&mut line).await.map_err(|e| RpcClient::ipc_error(e, url))? == 0 {
+				return Err(WasmLoaderError::IpcClient(format!("{url} closed the connection")));
+			}
+			if line.trim().is_empty() {
+				continue;
+			}
+
+			if let Some(result) = RpcClient::response_to(line.trim(), id)? {
+				return Ok(result);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_rpc_async {
+	use super::*;
+	use crate::{
+		rpc::mock::{mock_auth_node, mock_http_node, mock_node, mock_tls_node},
+		TlsConfig,
+	};
+
+	#[tokio::test]
+	async fn it_talks_to_a_ws_node() {
+		let endpoint = mock_node(vec![
+			r#"{"jsonrpc":"2.0","method":"chain_newHead","params":{"subscription":"abc","result":{}}}"#,
+			r#"{"jsonrpc":"2.0","id":{id},"result":"0x1234"}"#,
+		]);
+		let mut client = AsyncRpcClient::new(endpoint);
+
+		let first: String = client.request(subrpcer::chain::get_finalized_head_raw()).await.expect("Request failed");
+		let second: String = client.request(subrpcer::chain::get_finalized_head_raw()).await.expect("Request failed");
+		assert_eq!(first, "0x1234");
+		assert_eq!(second, "0x1234");
+	}
+
+	#[tokio::test]
+	async fn it_talks_to_an_http_node() {
+		let endpoint = mock_http_node(r#"{"jsonrpc":"2.0","id":{id},"result":"0x1234"}"#);
+		let mut client = AsyncRpcClient::new(endpoint);
+
+		let res: String = client.request(subrpcer::chain::get_finalized_head_raw()).await.expect("Request failed");
+		assert_eq!(res, "0x1234");
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn it_talks_to_an_ipc_node() {
+		let endpoint = crate::rpc::mock::mock_ipc_node(vec![
+			r#"{"jsonrpc":"2.0","method":"chain_newHead","params":{"subscription":"abc","result":{}}}"#,
+			r#"{"jsonrpc":"2.0","id":{id},"result":"0x1234"}"#,
+		]);
+		let mut client = AsyncRpcClient::new(endpoint);
+
+		let first: String = client.request(subrpcer::chain::get_finalized_head_raw()).await.expect("Request failed");
+		let second: String = client.request(subrpcer::chain::get_finalized_head_raw()).await.expect("Request failed");
+		assert_eq!(first, "0x1234");
+		assert_eq!(second, "0x1234");
+	}
+
+	#[tokio::test]
+	async fn it_sends_headers() {
+		let endpoint = mock_auth_node("x-api-key", "secret", r#"{"jsonrpc":"2.0","id":{id},"result":"0x1234"}"#);
+		let mut client = AsyncRpcClient::new(endpoint).with_headers(vec![("X-Api-Key".into(), "secret".into())]);
+
+		let res: String = client.request(subrpcer::chain::get_finalized_head_raw()).await.expect("Request failed");
+		assert_eq!(res, "0x1234");
+	}
+
+	#[tokio::test]
+	async fn it_times_out() {
+		let endpoint = mock_node(vec![]);
+		let mut client = AsyncRpcClient::new(endpoint).with_timeout(Duration::from_millis(200));
+
+		let res = client.request::<String>(subrpcer::chain::get_finalized_head_raw()).await;
+		assert!(matches!(res, Err(WasmLoaderError::RpcTimeout(_))));
+	}
+
+	#[tokio::test]
+	async fn it_uses_the_tls_config() {
+		let ca = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/tls/ca.pem");
+		let tls = TlsConfig { ca_bundles: vec![ca], ..Default::default() }.client_config().expect("Invalid config");
+
+		for ws in [true, false] {
+			let endpoint = mock_tls_node(ws, false, r#"{"jsonrpc":"2.0","id":{id},"result":"0x1234"}"#);
+			let mut client = AsyncRpcClient::new(endpoint).with_tls(tls.clone().expect("Missing config"));
+
+			let res: String = client.request(subrpcer::chain::get_finalized_head_raw()).await.expect("Request failed");
+			assert_eq!(res, "0x1234");
+		}
+	}
+}
@@ -0,0 +1,200 @@
+use crate::{error::*, BlockHash, NodeEndpoint, OnchainBlock, RpcClient, CODE};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A runtime as found on chain, at the first block where it is in place
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuntimeUpgrade {
+	pub block_number: u64,
+	pub block_hash: BlockHash,
+	pub spec_name: String,
+	pub spec_version: u32,
+
+	/// The blake2-256 hash of the runtime, as stored on chain
+	pub code_hash: String,
+}
+
+/// The runtime upgrades of a chain over a range of blocks.
+///
+/// The node must be an archive node, we need the state of old blocks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuntimeHistory {
+	/// The endpoint that served the history
+	pub endpoint: NodeEndpoint,
+
+	/// The first block of the range
+	pub from: u64,
+
+	/// The last block of the range, included
+	pub to: u64,
+
+	/// The runtime in place at `from`, this is not an upgrade
+	pub initial: RuntimeUpgrade,
+
+	/// The upgrades applied after `from`, oldest first
+	pub upgrades: Vec<RuntimeUpgrade>,
+}
+
+/// Looks for runtime upgrades, remembering the runtime hash of the blocks we already checked
+struct Scanner<'a> {
+	client: &'a mut RpcClient,
+	code_hashes: BTreeMap<u64, (BlockHash, String)>,
+}
+
+impl RuntimeHistory {
+	/// Scan the blocks from `from` to `to`, the finalized block by default, for runtime upgrades.
+	pub fn fetch(reference: &OnchainBlock, from: u64, to: Option<u64>) -> Result<Self> {
+		// No need to bother the node if we already know the range is empty
+		if let Some(to) = to {
+			Self::check_range(from, to)?;
+		}
+		let (history, endpoint) = reference.with_failover(|client| Self::fetch_with(client, from, to))?;
+		Ok(Self { endpoint, ..history })
+	}
+
+	/// Rather than checking every block, we bisect the range: if the hash of `:code` is the same at
+	/// both ends, we consider there was no upgrade in between. Otherwise, we split the range in two
+	/// until we find the first block of the new runtime. A handful of requests per upgrade is enough,
+	/// but a runtime that is replaced and later restored within a range goes unnoticed.
+	/// Comparing the code rather than the `spec_version` also finds the upgrades that did not bump it.
+	fn fetch_with(client: &mut RpcClient, from: u64, to: Option<u64>) -> Result<Self> {
+		let to = match to {
+			Some(to) => to,
+			None => client.finalized_number()?,
+		};
+		Self::check_range(from, to)?;
+		log::debug!("Scanning blocks #{from} to #{to} for runtime upgrades");
+
+		let mut scanner = Scanner { client, code_hashes: BTreeMap::new() };
+		let mut found = Vec::new();
+		scanner.bisect(from, to, &mut found)?;
+
+		let initial = scanner.runtime_at(from)?;
+		let upgrades = found.into_iter().map(|number| scanner.runtime_at(number)).collect::<Result<Vec<_>>>()?;
+		Ok(Self { endpoint: scanner.client.endpoint().clone(), from, to, initial, upgrades })
+	}
+
+	fn check_range(from: u64, to: u64) -> Result<()> {
+		match from > to {
+			true => Err(WasmLoaderError::OnchainBlockParsing(format!("Block #{from} is after block #{to}"))),
+			false => Ok(()),
+		}
+	}
+}
+
+impl Scanner<'_> {
+	fn code_hash_at(&mut self, number: u64) -> Result<&(BlockHash, String)> {
+		if !self.code_hashes.contains_key(&number) {
+			let hash = OnchainBlock::get_block_hash(self.client, number)?;
+			let code_hash = self
+				.client
+				.get_storage_hash(CODE, &hash)?
+				.ok_or_else(|| WasmLoaderError::StorageNotFound(":code".into(), hash.to_string()))?;
+			log::debug!("Block #{number} runs {code_hash}");
+			self.code_hashes.insert(number, (hash, code_hash));
+		}
+		Ok(&self.code_hashes[&number])
+	}
+
+	/// Push the first block of each runtime applied after `lo` and up to `hi` into `found`, in order
+	fn bisect(&mut self, lo: u64, hi: u64, found: &mut Vec<u64>) -> Result<()> {
+		let lo_code = self.code_hash_at(lo)?.1.clone();
+		if self.code_hash_at(hi)?.1 == lo_code {
+			return Ok(());
+		}
+		if hi - lo == 1 {
+			found.push(hi);
+			return Ok(());
+		}
+
+		let mid = lo + (hi - lo) / 2;
+		self.bisect(lo, mid, found)?;
+		self.bisect(mid, hi, found)
+	}
+
+	fn runtime_at(&mut self, number: u64) -> Result<RuntimeUpgrade> {
+		let (block_hash, code_hash) = self.code_hash_at(number)?.clone();
+		let version = self.client.runtime_version(&block_hash)?;
+
+		Ok(RuntimeUpgrade {
+			block_number: number,
+			block_hash,
+			spec_name: version.spec_name.to_string(),
+			spec_version: version.spec_version,
+			code_hash,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests_runtime_history {
+	use super::*;
+	use crate::rpc::mock::mock_node_with;
+	use serde_json::Value;
+
+	/// The spec version of a fake chain of 101 blocks, upgraded at #30 and #70
+	fn spec_version(number: u64) -> u32 {
+		match number {
+			0..=29 => 1000,
+			30..=69 => 1001,
+			_ => 1002,
+		}
+	}
+
+	/// The runtime of the fake chain, also upgraded at #50 without bumping the spec version
+	fn code(number: u64) -> u64 {
+		match number {
+			50..=69 => 5,
+			_ => spec_version(number).into(),
+		}
+	}
+
+	fn fake_chain() -> OnchainBlock {
+		let endpoint = mock_node_with(|request: &Value| {
+			let param = &request["params"][0];
+			let number_of = |hash: &Value| {
+				u64::from_str_radix(&hash.as_str().expect("Missing hash")[2..], 16).expect("Failed parsing hash")
+			};
+
+			let result = match request["method"].as_str() {
+				Some("chain_getFinalizedHead") => format!(r#""0x{:064x}""#, 100),
+				Some("chain_getHeader") => format!(r#"{{"number":"0x{:x}"}}"#, number_of(param)),
+				Some("chain_getBlockHash") => format!(r#""0x{:064x}""#, param.as_u64().expect("Missing number")),
+				Some("state_getRuntimeVersion") => format!(
+					r#"{{"specName":"polkadot","implName":"parity-polkadot","authoringVersion":0,"specVersion":{},"implVersion":0,"apis":[],"transactionVersion":26,"stateVersion":1}}"#,
+					spec_version(number_of(param))
+				),
+				Some("state_getStorageHash") => {
+					format!(r#""0x{:064x}""#, code(number_of(&request["params"][1])))
+				}
+				_ => return vec![r#"{"jsonrpc":"2.0","id":{id},"error":{"code":-1,"message":"Nope"}}"#.into()],
+			};
+			vec![format!(r#"{{"jsonrpc":"2.0","id":{{id}},"result":{result}}}"#)]
+		});
+		OnchainBlock::from_endpoint(endpoint, None)
+	}
+
+	#[test]
+	fn it_finds_upgrades() {
+		let history = RuntimeHistory::fetch(&fake_chain(), 0, None).expect("Failed fetching history");
+		assert_eq!(history.to, 100);
+		assert_eq!(history.initial.spec_version, 1000);
+		assert_eq!(
+			history.upgrades.iter().map(|u| (u.block_number, u.spec_version)).collect::<Vec<_>>(),
+			vec![(30, 1001), (50, 1001), (70, 1002)]
+		);
+		assert_eq!(history.upgrades[0].block_hash, format!("0x{:064x}", 30));
+		assert_eq!(history.upgrades[1].code_hash, format!("0x{:064x}", 5));
+		assert_eq!(history.upgrades[2].code_hash, format!("0x{:064x}", 1002));
+	}
+
+	#[test]
+	fn it_scans_a_range() {
+		let history = RuntimeHistory::fetch(&fake_chain(), 50, Some(69)).expect("Failed fetching history");
+		assert_eq!(history.initial.block_number, 50);
+		assert_eq!(history.initial.spec_version, 1001);
+		assert!(history.upgrades.is_empty());
+
+		assert!(RuntimeHistory::fetch(&fake_chain(), 50, Some(10)).is_err());
+	}
+}
@@ -0,0 +1,169 @@
+use crate::{error::*, WasmBytes, CODE};
+use scale::{Compact, Decode};
+use sp_core::{Blake2Hasher, H256};
+use sp_trie::{HashDBT, LayoutV1, PrefixedMemoryDB};
+use std::path::Path;
+
+/// The file extension used by try-runtime for its state snapshots
+pub const SNAPSHOT_EXTENSION: &str = "snap";
+
+/// The nodes of a storage trie, as `(prefixed key, (node, ref count))`
+pub type RawStorage = Vec<(Vec<u8>, (Vec<u8>, i32))>;
+
+/// A state snapshot as produced by `try-runtime create-snapshot`.
+///
+/// The snapshot does not contain the storage as key/value pairs but the nodes of
+/// the storage trie. We need to walk the trie from its root to find the `:code`.
+pub struct Snapshot {
+	/// Version of the snapshot format
+	pub version: u16,
+
+	/// State version of the trie (0 or 1)
+	pub state_version: u8,
+
+	/// Root of the storage trie
+	pub storage_root: H256,
+
+	/// The nodes of the storage trie
+	raw_storage: RawStorage,
+}
+
+impl Snapshot {
+	/// Check whether a file looks like a try-runtime snapshot
+	pub fn is_snapshot(path: &Path) -> bool {
+		path.extension().is_some_and(|ext| ext == SNAPSHOT_EXTENSION)
+	}
+
+	/// Decode a snapshot. Both the v3 (block hash) and v4 (header) formats are supported.
+	pub fn decode(bytes: &[u8]) -> Result<Self> {
+		let input = &mut &bytes[..];
+		let err = |e: scale::Error| WasmLoaderError::Snapshot(e.to_string());
+
+		let version = Compact::<u16>::decode(input).map_err(err)?.0;
+		if !(3..=4).contains(&version) {
+			return Err(WasmLoaderError::Snapshot(format!("Unsupported snapshot version {version}")));
+		}
+		let state_version = u8::decode(input).map_err(err)?;
+		if state_version > 1 {
+			return Err(WasmLoaderError::Snapshot(format!("Unsupported state version {state_version}")));
+		}
+		if version == 3 {
+			// v3 stored the block hash where v4 stores the header, after the storage root
+			let _block_hash = H256::decode(input).map_err(err)?;
+		}
+		let raw_storage = RawStorage::decode(input).map_err(err)?;
+		let storage_root = H256::decode(input).map_err(err)?;
+		// The header (v4) follows but we have no use for it
+
+		Ok(Self { version, state_version, storage_root, raw_storage })
+	}
+
+	/// Load and decode the snapshot stored at `path`
+	pub fn load(path: &Path) -> Result<Self> {
+		let bytes = std::fs::read(path).map_err(|e| WasmLoaderError::Snapshot(format!("{}: {e}", path.display())))?;
+		Self::decode(&bytes)
+	}
+
+	/// The nodes of the storage trie, its root and its state version, to build externalities from
+	pub fn into_parts(self) -> (RawStorage, H256, u8) {
+		(self.raw_storage, self.storage_root, self.state_version)
+	}
+
+	/// Read a value from the storage captured in the snapshot
+	pub fn get_storage(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+		let hash_len = H256::len_bytes();
+		let mut db = PrefixedMemoryDB::<Blake2Hasher>::default();
+		for (prefixed_key, (node, _ref_count)) in &self.raw_storage {
+			if prefixed_key.len() < hash_len {
+				log::warn!("Skipping invalid trie key in snapshot: {}", array_bytes::bytes2hex("0x", prefixed_key));
+				continue;
+			}
+			let (prefix, hash) = prefixed_key.split_at(prefixed_key.len() - hash_len);
+			db.emplace(H256::from_slice(hash), (prefix, None), node.to_owned());
+		}
+
+		// Reading the trie is the same for both layouts
+		sp_trie::read_trie_value::<LayoutV1<Blake2Hasher>, _>(&db, &self.storage_root, key, None, None)
+			.map_err(|e| WasmLoaderError::Snapshot(format!("Failed reading the trie: {e}")))
+	}
+
+	/// Returns the runtime captured in the snapshot
+	pub fn code(&self) -> Result<WasmBytes> {
+		self.get_storage(CODE)?.ok_or_else(|| {
+			WasmLoaderError::StorageNotFound(":code".into(), array_bytes::bytes2hex("0x", self.storage_root))
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests_snapshot {
+	use super::*;
+	use scale::Encode;
+	use sp_trie::{TrieDBMutBuilder, TrieMut};
+
+	/// Build a snapshot the way try-runtime does, from a few storage items
+	fn build_snapshot(version: u16, items: &[(&[u8], &[u8])]) -> Vec<u8> {
+		let mut db = PrefixedMemoryDB::<Blake2Hasher>::default();
+		let mut root = H256::default();
+		{
+			let mut trie = TrieDBMutBuilder::<LayoutV1<Blake2Hasher>>::new(&mut db, &mut root).build();
+			for (key, value) in items {
+				trie.insert(key, value).expect("Failed inserting into the trie");
+			}
+		}
+		let raw_storage: RawStorage = db.drain().into_iter().collect();
+
+		let mut bytes = Compact(version).encode();
+		bytes.extend(1u8.encode());
+		if version == 3 {
+			bytes.extend(H256::repeat_byte(0x42).encode());
+		}
+		bytes.extend(raw_storage.encode());
+		bytes.extend(root.encode());
+		bytes
+	}
+
+	#[test]
+	fn it_extracts_the_code() {
+		let code = [&[0x00, 0x61, 0x73, 0x6d][..], &[0x42; 1024]].concat();
+		for version in [3, 4] {
+			let bytes = build_snapshot(version, &[(CODE, &code), (b"foo", b"bar")]);
+			let snapshot = Snapshot::decode(&bytes).expect("Failed decoding snapshot");
+			assert_eq!(snapshot.version, version);
+			assert_eq!(snapshot.code().expect("Failed getting the code"), code);
+			assert_eq!(snapshot.get_storage(b"foo").expect("Failed reading storage"), Some(b"bar".to_vec()));
+		}
+	}
+
+	#[cfg(feature = "async")]
+	#[tokio::test]
+	async fn it_loads_the_code_async() {
+		let code = [&[0x00, 0x61, 0x73, 0x6d][..], &[0x42; 1024]].concat();
+		let path = std::env::temp_dir().join(format!("wasm-loader-{}.{SNAPSHOT_EXTENSION}", std::process::id()));
+		std::fs::write(&path, build_snapshot(4, &[(CODE, &code)])).expect("Failed writing snapshot");
+
+		let loader = crate::WasmLoader::load_from_source_async(&crate::Source::Snapshot(path.clone())).await;
+		std::fs::remove_file(path).expect("Failed removing snapshot");
+		assert_eq!(loader.expect("Failed loading snapshot").original_bytes(), &code);
+	}
+
+	#[test]
+	fn it_fails_without_code() {
+		let bytes = build_snapshot(4, &[(b"foo", b"bar")]);
+		let snapshot = Snapshot::decode(&bytes).expect("Failed decoding snapshot");
+		assert!(matches!(snapshot.code(), Err(WasmLoaderError::StorageNotFound(_, _))));
+	}
+
+	#[test]
+	fn it_rejects_unknown_versions() {
+		let bytes = build_snapshot(4, &[(CODE, b"code")]);
+		let mut bytes = bytes[1..].to_vec();
+		bytes.insert(0, Compact(42u16).encode()[0]);
+		assert!(Snapshot::decode(&bytes).is_err());
+		assert!(Snapshot::decode(&[]).is_err());
+
+		let mut bytes = build_snapshot(4, &[(CODE, b"code")]);
+		bytes[1] = 2;
+		assert!(Snapshot::decode(&bytes).is_err());
+	}
+}
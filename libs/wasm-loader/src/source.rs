@@ -1,16 +1,27 @@
 use std::{fmt::Display, path::PathBuf, str::FromStr};
 
-use crate::{error, NodeEndpoint, OnchainBlock};
+use crate::{error, NodeEndpoint, OnchainBlock, Snapshot};
 use error::*;
 
-/// The source of the wasm. It can come from the local file system (`File`) or from a chain (`Chain`).
-#[derive(Debug, Clone, PartialEq)]
+/// The source of the wasm. It can come from the local file system (`File`), from
+/// a try-runtime state snapshot (`Snapshot`), from a chain (`Chain`), from the relay chain
+/// of a parachain (`Parachain`) or from memory (`Bytes`).
+#[derive(Clone, PartialEq)]
 pub enum Source {
 	/// A file on your local filesystem
 	File(PathBuf),
 
+	/// A try-runtime state snapshot on your local filesystem
+	Snapshot(PathBuf),
+
 	/// A remote endpoint we can connect to
 	Chain(OnchainBlock),
+
+	/// The validation code of a parachain, given its id, read from its relay chain
+	Parachain(OnchainBlock, u32),
+
+	/// Some bytes you already have in memory, compressed or not
+	Bytes(Vec<u8>),
 }
 
 impl Source {
@@ -18,6 +29,9 @@ impl Source {
 		let path = PathBuf::from(s);
 
 		if path.exists() {
+			if Snapshot::is_snapshot(&path) {
+				return Ok(Source::Snapshot(path));
+			}
 			return Ok(Source::File(path));
 		}
 
@@ -29,11 +43,27 @@ impl Source {
 	}
 }
 
+/// Same as the derived impl but showing the length of [Source::Bytes] instead of dumping the whole runtime
+impl std::fmt::Debug for Source {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Source::File(f) => fmt.debug_tuple("File").field(f).finish(),
+			Source::Snapshot(f) => fmt.debug_tuple("Snapshot").field(f).finish(),
+			Source::Chain(c) => fmt.debug_tuple("Chain").field(c).finish(),
+			Source::Parachain(relay, para_id) => fmt.debug_tuple("Parachain").field(relay).field(para_id).finish(),
+			Source::Bytes(b) => write!(fmt, "Bytes({} bytes)", b.len()),
+		}
+	}
+}
+
 impl Display for Source {
 	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Source::File(f) => write!(fmt, "{f:?}"),
+			Source::Snapshot(f) => write!(fmt, "snapshot: {f:?}"),
 			Source::Chain(c) => write!(fmt, "{c:?}"),
+			Source::Parachain(relay, para_id) => write!(fmt, "para {para_id} on {relay:?}"),
+			Source::Bytes(b) => write!(fmt, "{} bytes", b.len()),
 		}
 	}
 }
@@ -42,7 +72,7 @@ impl FromStr for Source {
 	type Err = WasmLoaderError;
 
 	/// This can handle the path of an existing file or a string that
-	/// can be parsed as a valid endpoint (http://... or ws://...)
+	/// can be parsed as a valid endpoint (http://..., ws://... or ipc://...)
 	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
 		Self::get_source_type(s)
 	}
@@ -85,6 +115,15 @@ mod tests_source {
 		}
 	}
 
+	#[test]
+	fn it_converts_from_ipc() {
+		let src = Source::from_str("ipc:///var/run/node.sock").expect("Failed getting a source");
+		match src {
+			Source::Chain(r) => assert_eq!(r.endpoint, NodeEndpoint::Ipc(PathBuf::from("/var/run/node.sock"))),
+			_ => unreachable!(),
+		}
+	}
+
 	#[test]
 	fn it_converts_from_path() {
 		let mut dir = temp_dir();
@@ -99,6 +138,15 @@ mod tests_source {
 		}
 	}
 
+	#[test]
+	fn it_converts_from_snapshot() {
+		let mut path = temp_dir();
+		path.push("subwasm_fake_state.snap");
+		let _fake_snapshot = File::create(&path).expect("We should be able to create a tmpdir");
+
+		assert_eq!(Source::from_str(&path.display().to_string()), Ok(Source::Snapshot(path)));
+	}
+
 	#[test]
 	fn it_catches_unknown() {
 		let v = vec!["foo", "bar"];
@@ -0,0 +1,42 @@
+//! Helpers to build the storage keys of the few items we read from a node.
+
+use sp_crypto_hashing::{twox_128, twox_64};
+
+/// Key of a `StorageValue` named `item` in the pallet `pallet`
+pub fn storage_value_key(pallet: &str, item: &str) -> Vec<u8> {
+	[twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// Key of the entry `key` of a `Twox64Concat` `StorageMap` named `item` in the pallet `pallet`.
+/// The `key` must already be SCALE encoded.
+pub fn storage_map_key_twox64_concat(pallet: &str, item: &str, key: &[u8]) -> Vec<u8> {
+	[storage_value_key(pallet, item), twox_64(key).to_vec(), key.to_vec()].concat()
+}
+
+/// Key of the entry `key` of an `Identity` `StorageMap` named `item` in the pallet `pallet`.
+/// The `key` must already be SCALE encoded.
+pub fn storage_map_key_identity(pallet: &str, item: &str, key: &[u8]) -> Vec<u8> {
+	[storage_value_key(pallet, item), key.to_vec()].concat()
+}
+
+#[cfg(test)]
+mod tests_storage {
+	use super::*;
+
+	#[test]
+	fn it_computes_value_keys() {
+		assert_eq!(
+			array_bytes::bytes2hex("0x", storage_value_key("System", "Number")),
+			"0x26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac"
+		);
+	}
+
+	#[test]
+	fn it_computes_map_keys() {
+		// Paras::Heads(1000)
+		assert_eq!(
+			array_bytes::bytes2hex("0x", storage_map_key_twox64_concat("Paras", "Heads", &1000u32.to_le_bytes())),
+			"0xcd710b30bd2eab0352ddcc26417aa1941b3c252fcb29d88eff4f3de5de4476c3b6ff6f7d467b87a9e8030000"
+		);
+	}
+}
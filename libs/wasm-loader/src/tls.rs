@@ -0,0 +1,200 @@
+use crate::error::*;
+use rustls::{
+	client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+	crypto::{ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider},
+	pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime},
+	ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use std::{fs::File, io::BufReader, path::PathBuf, sync::Arc};
+
+/// The TLS settings used to connect to `https` and `wss` endpoints.
+///
+/// By default, the nodes must present a certificate issued by a well known CA or one trusted by the system.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TlsConfig {
+	/// PEM files with extra CA certificates to trust, for nodes using a private CA
+	pub ca_bundles: Vec<PathBuf>,
+
+	/// A PEM file with our certificate chain, for nodes requiring client authentication
+	pub client_cert: Option<PathBuf>,
+
+	/// A PEM file with the private key of `client_cert`. If not set, the key is read from `client_cert`.
+	pub client_key: Option<PathBuf>,
+
+	/// Accept any certificate from the nodes. This is insecure and must be opted in explicitly.
+	pub insecure: bool,
+}
+
+impl TlsConfig {
+	/// The configuration to give to the HTTP and WebSocket clients,
+	/// `None` if we can stick to the default one of the clients.
+	pub fn client_config(&self) -> Result<Option<Arc<ClientConfig>>> {
+		if *self == Self::default() {
+			return Ok(None);
+		}
+
+		let provider = Arc::new(ring::default_provider());
+		let builder = ClientConfig::builder_with_provider(provider.clone())
+			.with_safe_default_protocol_versions()
+			.map_err(|e| WasmLoaderError::Tls(e.to_string()))?;
+
+		let builder = if self.insecure {
+			log::warn!("TLS certificates of the nodes are not verified");
+			builder.dangerous().with_custom_certificate_verifier(Arc::new(NoVerification(provider)))
+		} else {
+			builder.with_root_certificates(self.root_store()?)
+		};
+
+		let config = match &self.client_cert {
+			Some(cert) => {
+				let key = Self::read_key(self.client_key.as_ref().unwrap_or(cert))?;
+				builder
+					.with_client_auth_cert(Self::read_certs(cert)?, key)
+					.map_err(|e| WasmLoaderError::Tls(e.to_string()))?
+			}
+			None => builder.with_no_client_auth(),
+		};
+		Ok(Some(Arc::new(config)))
+	}
+
+	/// The CAs we trust: the well known ones, the ones of the system and the ones of our bundles
+	fn root_store(&self) -> Result<RootCertStore> {
+		let mut roots = RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() };
+
+		let native = rustls_native_certs::load_native_certs();
+		for e in native.errors {
+			log::debug!("Failed loading a certificate of the system: {e}");
+		}
+		roots.add_parsable_certificates(native.certs);
+
+		for bundle in &self.ca_bundles {
+			for cert in Self::read_certs(bundle)? {
+				roots.add(cert).map_err(|e| WasmLoaderError::Tls(format!("{}: {e}", bundle.display())))?;
+			}
+		}
+		Ok(roots)
+	}
+
+	fn open(file: &PathBuf) -> Result<BufReader<File>> {
+		File::open(file)
+			.map(BufReader::new)
+			.map_err(|e| WasmLoaderError::FileRead(file.display().to_string(), e.to_string()))
+	}
+
+	fn read_certs(file: &PathBuf) -> Result<Vec<CertificateDer<'static>>> {
+		let certs = rustls_pemfile::certs(&mut Self::open(file)?)
+			.collect::<std::result::Result<Vec<_>, _>>()
+			.map_err(|e| WasmLoaderError::Tls(format!("{}: {e}", file.display())))?;
+
+		match certs.is_empty() {
+			true => Err(WasmLoaderError::Tls(format!("No certificate in {}", file.display()))),
+			false => Ok(certs),
+		}
+	}
+
+	fn read_key(file: &PathBuf) -> Result<PrivateKeyDer<'static>> {
+		rustls_pemfile::private_key(&mut Self::open(file)?)
+			.map_err(|e| WasmLoaderError::Tls(format!("{}: {e}", file.display())))?
+			.ok_or_else(|| WasmLoaderError::Tls(format!("No private key in {}", file.display())))
+	}
+}
+
+/// Accepts any certificate, we still check the signatures of the handshake
+#[derive(Debug)]
+struct NoVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerification {
+	fn verify_server_cert(
+		&self,
+		_end_entity: &CertificateDer<'_>,
+		_intermediates: &[CertificateDer<'_>],
+		_server_name: &ServerName<'_>,
+		_ocsp_response: &[u8],
+		_now: UnixTime,
+	) -> std::result::Result<ServerCertVerified, rustls::Error> {
+		Ok(ServerCertVerified::assertion())
+	}
+
+	fn verify_tls12_signature(
+		&self,
+		message: &[u8],
+		cert: &CertificateDer<'_>,
+		dss: &DigitallySignedStruct,
+	) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+		verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+	}
+
+	fn verify_tls13_signature(
+		&self,
+		message: &[u8],
+		cert: &CertificateDer<'_>,
+		dss: &DigitallySignedStruct,
+	) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+		verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+	}
+
+	fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+		self.0.signature_verification_algorithms.supported_schemes()
+	}
+}
+
+#[cfg(test)]
+mod tests_tls {
+	use super::*;
+	use crate::{rpc::mock::mock_tls_node, RpcClient};
+
+	const REPLY: &str = r#"{"jsonrpc":"2.0","id":{id},"result":"0x1234"}"#;
+
+	fn pem(name: &str) -> PathBuf {
+		PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/tls").join(name)
+	}
+
+	/// Send a request to a node using the given TLS settings
+	fn request(ws: bool, client_auth: bool, tls: TlsConfig) -> Result<String> {
+		let mut client = RpcClient::new(mock_tls_node(ws, client_auth, REPLY));
+		if let Some(config) = tls.client_config()? {
+			client = client.with_tls(config);
+		}
+		client.request(subrpcer::chain::get_finalized_head_raw())
+	}
+
+	#[test]
+	fn it_rejects_unknown_cas() {
+		assert!(request(true, false, TlsConfig::default()).is_err());
+		assert!(request(false, false, TlsConfig::default()).is_err());
+	}
+
+	#[test]
+	fn it_trusts_extra_cas() {
+		let tls = TlsConfig { ca_bundles: vec![pem("ca.pem")], ..Default::default() };
+		assert_eq!(request(true, false, tls.clone()), Ok("0x1234".into()));
+		assert_eq!(request(false, false, tls), Ok("0x1234".into()));
+	}
+
+	#[test]
+	fn it_accepts_any_certificate_when_insecure() {
+		let tls = TlsConfig { insecure: true, ..Default::default() };
+		assert_eq!(request(true, false, tls.clone()), Ok("0x1234".into()));
+		assert_eq!(request(false, false, tls), Ok("0x1234".into()));
+	}
+
+	#[test]
+	fn it_sends_client_certificates() {
+		let tls = TlsConfig { ca_bundles: vec![pem("ca.pem")], ..Default::default() };
+		assert!(request(true, true, tls.clone()).is_err());
+
+		let tls = TlsConfig { client_cert: Some(pem("client.pem")), client_key: Some(pem("client.key")), ..tls };
+		assert_eq!(request(true, true, tls.clone()), Ok("0x1234".into()));
+		assert_eq!(request(false, true, tls), Ok("0x1234".into()));
+	}
+
+	#[test]
+	fn it_fails_on_invalid_files() {
+		let tls = TlsConfig { ca_bundles: vec![pem("missing.pem")], ..Default::default() };
+		assert!(matches!(tls.client_config(), Err(WasmLoaderError::FileRead(_, _))));
+
+		// The certificate file holds no key
+		let tls = TlsConfig { client_cert: Some(pem("client.pem")), ..Default::default() };
+		assert!(matches!(tls.client_config(), Err(WasmLoaderError::Tls(_))));
+	}
+}
@@ -0,0 +1,158 @@
+use crate::{error::*, storage, BlockHash, NodeEndpoint, OnchainBlock, RpcClient};
+use serde::Serialize;
+use sp_crypto_hashing::blake2_256;
+
+/// Well known storage key of the code that will be applied at the beginning of the next block
+pub const PENDING_CODE: &[u8] = b":pending_code";
+
+/// An upgrade authorized by governance, as stored by `System::AuthorizedUpgrade`
+/// or `ParachainSystem::AuthorizedUpgrade`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpgradeAuthorization {
+	/// The blake2-256 hash of the code that is authorized
+	pub code_hash: String,
+
+	/// Whether the version of the new runtime will be checked when applying it.
+	/// Older runtimes only store the hash and do not have this flag.
+	pub check_version: Option<bool>,
+}
+
+impl UpgradeAuthorization {
+	/// Decode a SCALE encoded `CodeUpgradeAuthorization { code_hash, check_version }`
+	/// or a plain `Hash` as stored by older runtimes.
+	pub fn decode(bytes: &[u8]) -> Result<Self> {
+		let check_version = match bytes.len() {
+			32 => None,
+			33 => Some(bytes[32] != 0),
+			_ => return Err(WasmLoaderError::RpcUnexpectedResponse(array_bytes::bytes2hex("0x", bytes))),
+		};
+		Ok(Self { code_hash: array_bytes::bytes2hex("0x", &bytes[..32]), check_version })
+	}
+}
+
+/// Upgrades that are pending or authorized on a chain at a given block.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpgradeStatus {
+	/// The block the status was read at
+	pub block_hash: BlockHash,
+
+	/// The endpoint that served the status
+	pub endpoint: NodeEndpoint,
+
+	/// The blake2-256 hash of the code found under `:pending_code`
+	pub pending_code_hash: Option<String>,
+
+	/// The content of `System::AuthorizedUpgrade`
+	pub system_authorized_upgrade: Option<UpgradeAuthorization>,
+
+	/// The content of `ParachainSystem::AuthorizedUpgrade`
+	pub parachain_system_authorized_upgrade: Option<UpgradeAuthorization>,
+
+	/// The parachain the `paras_future_code_hash` was queried for
+	pub para_id: Option<u32>,
+
+	/// The content of `Paras::FutureCodeHash(para_id)` on a relay chain
+	pub paras_future_code_hash: Option<String>,
+}
+
+impl UpgradeStatus {
+	/// Read the upgrade related storage items of a chain. `Paras::FutureCodeHash`
+	/// is only queried if a `para_id` is provided.
+	pub fn fetch(reference: &OnchainBlock, para_id: Option<u32>) -> Result<Self> {
+		let (status, endpoint) = reference.with_failover(|client| Self::fetch_with(reference, client, para_id))?;
+		Ok(Self { endpoint, ..status })
+	}
+
+	fn fetch_with(reference: &OnchainBlock, client: &mut RpcClient, para_id: Option<u32>) -> Result<Self> {
+		let block_hash = reference.block_hash_with(client)?;
+		log::debug!("Fetching the upgrade status at {block_hash}");
+
+		let pending_code_hash =
+			client.get_storage(PENDING_CODE, &block_hash)?.map(|code| array_bytes::bytes2hex("0x", blake2_256(&code)));
+
+		let mut authorization = |pallet: &str| -> Result<Option<UpgradeAuthorization>> {
+			client
+				.get_storage(&storage::storage_value_key(pallet, "AuthorizedUpgrade"), &block_hash)?
+				.map(|bytes| UpgradeAuthorization::decode(&bytes))
+				.transpose()
+		};
+		let system_authorized_upgrade = authorization("System")?;
+		let parachain_system_authorized_upgrade = authorization("ParachainSystem")?;
+
+		let paras_future_code_hash = match para_id {
+			Some(id) => client
+				.get_storage(
+					&storage::storage_map_key_twox64_concat("Paras", "FutureCodeHash", &id.to_le_bytes()),
+					&block_hash,
+				)?
+				.map(|hash| array_bytes::bytes2hex("0x", hash)),
+			None => None,
+		};
+
+		Ok(Self {
+			block_hash,
+			endpoint: client.endpoint().clone(),
+			pending_code_hash,
+			system_authorized_upgrade,
+			parachain_system_authorized_upgrade,
+			para_id,
+			paras_future_code_hash,
+		})
+	}
+
+	/// Returns the names of the storage items referring to the code with the given blake2-256 hash
+	pub fn matches(&self, code_hash: &str) -> Vec<&'static str> {
+		let code_hash = code_hash.to_lowercase();
+		let candidates = [
+			(":pending_code", self.pending_code_hash.as_ref()),
+			("System::AuthorizedUpgrade", self.system_authorized_upgrade.as_ref().map(|a| &a.code_hash)),
+			(
+				"ParachainSystem::AuthorizedUpgrade",
+				self.parachain_system_authorized_upgrade.as_ref().map(|a| &a.code_hash),
+			),
+			("Paras::FutureCodeHash", self.paras_future_code_hash.as_ref()),
+		];
+
+		candidates.into_iter().filter(|(_, hash)| hash == &Some(&code_hash)).map(|(name, _)| name).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests_upgrade_status {
+	use super::*;
+
+	const HASH: &str = "0x0101010101010101010101010101010101010101010101010101010101010101";
+
+	#[test]
+	fn it_decodes_authorizations() {
+		let auth = UpgradeAuthorization::decode(&[[1u8; 32].to_vec(), vec![1]].concat()).expect("Failed decoding");
+		assert_eq!(auth, UpgradeAuthorization { code_hash: HASH.to_string(), check_version: Some(true) });
+
+		let auth = UpgradeAuthorization::decode(&[1u8; 32]).expect("Failed decoding");
+		assert_eq!(auth, UpgradeAuthorization { code_hash: HASH.to_string(), check_version: None });
+
+		assert!(UpgradeAuthorization::decode(&[1u8; 12]).is_err());
+	}
+
+	#[test]
+	fn it_finds_matches() {
+		let status = UpgradeStatus {
+			block_hash: HASH.to_string(),
+			endpoint: NodeEndpoint::WebSocket("ws://localhost:9944".into()),
+			pending_code_hash: None,
+			system_authorized_upgrade: None,
+			parachain_system_authorized_upgrade: Some(UpgradeAuthorization {
+				code_hash: HASH.to_string(),
+				check_version: Some(false),
+			}),
+			para_id: None,
+			paras_future_code_hash: None,
+		};
+
+		assert_eq!(
+			status.matches(&HASH.to_uppercase().replace("0X", "0x")),
+			vec!["ParachainSystem::AuthorizedUpgrade"]
+		);
+		assert!(status.matches("0x1234").is_empty());
+	}
+}
@@ -0,0 +1,160 @@
+use crate::{error::*, storage, BlockHash, NodeEndpoint, OnchainBlock, RpcClient, RuntimeCache, WasmBytes};
+use serde::Serialize;
+
+/// The validation code of a parachain, as stored by its relay chain.
+///
+/// When the relay chain is the only endpoint we trust, this is how we get the runtime of a
+/// parachain: `Paras::CurrentCodeHash(para_id)` gives the hash of the code and `Paras::CodeByHash`
+/// the code itself.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationCode {
+	pub para_id: u32,
+
+	/// The block of the relay chain the code was read at
+	pub block_hash: BlockHash,
+
+	/// The relay chain endpoint that served the code
+	pub endpoint: NodeEndpoint,
+
+	/// The validation code hash, as the relay chain sees it. This is the blake2-256 hash of the code.
+	pub code_hash: String,
+
+	#[serde(skip)]
+	pub code: WasmBytes,
+}
+
+impl ValidationCode {
+	/// Read the current validation code of `para_id` from the relay chain.
+	/// If a cache is provided, the code is only fetched if we do not have it yet.
+	pub fn fetch(relay: &OnchainBlock, para_id: u32, cache: Option<&RuntimeCache>) -> Result<Self> {
+		let (code, endpoint) = relay.with_failover(|client| Self::fetch_with(relay, client, para_id, cache))?;
+		Ok(Self { endpoint, ..code })
+	}
+
+	fn fetch_with(
+		relay: &OnchainBlock,
+		client: &mut RpcClient,
+		para_id: u32,
+		cache: Option<&RuntimeCache>,
+	) -> Result<Self> {
+		let block_hash = relay.block_hash_with(client)?;
+		log::debug!("Fetching the validation code of para {para_id} at {block_hash}");
+
+		let code_hash = client.get_storage(&Self::code_hash_key(para_id), &block_hash)?;
+		let code_hash = Self::decode_code_hash(para_id, &block_hash, code_hash)?;
+
+		let code = match cache.and_then(|c| c.get_blob_path(&code_hash)).and_then(|p| std::fs::read(p).ok()) {
+			Some(code) => code,
+			None => {
+				let code = client.get_storage(&Self::code_key(&code_hash)?, &block_hash)?;
+				Self::store_code(&code_hash, &block_hash, code, cache)?
+			}
+		};
+
+		Ok(Self { para_id, block_hash, endpoint: client.endpoint().clone(), code_hash, code })
+	}
+
+	/// Key of `Paras::CurrentCodeHash(para_id)`
+	fn code_hash_key(para_id: u32) -> Vec<u8> {
+		storage::storage_map_key_twox64_concat("Paras", "CurrentCodeHash", &para_id.to_le_bytes())
+	}
+
+	/// Key of `Paras::CodeByHash(code_hash)`
+	fn code_key(code_hash: &str) -> Result<Vec<u8>> {
+		let code_hash = array_bytes::hex2bytes(code_hash).map_err(|_e| WasmLoaderError::HexDecoding())?;
+		Ok(storage::storage_map_key_identity("Paras", "CodeByHash", &code_hash))
+	}
+
+	fn decode_code_hash(para_id: u32, block_hash: &BlockHash, code_hash: Option<Vec<u8>>) -> Result<String> {
+		match code_hash {
+			Some(hash) if hash.len() == 32 => Ok(array_bytes::bytes2hex("0x", hash)),
+			Some(hash) => Err(WasmLoaderError::RpcUnexpectedResponse(array_bytes::bytes2hex("0x", hash))),
+			None => {
+				Err(WasmLoaderError::StorageNotFound(format!("Paras::CurrentCodeHash({para_id})"), block_hash.into()))
+			}
+		}
+	}
+
+	/// Check the code we got and cache it
+	fn store_code(
+		code_hash: &str,
+		block_hash: &BlockHash,
+		code: Option<WasmBytes>,
+		cache: Option<&RuntimeCache>,
+	) -> Result<WasmBytes> {
+		let code = code.ok_or_else(|| {
+			WasmLoaderError::StorageNotFound(format!("Paras::CodeByHash({code_hash})"), block_hash.into())
+		})?;
+
+		if let Some(Err(e)) = cache.map(|c| c.insert(&RuntimeCache::validation_code_key(code_hash), &code)) {
+			log::warn!("Failed caching the validation code: {e}");
+		}
+		Ok(code)
+	}
+}
+
+#[cfg(feature = "async")]
+impl ValidationCode {
+	/// See [ValidationCode::fetch]
+	pub async fn fetch_async(relay: &OnchainBlock, para_id: u32, cache: Option<&RuntimeCache>) -> Result<Self> {
+		let (code, endpoint) = relay
+			.with_failover_async(|mut client| async move {
+				let block_hash = relay.block_hash_with_async(&mut client).await?;
+				let code_hash = client.get_storage(&Self::code_hash_key(para_id), &block_hash).await?;
+				let code_hash = Self::decode_code_hash(para_id, &block_hash, code_hash)?;
+
+				let code = match cache.and_then(|c| c.get_blob_path(&code_hash)).and_then(|p| std::fs::read(p).ok()) {
+					Some(code) => code,
+					None => {
+						let code = client.get_storage(&Self::code_key(&code_hash)?, &block_hash).await?;
+						Self::store_code(&code_hash, &block_hash, code, cache)?
+					}
+				};
+				Ok(Self { para_id, block_hash, endpoint: client.endpoint().clone(), code_hash, code })
+			})
+			.await?;
+		Ok(Self { endpoint, ..code })
+	}
+}
+
+#[cfg(test)]
+mod tests_validation_code {
+	use super::*;
+	use crate::{rpc::mock::mock_node_with, BlockRef};
+	use serde_json::Value;
+
+	const BLOCK: &str = "0x4d6a0bca208b85d41833a7f35cf73d1ae6974f4bad8ab576e2c3f751d691fe6c";
+	const CODE: &[u8] = &[0x00, 0x61, 0x73, 0x6d, 0x01];
+
+	fn relay() -> OnchainBlock {
+		let code_hash = array_bytes::bytes2hex("0x", sp_crypto_hashing::blake2_256(CODE));
+		let code_hash_key = array_bytes::bytes2hex("0x", ValidationCode::code_hash_key(1000));
+		let code_key = array_bytes::bytes2hex("0x", ValidationCode::code_key(&code_hash).expect("Invalid hash"));
+
+		let endpoint = mock_node_with(move |request: &Value| {
+			let key = request["params"][0].as_str().unwrap_or_default();
+			let result = match request["method"].as_str() {
+				Some("state_getStorage") if key == code_hash_key => format!(r#""{code_hash}""#),
+				Some("state_getStorage") if key == code_key => format!(r#""{}""#, array_bytes::bytes2hex("0x", CODE)),
+				Some("state_getStorage") => "null".into(),
+				_ => return vec![r#"{"jsonrpc":"2.0","id":{id},"error":{"code":-1,"message":"Nope"}}"#.into()],
+			};
+			vec![format!(r#"{{"jsonrpc":"2.0","id":{{id}},"result":{result}}}"#)]
+		});
+		OnchainBlock::from_endpoint(endpoint, Some(BlockRef::Hash(BLOCK.into())))
+	}
+
+	#[test]
+	fn it_fetches_the_validation_code() {
+		let code = ValidationCode::fetch(&relay(), 1000, None).expect("Failed fetching the code");
+		assert_eq!(code.code, CODE);
+		assert_eq!(code.code_hash, array_bytes::bytes2hex("0x", sp_crypto_hashing::blake2_256(CODE)));
+		assert_eq!(code.block_hash, BLOCK);
+	}
+
+	#[test]
+	fn it_fails_for_unknown_paras() {
+		let res = ValidationCode::fetch(&relay(), 2000, None);
+		assert!(matches!(res, Err(WasmLoaderError::StorageNotFound(_, _))));
+	}
+}
@@ -1,13 +1,14 @@
 use sc_executor_common::error::WasmError;
 use substrate_runtime_proposal_hash::error::RuntimePropHashError;
 use thiserror::Error;
+use wasm_loader::WasmLoaderError;
 
 pub type Result<T> = std::result::Result<T, WasmTestbedError>;
 
 #[derive(Error, Debug, Clone)]
 pub enum WasmTestbedError {
-	#[error("Error while loading source: `{0}`")]
-	Loading(String),
+	#[error("Error while loading source `{0}`: {1}")]
+	Loading(String, WasmLoaderError),
 
 	#[error("Error while calling method: `{0}`")]
 	Calling(String),
@@ -18,6 +19,12 @@ pub enum WasmTestbedError {
 	#[error("Hash Error")]
 	HashError(),
 
+	#[error("Invalid storage: {0}")]
+	Storage(String),
+
+	#[error("The runtime was not built with the `try-runtime` feature")]
+	NoTryRuntime,
+
 	#[error("This runtime is not supported")]
 	UnsupportedRuntime,
 }
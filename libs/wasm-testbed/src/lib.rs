@@ -1,23 +1,36 @@
 mod error;
 mod logger_mock;
+mod storage;
+mod try_runtime;
 
 pub use error::{Result, WasmTestbedError};
 use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
 use hex::FromHex;
-use sc_executor::{WasmExecutionMethod, WasmExecutor};
+use sc_executor::{Externalities, HostFunctions, WasmExecutionMethod, WasmExecutor};
 use sc_executor_common::runtime_blob::RuntimeBlob;
-use scale::Decode;
+use scale::{Decode, Encode};
+use sp_core::storage::well_known_keys::CODE;
 use sp_core::Hasher;
 use sp_runtime::traits::BlakeTwo256;
 use sp_version::RuntimeVersion as SubstrateRuntimeVersion;
-use std::{env, fmt};
+use sp_wasm_interface::ExtendedHostFunctions;
+use std::{
+	env, fmt,
+	sync::{Arc, Mutex},
+};
+pub use storage::TestbedStorage;
 use substrate_runtime_proposal_hash::{error::RuntimePropHashError, *};
+use try_runtime::{logging, RuntimeLogsExt};
+pub use try_runtime::{RuntimeLog, TryUpgradeOutcome, UpgradeChecks, Weight};
 use wasm_loader::*;
 
 /// This is a "magic" number signaling that out Wasm is a substrate wasm.
 pub type ReservedMeta = [u8; 4];
 const META: ReservedMeta = [0x6d, 0x65, 0x74, 0x61]; // 1635018093 in decimal, 'atem' as string...
 
+/// The latest metadata version we can decode, runtimes offering it are loaded with this version
+pub const LATEST_METADATA_VERSION: u32 = 16;
+
 /// A Substrate wasm runtime testbed. This is a (very) minimum environment that allows loading and
 /// executing *some* calls into the wasm. Note that any call that most calls that requires storage will fail
 /// (for instance `balances.transfer`, `system.remark`, ...)
@@ -38,8 +51,20 @@ pub struct WasmTestBed {
 	/// Metadata version
 	metadata_version: u8,
 
+	/// All the metadata versions the runtime offers
+	metadata_versions: Vec<u32>,
+
 	/// Core version as reported by the runtime
 	core_version: SubstrateRuntimeVersion,
+
+	/// Hash of the block the runtime was fetched at, if it came from a node
+	block_hash: Option<BlockHash>,
+
+	/// The endpoint that served the runtime, if it came from a node
+	endpoint: Option<NodeEndpoint>,
+
+	/// The validation code hash as seen by the relay chain, if the runtime came from there
+	validation_code_hash: Option<String>,
 }
 
 impl fmt::Debug for WasmTestBed {
@@ -53,13 +78,18 @@ impl fmt::Debug for WasmTestBed {
 
 impl WasmTestBed {
 	pub fn new(source: &Source) -> Result<Self> {
-		log::debug!("Loading testbed with source: {source:?}");
+		Self::new_with_cache(source, None)
+	}
+
+	/// Same as [WasmTestBed::new] but using the given cache for runtimes fetched from a node
+	pub fn new_with_cache(source: &Source, cache: Option<&RuntimeCache>) -> Result<Self> {
+		log::debug!("Loading testbed with source: {source}");
 
-		let loader = WasmLoader::load_from_source(source).map_err(|_| WasmTestbedError::Loading(source.to_string()))?;
+		let loader = WasmLoader::load_from_source_with_cache(source, cache)
+			.map_err(|e| WasmTestbedError::Loading(source.to_string(), e))?;
 		let wasm = loader.uncompressed_bytes().to_vec();
-		let metadata_encoded = Self::call(&wasm, "Metadata_metadata", &[])?;
-		let metadata =
-			<Vec<u8>>::decode(&mut &metadata_encoded[..]).map_err(|_| WasmTestbedError::Decoding(metadata_encoded))?;
+		let mut metadata_versions = Self::get_metadata_versions(&wasm);
+		let metadata = Self::get_metadata(&wasm, &metadata_versions)?;
 
 		log::debug!("decoded_metadata bytes, length: {}", metadata.len());
 		if !WasmTestBed::is_substrate_wasm(&metadata) {
@@ -74,6 +104,9 @@ impl WasmTestBed {
 
 		let core_version = Self::get_core_version(&wasm)?;
 		let metadata_version = Self::get_metadata_version(&metadata);
+		if metadata_versions.is_empty() {
+			metadata_versions.push(metadata_version.into());
+		}
 
 		Ok(Self {
 			wasm,
@@ -81,8 +114,12 @@ impl WasmTestBed {
 			runtime_metadata_prefixed,
 			metadata,
 			metadata_version,
+			metadata_versions,
 			core_version,
 			compression: loader.compression(),
+			block_hash: loader.block_hash().cloned(),
+			endpoint: loader.endpoint().cloned(),
+			validation_code_hash: loader.validation_code_hash().cloned(),
 		})
 	}
 
@@ -129,21 +166,190 @@ impl WasmTestBed {
 	/// Call a function in the provided wasm. Note that we can only call a few limited set of functions
 	/// as we have no blocks, storage, etc...
 	fn call(wasm: &[u8], method: &str, call_data: &[u8]) -> Result<Vec<u8>> {
-		let mut ext = sp_state_machine::BasicExternalities::default();
+		Self::call_with(wasm, &mut sp_state_machine::BasicExternalities::default(), method, call_data)
+	}
 
+	/// Call a function in the provided wasm against the state of `ext`
+	fn call_with(wasm: &[u8], ext: &mut dyn Externalities, method: &str, call_data: &[u8]) -> Result<Vec<u8>> {
+		let runtime_blob = RuntimeBlob::new(wasm)?;
+		Self::executor::<sp_io::SubstrateHostFunctions>()
+			.uncached_call(runtime_blob, ext, true, method, call_data)
+			.map_err(|_| WasmTestbedError::Calling(method.to_string()))
+	}
+
+	fn executor<H: HostFunctions>() -> WasmExecutor<H> {
 		// Substrate V14 requires a heap of ~34.
 		// Polkadot V14 requires a heap of ~20.
-		let executor: WasmExecutor<sp_io::SubstrateHostFunctions> = WasmExecutor::builder()
+		WasmExecutor::builder()
 			.with_execution_method(WasmExecutionMethod::default())
 			.with_offchain_heap_alloc_strategy(sc_executor::HeapAllocStrategy::Dynamic { maximum_pages: Some(64) })
 			.with_max_runtime_instances(8)
 			.with_runtime_cache_size(2)
-			.build();
+			.build()
+	}
 
-		let runtime_blob = RuntimeBlob::new(wasm)?;
-		executor
-			.uncached_call(runtime_blob, &mut ext, true, method, call_data)
-			.map_err(|_| WasmTestbedError::Calling(method.to_string()))
+	/// Call the runtime API `method`, such as `Core_version`, with the SCALE encoded `data` and return
+	/// the SCALE encoded output. Only the APIs that do not need any state can succeed.
+	pub fn call_api(&self, method: &str, data: &[u8]) -> Result<Vec<u8>> {
+		Self::call(&self.wasm, method, data)
+	}
+
+	/// Same as [Self::call_api] but against the given `storage`. The changes made by the call
+	/// are discarded, this is meant for the read-only APIs such as `AccountNonceApi_account_nonce`.
+	pub fn call_api_with_storage(&self, method: &str, data: &[u8], storage: &TestbedStorage) -> Result<Vec<u8>> {
+		let mut ext = storage.externalities();
+		Self::call_with(&self.wasm, &mut ext.ext(), method, data)
+	}
+
+	/// Run the runtime upgrade against `storage`, as `try-runtime` does: `:code` is set to the original,
+	/// possibly compressed, bytes of this runtime before calling `TryRuntime_on_runtime_upgrade`. This requires a runtime built with the `try-runtime`
+	/// feature. The upgrade itself failing is not an error, it is reported in the outcome with the logs.
+	pub fn try_upgrade(&self, storage: &TestbedStorage, checks: UpgradeChecks) -> Result<TryUpgradeOutcome> {
+		const METHOD: &str = "TryRuntime_on_runtime_upgrade";
+
+		if !self.core_version.has_api_with(&sp_core::hashing::blake2_64(b"TryRuntime"), |_| true) {
+			return Err(WasmTestbedError::NoTryRuntime);
+		}
+
+		let logs = Arc::new(Mutex::new(Vec::new()));
+		let mut ext = storage.externalities();
+		ext.insert(CODE.to_vec(), self.bytes.clone());
+		ext.register_extension(RuntimeLogsExt(logs.clone()));
+
+		let runtime_blob = RuntimeBlob::new(&self.wasm)?;
+		let executor = Self::executor::<ExtendedHostFunctions<sp_io::SubstrateHostFunctions, logging::HostFunctions>>();
+		let result = match executor.uncached_call(runtime_blob, &mut ext.ext(), true, METHOD, &checks.encode()) {
+			Ok(encoded) => {
+				Ok(<(Weight, Weight)>::decode(&mut &encoded[..]).map_err(|_| WasmTestbedError::Decoding(encoded))?)
+			}
+			Err(e) => Err(e.to_string()),
+		};
+
+		let logs = logs.lock().expect("Logs are never poisoned").clone();
+		Ok(TryUpgradeOutcome { result, logs })
+	}
+
+	/// The names of the genesis presets of the runtime, using `GenesisBuilder_preset_names`
+	pub fn genesis_preset_names(&self) -> Result<Vec<String>> {
+		let encoded = Self::call(&self.wasm, "GenesisBuilder_preset_names", &[])?;
+		let names = <Vec<Vec<u8>>>::decode(&mut &encoded[..]).map_err(|_| WasmTestbedError::Decoding(encoded))?;
+		Ok(names.into_iter().map(|name| String::from_utf8_lossy(&name).into_owned()).collect())
+	}
+
+	/// Get the genesis preset `name` as JSON, using `GenesisBuilder_get_preset`. Without `name`, this is
+	/// the whole default genesis config, the runtimes only offering `GenesisBuilder_create_default_config`
+	/// are supported in this case. This is `None` if the runtime does not know this preset.
+	pub fn genesis_preset(&self, name: Option<&str>) -> Result<Option<serde_json::Value>> {
+		let id = name.map(|name| name.as_bytes().to_vec());
+		let preset = match Self::call(&self.wasm, "GenesisBuilder_get_preset", &id.encode()) {
+			Ok(encoded) => {
+				<Option<Vec<u8>>>::decode(&mut &encoded[..]).map_err(|_| WasmTestbedError::Decoding(encoded))?
+			}
+			Err(_) if name.is_none() => {
+				let encoded = Self::call(&self.wasm, "GenesisBuilder_create_default_config", &[])?;
+				Some(<Vec<u8>>::decode(&mut &encoded[..]).map_err(|_| WasmTestbedError::Decoding(encoded))?)
+			}
+			Err(e) => return Err(e),
+		};
+
+		preset.map(|json| serde_json::from_slice(&json).map_err(|_| WasmTestbedError::Decoding(json))).transpose()
+	}
+
+	/// Get the metadata of the given `version`, using `Metadata_metadata_at_version`.
+	/// This is `None` if the runtime does not offer this version or the call itself.
+	pub fn metadata_at_version(&self, version: u32) -> Result<Option<RuntimeMetadataPrefixed>> {
+		let Ok(metadata) = Self::get_metadata_at_version(&self.wasm, version) else {
+			return Ok(None);
+		};
+
+		metadata
+			.map(|metadata| {
+				RuntimeMetadataPrefixed::decode(&mut &metadata[..])
+					.map_err(|_| WasmTestbedError::Decoding(metadata[..metadata.len().min(128)].to_vec()))
+			})
+			.transpose()
+	}
+
+	/// Decode the `output` of the runtime API `method` as JSON, using the types of the V15 or V16 metadata.
+	/// This is `None` if the runtime does not describe its APIs or does not know this `method`.
+	pub fn decode_api_output(&self, method: &str, output: &[u8]) -> Result<Option<serde_json::Value>> {
+		let fetched;
+		let metadata = match self.metadata() {
+			metadata @ (RuntimeMetadata::V15(_) | RuntimeMetadata::V16(_)) => metadata,
+			_ => {
+				fetched = self.metadata_at_version(15)?;
+				let Some(RuntimeMetadataPrefixed(_, metadata)) = &fetched else {
+					return Ok(None);
+				};
+				metadata
+			}
+		};
+		Self::decode_api_output_with(metadata, method, output)
+	}
+
+	fn decode_api_output_with(
+		metadata: &RuntimeMetadata,
+		method: &str,
+		output: &[u8],
+	) -> Result<Option<serde_json::Value>> {
+		let Some((api, method)) = method.split_once('_') else {
+			return Ok(None);
+		};
+		let found = match metadata {
+			RuntimeMetadata::V15(metadata) => metadata
+				.apis
+				.iter()
+				.filter(|a| a.name == api)
+				.flat_map(|a| a.methods.iter())
+				.find(|m| m.name == method)
+				.map(|m| (m.output.id, &metadata.types)),
+			RuntimeMetadata::V16(metadata) => metadata
+				.apis
+				.iter()
+				.filter(|a| a.name == api)
+				.flat_map(|a| a.methods.iter())
+				.find(|m| m.name == method)
+				.map(|m| (m.output.id, &metadata.types)),
+			_ => None,
+		};
+		let Some((output_ty, types)) = found else {
+			return Ok(None);
+		};
+
+		let value = scale_value::scale::decode_as_type(&mut &output[..], output_ty, types)
+			.map_err(|_| WasmTestbedError::Decoding(output.to_vec()))?;
+		let json = serde_json::to_value(value).map_err(|_| WasmTestbedError::Decoding(output.to_vec()))?;
+		Ok(Some(json))
+	}
+
+	/// The metadata versions offered by the runtime, this is empty for the runtimes
+	/// that only offer `Metadata_metadata`.
+	fn get_metadata_versions(wasm: &[u8]) -> Vec<u32> {
+		Self::call(wasm, "Metadata_metadata_versions", &[])
+			.ok()
+			.and_then(|encoded| <Vec<u32>>::decode(&mut &encoded[..]).ok())
+			.unwrap_or_default()
+	}
+
+	/// Get the metadata in the latest version we support. `Metadata_metadata` only
+	/// returns V14 at most so we ask for more recent versions when the runtime offers them.
+	/// Only the stable versions are fetched: the unstable metadata, offered as `u32::MAX`,
+	/// changes without notice and cannot be decoded reliably.
+	fn get_metadata(wasm: &[u8], versions: &[u32]) -> Result<Vec<u8>> {
+		let latest = versions.iter().copied().filter(|v| *v > 14 && *v <= LATEST_METADATA_VERSION).max();
+		if let Some(version) = latest {
+			if let Some(metadata) = Self::get_metadata_at_version(wasm, version)? {
+				return Ok(metadata);
+			}
+		}
+
+		let encoded = Self::call(wasm, "Metadata_metadata", &[])?;
+		<Vec<u8>>::decode(&mut &encoded[..]).map_err(|_| WasmTestbedError::Decoding(encoded))
+	}
+
+	fn get_metadata_at_version(wasm: &[u8], version: u32) -> Result<Option<Vec<u8>>> {
+		let encoded = Self::call(wasm, "Metadata_metadata_at_version", &version.encode())?;
+		<Option<Vec<u8>>>::decode(&mut &encoded[..]).map_err(|_| WasmTestbedError::Decoding(encoded))
 	}
 
 	pub fn get_core_version(wasm: &[u8]) -> Result<SubstrateRuntimeVersion> {
@@ -163,6 +369,11 @@ impl WasmTestBed {
 		&self.metadata_version
 	}
 
+	/// Get all the metadata versions the runtime offers
+	pub fn metadata_versions(&self) -> &[u32] {
+		&self.metadata_versions
+	}
+
 	/// Get the size of the runtime
 	pub fn size(&self) -> usize {
 		self.bytes.len()
@@ -183,6 +394,24 @@ impl WasmTestBed {
 		self.core_version.clone()
 	}
 
+	/// Get the hash of the block the runtime was fetched at.
+	/// This is `None` unless the runtime was loaded from a node.
+	pub fn block_hash(&self) -> Option<&BlockHash> {
+		self.block_hash.as_ref()
+	}
+
+	/// Get the endpoint that served the runtime.
+	/// This is `None` unless the runtime was loaded from a node.
+	pub fn endpoint(&self) -> Option<&NodeEndpoint> {
+		self.endpoint.as_ref()
+	}
+
+	/// Get the validation code hash of the runtime, as the relay chain sees it.
+	/// This is `None` unless the runtime was loaded from the relay chain of a parachain.
+	pub fn validation_code_hash(&self) -> Option<&String> {
+		self.validation_code_hash.as_ref()
+	}
+
 	/// Compute the proposal hash of the runtime
 	pub fn proposal_hash(&self) -> Result<String> {
 		let result: SrhResult = get_result(substrate_runtime_proposal_hash::PREFIX_SYSTEM_SETCODE, &self.bytes)?;
@@ -242,6 +471,12 @@ mod tests {
 	const RUNTIME_V12: &str = "../../data/kusama/V12/2030.wasm";
 	const RUNTIME_V13: &str = "../../data/kusama/V13/9090.wasm";
 	const RUNTIME_V14: &str = "../../data/polkadot/V14/9100.wasm";
+	const RUNTIME_V15: &str = "../../data/polkadot/V15/1002000.wasm";
+	const RUNTIME_PRESETS: &str = "../../data/westend/V15/1014000.wasm";
+	const RUNTIME_GENESIS_V1: &str = "../../data/westend/V15/1007000.wasm";
+	// Those cannot be downloaded, see `scripts/fetch-wasm.sh` to build and create them
+	const RUNTIME_TRY_RUNTIME: &str = "../../data/westend/V15/1014000-try-runtime.wasm";
+	const SNAPSHOT_1013000: &str = "../../data/westend/westend-1013000.snap";
 
 	#[cfg(test)]
 	mod common {
@@ -253,6 +488,159 @@ mod tests {
 		}
 	}
 
+	#[cfg(test)]
+	mod api {
+		use super::*;
+		use frame_metadata::{v15, v16};
+		use scale_info::{meta_type, TypeInfo};
+		use substrate_differ::differs::test_runtimes::{metadata_v15, metadata_v16};
+
+		#[derive(TypeInfo, Encode)]
+		struct Version {
+			spec_version: u32,
+			authoring: bool,
+		}
+
+		fn metadata() -> RuntimeMetadata {
+			let version = v15::RuntimeApiMethodMetadata {
+				name: "version",
+				inputs: vec![],
+				output: meta_type::<Version>(),
+				docs: vec![],
+			};
+			let core = v15::RuntimeApiMetadata { name: "Core", methods: vec![version], docs: vec![] };
+			RuntimeMetadata::V15(metadata_v15(vec![core]))
+		}
+
+		fn metadata_with_v16() -> RuntimeMetadata {
+			let version = v16::RuntimeApiMethodMetadata {
+				name: "version",
+				inputs: vec![],
+				output: meta_type::<Version>(),
+				docs: vec![],
+				deprecation_info: v16::DeprecationStatus::NotDeprecated,
+			};
+			let core = v16::RuntimeApiMetadata {
+				name: "Core",
+				methods: vec![version],
+				docs: vec![],
+				deprecation_info: v16::DeprecationStatus::NotDeprecated,
+				version: 5,
+			};
+			RuntimeMetadata::V16(metadata_v16(&[], false, vec![core]))
+		}
+
+		#[test]
+		fn it_decodes_api_outputs() {
+			let output = Version { spec_version: 42, authoring: true }.encode();
+			let decoded = WasmTestBed::decode_api_output_with(&metadata(), "Core_version", &output)
+				.expect("Failed decoding output")
+				.expect("Method not found");
+			assert_eq!(decoded, serde_json::json!({ "spec_version": 42, "authoring": true }));
+		}
+
+		#[test]
+		fn it_decodes_api_outputs_with_v16() {
+			let output = Version { spec_version: 42, authoring: false }.encode();
+			let decoded = WasmTestBed::decode_api_output_with(&metadata_with_v16(), "Core_version", &output)
+				.expect("Failed decoding output")
+				.expect("Method not found");
+			assert_eq!(decoded, serde_json::json!({ "spec_version": 42, "authoring": false }));
+		}
+
+		#[test]
+		fn it_skips_unknown_apis() {
+			let decoded = WasmTestBed::decode_api_output_with(&metadata(), "Core_initialize_block", &[])
+				.expect("Failed decoding");
+			assert!(decoded.is_none());
+			assert!(WasmTestBed::decode_api_output_with(&metadata(), "Foo_version", &[]).expect("Failed").is_none());
+		}
+
+		#[test]
+		fn it_fails_on_truncated_outputs() {
+			assert!(WasmTestBed::decode_api_output_with(&metadata(), "Core_version", &[42]).is_err());
+		}
+	}
+
+	#[cfg(test)]
+	mod runtime_apis {
+		use super::*;
+
+		#[test]
+		#[ignore = "local data"]
+		fn it_calls_and_decodes_core_version() {
+			let runtime = WasmTestBed::new(&Source::File(PathBuf::from(RUNTIME_V15))).expect("Failed loading runtime");
+			let output = runtime.call_api("Core_version", &[]).expect("Failed calling Core_version");
+			assert_eq!(SubstrateRuntimeVersion::decode(&mut &output[..]).ok(), Some(runtime.core_version()));
+
+			let decoded = runtime
+				.decode_api_output("Core_version", &output)
+				.expect("Failed decoding output")
+				.expect("Core_version is not described");
+			assert_eq!(decoded["spec_name"], "polkadot");
+			assert_eq!(decoded["spec_version"], runtime.core_version().spec_version);
+		}
+	}
+
+	#[cfg(test)]
+	mod genesis {
+		use super::*;
+
+		#[test]
+		#[ignore = "local data"]
+		fn it_lists_and_gets_the_presets() {
+			let runtime =
+				WasmTestBed::new(&Source::File(PathBuf::from(RUNTIME_PRESETS))).expect("Failed loading runtime");
+			let names = runtime.genesis_preset_names().expect("Failed listing the presets");
+			assert!(names.iter().any(|name| name == "development"));
+
+			let preset = runtime.genesis_preset(Some("development")).expect("Failed getting preset");
+			assert!(preset.expect("Unknown preset").is_object());
+			assert_eq!(runtime.genesis_preset(Some("foobar")).expect("Failed getting preset"), None);
+			assert!(runtime.genesis_preset(None).expect("Failed getting config").expect("No config").is_object());
+		}
+
+		#[test]
+		#[ignore = "local data"]
+		fn it_falls_back_to_the_default_config() {
+			// This runtime only offers the first version of `GenesisBuilder`, without presets
+			let runtime =
+				WasmTestBed::new(&Source::File(PathBuf::from(RUNTIME_GENESIS_V1))).expect("Failed loading runtime");
+			assert!(runtime.genesis_preset_names().is_err());
+			assert!(runtime.genesis_preset(Some("development")).is_err());
+
+			let config = runtime.genesis_preset(None).expect("Failed getting config").expect("No config");
+			assert!(config["system"].is_object());
+		}
+	}
+
+	#[cfg(test)]
+	mod try_upgrade {
+		use super::*;
+		use std::path::Path;
+
+		#[test]
+		#[ignore = "local data"]
+		fn it_tries_the_upgrade_against_a_snapshot() {
+			let runtime =
+				WasmTestBed::new(&Source::File(PathBuf::from(RUNTIME_TRY_RUNTIME))).expect("Failed loading runtime");
+			// The snapshot holds the previous runtime, the upgrade must run against ours
+			let snapshot = Snapshot::load(Path::new(SNAPSHOT_1013000)).expect("Failed loading snapshot");
+			assert_ne!(snapshot.code().expect("No runtime in the snapshot"), *runtime.raw_bytes());
+
+			let storage = TestbedStorage::load(Path::new(SNAPSHOT_1013000)).expect("Failed loading storage");
+			let outcome = runtime.try_upgrade(&storage, UpgradeChecks::PreAndPost).expect("Failed trying the upgrade");
+
+			let (used, max) = outcome.result.expect("The upgrade failed");
+			assert!(used.ref_time > 0);
+			assert!(used.ref_time <= max.ref_time);
+
+			// The logs of the runtime are collected by our logging host functions, up to `info`
+			assert!(outcome.logs.iter().any(|log| log.target == "runtime::frame-support"));
+			assert!(outcome.logs.iter().all(|log| log.level <= log::Level::Info));
+		}
+	}
+
 	#[cfg(test)]
 	mod runtime_versions {
 		use super::*;
@@ -285,6 +673,14 @@ mod tests {
 			assert!(runtime.metadata_version == 14);
 			assert!(runtime.is_supported());
 		}
+
+		#[test]
+		#[ignore = "local data"]
+		fn it_loads_from_bytes() {
+			let bytes = std::fs::read(RUNTIME_V14).expect("Failed reading runtime");
+			let runtime = WasmTestBed::new(&Source::Bytes(bytes)).expect("Failed loading runtime");
+			assert!(runtime.metadata_version == 14);
+		}
 	}
 
 	#[cfg(test)]
@@ -313,8 +709,8 @@ mod tests {
 			assert!(runtime.is_supported());
 
 			let v = &runtime.core_version;
-			assert!(v.spec_name == String::from("kusama"));
-			assert!(v.impl_name == String::from("parity-kusama"));
+			assert!(v.spec_name == "kusama");
+			assert!(v.impl_name == "parity-kusama");
 			assert!(v.authoring_version == 2);
 			assert!(v.spec_version == 1062);
 			assert!(v.impl_version == 0);
@@ -341,8 +737,8 @@ mod tests {
 			assert!(runtime.is_supported());
 
 			let v = &runtime.core_version;
-			assert!(v.spec_name == String::from("kusama"));
-			assert!(v.impl_name == String::from("parity-kusama"));
+			assert!(v.spec_name == "kusama");
+			assert!(v.impl_name == "parity-kusama");
 			assert!(v.authoring_version == 2);
 			assert!(v.spec_version == 2030);
 			assert!(v.impl_version == 0);
@@ -4,6 +4,7 @@ use sp_wasm_interface::{Function, FunctionContext, Signature, Value};
 /// Newer runtimes require the implementation of a HostFunction called
 /// `ext_logging_max_level_version_1`. This mock provides a version doing
 /// nothing but allowing to decode newer runtimes.
+#[allow(dead_code)]
 pub struct LoggerMock;
 
 impl Function for LoggerMock {
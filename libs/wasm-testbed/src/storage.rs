@@ -0,0 +1,158 @@
+use crate::error::{Result, WasmTestbedError};
+use serde_json::{Map, Value};
+use sp_core::{
+	storage::{ChildInfo, StateVersion, Storage, StorageChild, StorageMap},
+	H256,
+};
+use sp_runtime::traits::BlakeTwo256;
+use sp_state_machine::TestExternalities;
+use std::{fs, path::Path};
+use wasm_loader::{RawStorage, Snapshot};
+
+/// The storage the runtime APIs are called against. Without it, the runtime only sees an
+/// empty state and the APIs reading it, such as `AccountNonceApi`, cannot succeed.
+#[derive(Debug, Clone)]
+pub enum TestbedStorage {
+	/// Plain key/values, as found in a JSON map or a raw chain spec
+	KeyValues(Storage),
+
+	/// The trie nodes of a state snapshot, as created by `try-runtime`
+	Snapshot { raw_storage: RawStorage, storage_root: H256, state_version: StateVersion },
+}
+
+impl TestbedStorage {
+	/// Load the storage from a file which is either a JSON map of hex keys to hex values,
+	/// a raw chain spec or a `try-runtime` snapshot.
+	pub fn load(path: &Path) -> Result<Self> {
+		let bytes = fs::read(path).map_err(|e| WasmTestbedError::Storage(format!("{}: {e}", path.display())))?;
+
+		match serde_json::from_slice::<Value>(&bytes) {
+			Ok(json) => Self::from_json(&json),
+			Err(_) => {
+				let snapshot = Snapshot::decode(&bytes).map_err(|e| WasmTestbedError::Storage(e.to_string()))?;
+				Self::from_snapshot(snapshot)
+			}
+		}
+	}
+
+	/// Read the storage from a JSON map of hex keys to hex values or from a raw chain spec.
+	pub fn from_json(json: &Value) -> Result<Self> {
+		let Some(genesis) = json.get("genesis") else {
+			let top = json.as_object().ok_or_else(|| WasmTestbedError::Storage("Expected a JSON map".into()))?;
+			return Ok(Self::KeyValues(Storage { top: Self::storage_map(top)?, ..Default::default() }));
+		};
+
+		let raw = genesis
+			.get("raw")
+			.ok_or_else(|| WasmTestbedError::Storage("The chain spec is not a raw chain spec".into()))?;
+		let top = raw
+			.get("top")
+			.and_then(Value::as_object)
+			.ok_or_else(|| WasmTestbedError::Storage("The raw chain spec has no `top` storage".into()))?;
+
+		let mut storage = Storage { top: Self::storage_map(top)?, ..Default::default() };
+		if let Some(children) = raw.get("childrenDefault").and_then(Value::as_object) {
+			for (key, data) in children {
+				let key = Self::decode_hex(key)?;
+				let data = data
+					.as_object()
+					.ok_or_else(|| WasmTestbedError::Storage("Expected a JSON map of child storage".into()))?;
+				let child = StorageChild { data: Self::storage_map(data)?, child_info: ChildInfo::new_default(&key) };
+				storage.children_default.insert(key, child);
+			}
+		}
+
+		Ok(Self::KeyValues(storage))
+	}
+
+	/// Use the storage of a state snapshot as created by `try-runtime`
+	pub fn from_snapshot(snapshot: Snapshot) -> Result<Self> {
+		let (raw_storage, storage_root, state_version) = snapshot.into_parts();
+		let state_version = StateVersion::try_from(state_version)
+			.map_err(|_| WasmTestbedError::Storage(format!("Unsupported state version {state_version}")))?;
+		Ok(Self::Snapshot { raw_storage, storage_root, state_version })
+	}
+
+	/// Build fresh externalities holding this storage
+	pub fn externalities(&self) -> TestExternalities<BlakeTwo256> {
+		match self {
+			Self::KeyValues(storage) => TestExternalities::new(storage.clone()),
+			Self::Snapshot { raw_storage, storage_root, state_version } => {
+				TestExternalities::from_raw_snapshot(raw_storage.clone(), *storage_root, *state_version)
+			}
+		}
+	}
+
+	fn storage_map(map: &Map<String, Value>) -> Result<StorageMap> {
+		map.iter()
+			.map(|(key, value)| {
+				let value = value
+					.as_str()
+					.ok_or_else(|| WasmTestbedError::Storage(format!("Expected a hex value for {key}")))?;
+				Ok((Self::decode_hex(key)?, Self::decode_hex(value)?))
+			})
+			.collect()
+	}
+
+	fn decode_hex(s: &str) -> Result<Vec<u8>> {
+		hex::decode(s.trim_start_matches("0x")).map_err(|_| WasmTestbedError::Storage(format!("Invalid hex: {s}")))
+	}
+}
+
+#[cfg(test)]
+mod tests_storage {
+	use super::*;
+	use scale::{Compact, Encode};
+	use serde_json::json;
+	use sp_core::traits::Externalities;
+
+	fn read(storage: &TestbedStorage, key: &[u8]) -> Option<Vec<u8>> {
+		storage.externalities().ext().storage(key)
+	}
+
+	#[test]
+	fn it_reads_a_json_map() {
+		let storage = TestbedStorage::from_json(&json!({ "0x0102": "0x03" })).expect("Valid map");
+		assert_eq!(read(&storage, &[1, 2]), Some(vec![3]));
+		assert_eq!(read(&storage, &[1]), None);
+	}
+
+	#[test]
+	fn it_reads_a_raw_chain_spec() {
+		let spec = json!({
+			"name": "Test",
+			"genesis": { "raw": {
+				"top": { "0x01": "0x02" },
+				"childrenDefault": { "0x0a": { "0x0b": "0x0c" } }
+			}}
+		});
+		let storage = TestbedStorage::from_json(&spec).expect("Valid chain spec");
+		assert_eq!(read(&storage, &[1]), Some(vec![2]));
+		let child = ChildInfo::new_default(&[0x0a]);
+		assert_eq!(storage.externalities().ext().child_storage(&child, &[0x0b]), Some(vec![0x0c]));
+	}
+
+	#[test]
+	fn it_rejects_invalid_json() {
+		assert!(TestbedStorage::from_json(&json!({ "0x01": 2 })).is_err());
+		assert!(TestbedStorage::from_json(&json!({ "zz": "0x01" })).is_err());
+		assert!(TestbedStorage::from_json(&json!({ "genesis": { "runtimeGenesis": {} } })).is_err());
+	}
+
+	#[test]
+	fn it_reads_a_snapshot() {
+		let mut ext = TestExternalities::<BlakeTwo256>::default();
+		ext.insert(vec![1, 2], vec![3]);
+		ext.commit_all().expect("Committed");
+		let (raw_storage, storage_root) = ext.into_raw_snapshot();
+
+		let mut bytes = Compact(4u16).encode();
+		bytes.extend(StateVersion::V1.encode());
+		bytes.extend(raw_storage.encode());
+		bytes.extend(storage_root.encode());
+
+		let snapshot = Snapshot::decode(&bytes).expect("Valid snapshot");
+		let storage = TestbedStorage::from_snapshot(snapshot).expect("Valid snapshot");
+		assert_eq!(read(&storage, &[1, 2]), Some(vec![3]));
+	}
+}
@@ -0,0 +1,141 @@
+use scale::{Compact, Decode, Encode, Input};
+use sp_core::{LogLevel, LogLevelFilter};
+use sp_externalities::ExternalitiesExt;
+use sp_runtime_interface::runtime_interface;
+use std::{
+	fmt,
+	str::FromStr,
+	sync::{Arc, Mutex},
+};
+
+/// The checks run by `TryRuntime_on_runtime_upgrade`, encoded as the `UpgradeCheckSelect` of `frame-try-runtime`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpgradeChecks {
+	/// Only run the migrations
+	None,
+	/// Run the `pre_upgrade` and `post_upgrade` hooks as well as the `try_state` hooks
+	#[default]
+	All,
+	/// Run the `pre_upgrade` and `post_upgrade` hooks
+	PreAndPost,
+	/// Run the `try_state` hooks
+	TryState,
+}
+
+impl Encode for UpgradeChecks {
+	fn encode(&self) -> Vec<u8> {
+		let index: u8 = match self {
+			Self::None => 0,
+			Self::All => 1,
+			Self::PreAndPost => 2,
+			Self::TryState => 3,
+		};
+		vec![index]
+	}
+}
+
+impl FromStr for UpgradeChecks {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"none" => Ok(Self::None),
+			"all" => Ok(Self::All),
+			"pre-and-post" => Ok(Self::PreAndPost),
+			"try-state" => Ok(Self::TryState),
+			_ => Err(format!("Cannot convert '{s}' to known checks: none, all, pre-and-post or try-state")),
+		}
+	}
+}
+
+/// A weight, as returned by the runtime since the weights have two dimensions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Weight {
+	pub ref_time: u64,
+	pub proof_size: u64,
+}
+
+impl Decode for Weight {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, scale::Error> {
+		let Compact(ref_time) = Compact::<u64>::decode(input)?;
+		let Compact(proof_size) = Compact::<u64>::decode(input)?;
+		Ok(Self { ref_time, proof_size })
+	}
+}
+
+impl fmt::Display for Weight {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "ref_time: {}, proof_size: {}", self.ref_time, self.proof_size)
+	}
+}
+
+/// A log line emitted by the runtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeLog {
+	pub level: log::Level,
+	pub target: String,
+	pub message: String,
+}
+
+impl fmt::Display for RuntimeLog {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:<5} {}: {}", self.level, self.target, self.message)
+	}
+}
+
+/// What came out of `TryRuntime_on_runtime_upgrade`
+#[derive(Debug, Clone)]
+pub struct TryUpgradeOutcome {
+	/// The weight used by the upgrade and the maximum weight of a block,
+	/// or the reason why the upgrade or its checks failed
+	pub result: Result<(Weight, Weight), String>,
+
+	/// The logs of the runtime, up to the `info` level
+	pub logs: Vec<RuntimeLog>,
+}
+
+sp_externalities::decl_extension! {
+	/// Collects the logs of the runtime instead of forwarding them to the logger of the host
+	pub(crate) struct RuntimeLogsExt(Arc<Mutex<Vec<RuntimeLog>>>);
+}
+
+/// Replaces the logging host functions of `sp-io` to collect the logs into [RuntimeLogsExt].
+/// Only the host functions are used, the functions to call them from a runtime are not.
+#[runtime_interface]
+#[allow(dead_code)]
+pub(crate) trait Logging {
+	fn log(&mut self, level: LogLevel, target: &str, message: &[u8]) {
+		if let Some(logs) = self.extension::<RuntimeLogsExt>() {
+			let log = RuntimeLog {
+				level: level.into(),
+				target: target.to_string(),
+				message: String::from_utf8_lossy(message).into_owned(),
+			};
+			logs.lock().expect("Logs are never poisoned").push(log);
+		}
+	}
+
+	fn max_level() -> LogLevelFilter {
+		log::LevelFilter::Info.into()
+	}
+}
+
+#[cfg(test)]
+mod tests_try_runtime {
+	use super::*;
+
+	#[test]
+	fn it_encodes_the_checks() {
+		assert_eq!(UpgradeChecks::from_str("pre-and-post").expect("Valid checks").encode(), vec![2]);
+		assert_eq!(UpgradeChecks::default().encode(), true.encode());
+		assert!(UpgradeChecks::from_str("some").is_err());
+	}
+
+	#[test]
+	fn it_decodes_weights() {
+		let encoded = (Compact(1_000u64), Compact(2u64), Compact(u64::MAX), Compact(5u64)).encode();
+		let (used, max) = <(Weight, Weight)>::decode(&mut &encoded[..]).expect("Valid weights");
+		assert_eq!(used, Weight { ref_time: 1_000, proof_size: 2 });
+		assert_eq!(max, Weight { ref_time: u64::MAX, proof_size: 5 });
+	}
+}
@@ -7,9 +7,9 @@
 # export POLKADOT=http://$SRV:9934
 
 mkdir -p \
-    data/polkadot/V12 data/polkadot/V13 data/polkadot/V14 \
+    data/polkadot/V12 data/polkadot/V13 data/polkadot/V14 data/polkadot/V15 \
     data/kusama/V12 data/kusama/V13 data/kusama/V14 \
-    data/westend/V12 data/westend/V13 data/westend/V14
+    data/westend/V12 data/westend/V13 data/westend/V14 data/westend/V15
 
 # Kusama
 # cargo run --profile production -p subwasm -- get --url $KUSAMA --block 0x476801cf6aac6ed4f8d782c96b450ba9444b545459fe0a30e1cea99c4dba4420 --output kusama/kusama-1050.wasm
@@ -98,6 +98,35 @@ for chain in polkadot kusama; do
     done
 done
 
+# Since 1.0.0, the runtimes are released as spec versions such as 1002000 for 1.2.0
+function spec_to_semver {
+    printf "%d.%d.%d\n" $(( $1 / 1000000 )) $(( $1 / 1000 % 1000 )) $(( $1 % 1000 ))
+}
+
+for version in 1002000; do
+    echo polkadot $version
+    semver=$(spec_to_semver $version )
+    url=https://github.com/polkadot-fellows/runtimes/releases/download/v$semver/polkadot_runtime-v$version.compact.compressed.wasm
+    # echo $url
+    wget $url -O data/polkadot/V15/$version.wasm
+done
+
+for version in 1007000 1014000; do
+    echo westend $version
+    semver=$(spec_to_semver $version )
+    url=https://github.com/paritytech/polkadot-sdk/releases/download/polkadot-v$semver/westend_runtime-v$version.compact.compressed.wasm
+    # echo $url
+    wget $url -O data/westend/V15/$version.wasm
+done
+
+# The try-runtime upgrade tests need a westend runtime built with the `try-runtime` feature and a snapshot
+# of the state of westend while it was running the previous runtime, those cannot be downloaded:
+# git clone --branch polkadot-v1.14.0 https://github.com/paritytech/polkadot-sdk && cd polkadot-sdk
+# cargo build --release -p westend-runtime --features try-runtime
+# cp target/release/wbuild/westend-runtime/westend_runtime.compact.compressed.wasm ../data/westend/V15/1014000-try-runtime.wasm
+# cargo install --git https://github.com/paritytech/try-runtime-cli --locked
+# try-runtime create-snapshot --uri <westend archive node> --at <hash of a block running 1013000> ../data/westend/westend-1013000.snap
+
 wget https://github.com/paritytech/polkadot/releases/download/v0.8.30/kusama_runtime-v2030.compact.wasm -O data/kusama/V12/2030.wasm
 wget https://github.com/paritytech/polkadot/releases/download/v0.8.30/polkadot_runtime-v30.compact.wasm -O data/polkadot/V12/2030.wasm
 